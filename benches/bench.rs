@@ -51,5 +51,46 @@ pub fn bench_points(c: &mut Criterion) {
     });
 }
 
-criterion_group! {name=benches1; config = Criterion::default().sample_size(40); targets=bench_segments,bench_points}
+#[cfg(test)]
+/// Rebuilds a small diagram many times, with a new builder and diagram each time
+pub fn bench_rebuild(c: &mut Criterion) {
+    let input = include_str!("input_data/voronoi_point.txt");
+    let (points, _) = BV::read_boost_input_buffer::<I, _>(BufReader::new(Cursor::new(input)))
+        .expect("bench_rebuild");
+    let points = &points[..100];
+    c.bench_function("bench_rebuild", |b| {
+        b.iter(|| {
+            for _ in 0..100 {
+                let _output = Builder::<I, F>::default()
+                    .with_vertices(points.iter())
+                    .expect("bench_rebuild")
+                    .build()
+                    .expect("bench_rebuild");
+            }
+        })
+    });
+}
+
+#[cfg(test)]
+/// Same as `bench_rebuild` but the builder and the diagram are reused
+pub fn bench_rebuild_into(c: &mut Criterion) {
+    let input = include_str!("input_data/voronoi_point.txt");
+    let (points, _) = BV::read_boost_input_buffer::<I, _>(BufReader::new(Cursor::new(input)))
+        .expect("bench_rebuild_into");
+    let points = &points[..100];
+    let mut builder = Builder::<I, F>::default();
+    let mut output = Diagram::<F>::default();
+    c.bench_function("bench_rebuild_into", |b| {
+        b.iter(|| {
+            for _ in 0..100 {
+                builder = std::mem::take(&mut builder)
+                    .with_vertices(points.iter())
+                    .expect("bench_rebuild_into");
+                builder.build_into(&mut output).expect("bench_rebuild_into");
+            }
+        })
+    });
+}
+
+criterion_group! {name=benches1; config = Criterion::default().sample_size(40); targets=bench_segments,bench_points,bench_rebuild,bench_rebuild_into}
 criterion_main!(benches1);
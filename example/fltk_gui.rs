@@ -4,7 +4,6 @@ use BV::prelude::*;
 use fltk::{app, button, dialog, draw, enums, frame, group, menu, prelude::*, window};
 use geo::prelude::Intersects;
 use geo_cr as geo;
-use ordered_float::{FloatCore, OrderedFloat};
 use std::cell::{RefCell, RefMut};
 use std::rc::Rc;
 
@@ -711,6 +710,26 @@ impl<I: InputType, F: OutputType + ordered_float::FloatCore> VoronoiVisualizer<I
         let draw_curved_as_line = config.draw_flag.contains(DrawFilterFlag::CURVE_LINE);
         let draw_infinite_edges = config.draw_flag.contains(DrawFilterFlag::INFINITE);
 
+        // infinite edges are clipped to the screen, in unrounded voronoi coordinates
+        let clipper = {
+            let mut aabb = BV::Aabb2::<F>::default();
+            for corner in [self.screen_aabb.get_low(), self.screen_aabb.get_high()]
+                .into_iter()
+                .flatten()
+            {
+                let corner = affine.reverse_transform_f(corner);
+                aabb.update_vertex(corner[0], corner[1]);
+            }
+            BV::ConvexRegion::from_aabb(&aabb).map(|region| {
+                BV::DiagramClipper::new(
+                    &self.diagram,
+                    &self.point_data_,
+                    &self.segment_data_,
+                    region,
+                )
+            })
+        };
+
         let mut already_drawn = {
             let l = self.diagram.edges().len();
             let mut vb = vob::Vob::<u32>::new_with_storage_type(l);
@@ -763,9 +782,18 @@ impl<I: InputType, F: OutputType + ordered_float::FloatCore> VoronoiVisualizer<I
             // the coordinates in samples must be 'screen' coordinates, i.e. affine transformed
             let mut samples = Vec::<[F; 2]>::new();
             if self.diagram.edge_is_infinite(edge_id)? {
-                let a = self.clip_infinite_edge(&affine, edge_id, &mut samples);
-                if let Err(err) = a {
-                    println!("Ignoring error : {:?}", err);
+                // infinite edges are straight, so there is at most one piece inside the screen
+                match clipper
+                    .as_ref()
+                    .map(|clipper| clipper.clip_edge(edge_id, F::one()))
+                {
+                    Ok(Ok(pieces)) => {
+                        if let Some(piece) = pieces.first() {
+                            samples.extend(piece.iter().map(|p| affine.transform(p[0], p[1])));
+                        }
+                    }
+                    Ok(Err(err)) => println!("Ignoring error : {:?}", err),
+                    Err(err) => println!("Ignoring error : {:?}", err),
                 }
             } else {
                 // edge is finite, so vertex0 & vertex1 must exists -> unwrap is safe
@@ -823,94 +851,6 @@ impl<I: InputType, F: OutputType + ordered_float::FloatCore> VoronoiVisualizer<I
         Ok(())
     }
 
-    fn clip_infinite_edge(
-        &self,
-        affine: &BV::SimpleAffine<F>,
-        edge_id: BV::EdgeIndex,
-        clipped_edge: &mut Vec<[F; 2]>,
-    ) -> Result<(), BvError> {
-        let edge = self.diagram.get_edge(edge_id)?;
-        //const cell_type& cell1 = *edge.cell();
-        let cell1_id = self.diagram.edge_get_cell(edge_id)?;
-        let cell1 = self.diagram.get_cell(cell1_id)?.get();
-        //const cell_type& cell2 = *edge.twin()->cell();
-        let cell2_id = {
-            let twin = self.diagram.edge_get_twin(edge_id)?;
-            self.diagram.edge_get_cell(twin)?
-        };
-        let cell2 = self.diagram.get_cell(cell2_id)?.get();
-
-        let mut origin = [F::default(), F::default()];
-        let mut direction = [F::default(), F::default()];
-        // Infinite edges could not be created by two segment sites.
-        if cell1.contains_point() && cell2.contains_point() {
-            let p1 = self.retrieve_point(cell1_id)?;
-            let p2 = self.retrieve_point(cell2_id)?;
-            origin[0] = (cast::<I, F>(p1.x) + cast::<I, F>(p2.x)) * cast::<f64, F>(0.5);
-            origin[1] = (cast::<I, F>(p1.y) + cast::<I, F>(p2.y)) * cast::<f64, F>(0.5);
-            direction[0] = cast::<I, F>(p1.y) - cast::<I, F>(p2.y);
-            direction[1] = cast::<I, F>(p2.x) - cast::<I, F>(p1.x);
-        } else {
-            origin = if cell1.contains_segment() {
-                let p = self.retrieve_point(cell2_id)?;
-                [cast::<I, F>(p.x), cast::<I, F>(p.y)]
-            } else {
-                let p = self.retrieve_point(cell1_id)?;
-                [cast::<I, F>(p.x), cast::<I, F>(p.y)]
-            };
-            let segment = if cell1.contains_segment() {
-                self.retrieve_segment(cell1_id)?
-            } else {
-                self.retrieve_segment(cell2_id)?
-            };
-            let dx = segment.end.x - segment.start.x;
-            let dy = segment.end.y - segment.start.y;
-            if ([cast::<I, F>(segment.start.x), cast::<I, F>(segment.start.y)] == origin)
-                ^ cell1.contains_point()
-            {
-                direction[0] = cast::<I, F>(dy);
-                direction[1] = cast::<I, F>(-dx);
-            } else {
-                direction[0] = cast::<I, F>(-dy);
-                direction[1] = cast::<I, F>(dx);
-            }
-        }
-
-        let side =
-            cast::<I, F>(affine.reverse_transform_x(self.screen_aabb.get_high().unwrap()[0])?)
-                - cast::<I, F>(affine.reverse_transform_x(self.screen_aabb.get_low().unwrap()[0])?);
-        // absolute value is taken in case the affine transform flips one coordinate
-        let side = FloatCore::abs(side);
-        let coefficient = side / Self::max_f(FloatCore::abs(direction[0]), FloatCore::abs(direction[1]));
-
-        if let Some(vertex0) = edge.get().vertex0() {
-            let vertex0 = self.diagram.vertex_get(vertex0)?.get();
-            clipped_edge.push([
-                affine.transform_x(vertex0.x()),
-                affine.transform_y(vertex0.y()),
-            ]);
-        } else {
-            clipped_edge.push([
-                affine.transform_x(origin[0] - direction[0] * coefficient),
-                affine.transform_y(origin[1] - direction[1] * coefficient),
-            ]);
-        }
-
-        if let Some(vertex1) = self.diagram.edge_get_vertex1(edge_id)? {
-            let vertex1 = self.diagram.vertex_get(vertex1)?.get();
-            clipped_edge.push([
-                affine.transform_x(vertex1.x()),
-                affine.transform_y(vertex1.y()),
-            ]);
-        } else {
-            clipped_edge.push([
-                affine.transform_x(origin[0] + direction[0] * coefficient),
-                affine.transform_y(origin[1] + direction[1] * coefficient),
-            ]);
-        }
-        Ok(())
-    }
-
     /// Important: sampled_edge should contain both edge endpoints initially.
     /// sampled_edge should be 'screen' coordinates, i.e. affine transformed from voronoi output
     fn sample_curved_edge(
@@ -1062,9 +1002,4 @@ impl<I: InputType, F: OutputType + ordered_float::FloatCore> VoronoiVisualizer<I
         };
         geo::Line::<f64>::new(ps, pe)
     }
-
-    #[inline(always)]
-    fn max_f(a: F, b: F) -> F {
-        FloatCore::max(OrderedFloat(a), OrderedFloat(b)).into_inner()
-    }
 }
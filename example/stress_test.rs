@@ -118,7 +118,7 @@ fn boostvoronoi_test(rnd_seed: [u8;32], printout_lock: Arc<Mutex<()>>) -> Result
             .with_segments(segments.iter())?
             .build();
         if result.is_err() || fault_check(&result, geo_segments).is_err() {
-            let _ = printout_lock.lock();
+            let _guard = printout_lock.lock();
             println!("\nfound a bad example:");
             println!("-------\n{}", vertices.len());
             for p in vertices.iter() {
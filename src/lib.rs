@@ -40,14 +40,34 @@ pub mod prelude {
     pub use boostvoronoi_core::{cast, try_cast, BvError, InputType, OutputType};
 }
 
-pub use boostvoronoi_core::builder::Builder;
+pub use boostvoronoi_core::attributes::{
+    AttributeMap, CellAttributes, EdgeAttributes, ElementIndex, VertexAttributes,
+};
+pub use boostvoronoi_core::builder::{BuildProgress, Builder};
+pub use boostvoronoi_core::clipping::{ConvexRegion, DiagramClipper, Ray};
+pub use boostvoronoi_core::delaunay::DelaunayTriangulation;
 pub use boostvoronoi_core::diagram::{
-    Cell, CellIndex, ColorType, Diagram, Edge, EdgeIndex, SourceCategory, SourceIndex, Vertex,
-    VertexIndex,
+    Cell, CellIndex, ColorType, Diagram, Edge, EdgeIndex, PathElement, PathSource, SourceCategory,
+    SourceIndex, Vertex, VertexIndex,
+};
+pub use boostvoronoi_core::empty_circle::{
+    largest_empty_circle, maximum_inscribed_circle, EmptyCircle,
 };
 pub use boostvoronoi_core::file_reader::{read_boost_input_buffer, read_boost_input_file};
+pub use boostvoronoi_core::float_builder::FloatBuilder;
 pub use boostvoronoi_core::geometry::*;
+pub use boostvoronoi_core::inside_outside::{FillRule, InsideOutside, Location};
+pub use boostvoronoi_core::lloyd::{LloydRelaxation, LloydResult, LloydStatistics};
+pub use boostvoronoi_core::measure::CellMeasure;
+pub use boostvoronoi_core::medial_axis::{MedialAxis, MedialAxisEdge, MedialAxisVertex};
+pub use boostvoronoi_core::offset::{OffsetElement, OffsetLoop, Offsetter};
+pub use boostvoronoi_core::parabola::{ParabolicArc, QuadraticBezier};
+pub use boostvoronoi_core::path_planning::{PathPlanner, PlannedPath};
+pub use boostvoronoi_core::point_location::PointLocator;
+pub use boostvoronoi_core::snap_rounding::{snap_round, SnapRounded};
 pub use boostvoronoi_core::sync_diagram::SyncDiagram;
+pub use boostvoronoi_core::trace::{SweepEvent, SweepObserver, TraceSite};
+pub use boostvoronoi_core::validation::{TopologyReport, TopologyViolation, ValidationReport};
 pub use boostvoronoi_core::visual_utils::*;
 pub use boostvoronoi_core::{cast, try_cast, BvError, InputType, OutputType};
 
@@ -0,0 +1,172 @@
+use boostvoronoi::prelude::*;
+use boostvoronoi::{CellAttributes, EdgeAttributes, SourceCategory, SyncDiagram, VertexAttributes};
+
+#[derive(Debug, Clone, PartialEq)]
+struct EdgeInfo {
+    length: f64,
+    label: String,
+}
+
+fn diagram_of(points: &[Point<i32>], segments: &[Line<i32>]) -> Result<Diagram<f64>, BvError> {
+    Builder::<i32, f64>::default()
+        .with_vertices(points.iter())?
+        .with_segments(segments.iter())?
+        .build()
+}
+
+#[test]
+/// Insert, replace and remove values, the element colors are not touched
+fn attributes_test_1() -> Result<(), BvError> {
+    let points: Vec<Point<i32>> = vec![[0, 0].into(), [50, 10].into(), [20, 40].into()];
+    let segments: Vec<Line<i32>> = vec![[-20, 60, 80, 70].into()];
+    let diagram = diagram_of(&points, &segments)?;
+
+    let mut info = EdgeAttributes::<EdgeInfo>::new();
+    assert!(info.is_empty());
+    for edge in diagram.edges().iter() {
+        let edge = edge.get();
+        if let (Some(v0), Some(v1)) = (edge.vertex0(), diagram.edge_get_vertex1(edge.id())?) {
+            let (v0, v1) = (diagram.vertex_get(v0)?.get(), diagram.vertex_get(v1)?.get());
+            let info_ = EdgeInfo {
+                length: (v1.x() - v0.x()).hypot(v1.y() - v0.y()),
+                label: format!("edge {}", edge.id().0),
+            };
+            assert!(info.insert(edge.id(), info_).is_none());
+        }
+    }
+    let finite = diagram
+        .edges()
+        .iter()
+        .filter(|e| diagram.edge_is_finite(e.get().id()).unwrap())
+        .count();
+    assert!(finite > 0);
+    assert_eq!(info.len(), finite);
+    for (edge_id, value) in info.iter() {
+        assert!(diagram.edge_is_finite(edge_id)?);
+        assert_eq!(value.label, format!("edge {}", edge_id.0));
+        let twin_id = diagram.edge_get_twin(edge_id)?;
+        assert_eq!(info.get(twin_id).map(|t| t.length), Some(value.length));
+    }
+
+    let first = info.keys().next().unwrap();
+    info.get_mut(first).unwrap().label.push('!');
+    let replaced = info.insert(
+        first,
+        EdgeInfo {
+            length: 0.0,
+            label: "replaced".to_string(),
+        },
+    );
+    assert!(replaced.unwrap().label.ends_with('!'));
+    assert_eq!(info.len(), finite);
+    assert_eq!(info.remove(first).unwrap().label, "replaced");
+    assert!(info.remove(first).is_none());
+    assert!(!info.contains_key(first));
+    assert_eq!(info.len(), finite - 1);
+    // far beyond the last edge
+    assert!(info.get(EdgeIndex(usize::MAX)).is_none());
+    info.clear();
+    assert!(info.is_empty() && info.iter().next().is_none());
+    assert_eq!(info, EdgeAttributes::new());
+
+    // removed values do not count when comparing
+    let mut grown = CellAttributes::<i32>::new();
+    let _ = grown.insert(CellIndex(5), 1);
+    let _ = grown.insert(CellIndex(1), 2);
+    assert_ne!(grown, CellAttributes::new());
+    let _ = grown.remove(CellIndex(5));
+    assert_eq!(grown, [(CellIndex(1), 2)].into_iter().collect());
+    let _ = grown.remove(CellIndex(1));
+    assert_eq!(grown, CellAttributes::new());
+
+    // the user color bits and the reserved bits are untouched
+    for edge in diagram.edges().iter() {
+        assert_eq!(edge.get().get_color(), 0);
+    }
+    for vertex in diagram.vertices().iter() {
+        assert_eq!(vertex.get().get_color(), 0);
+    }
+    Ok(())
+}
+
+#[test]
+/// The same maps work for a `SyncDiagram`, the indices are the same
+fn attributes_test_2() -> Result<(), BvError> {
+    let points: Vec<Point<i32>> = vec![[0, 0].into(), [30, 5].into(), [10, 25].into()];
+    let segments: Vec<Line<i32>> = vec![[-10, 40, 40, 45].into()];
+    let diagram = diagram_of(&points, &segments)?;
+
+    let categories: CellAttributes<SourceCategory> = diagram
+        .cells()
+        .iter()
+        .map(|c| (c.get().id(), c.get().source_category()))
+        .collect();
+    assert_eq!(categories.len(), diagram.cells().len());
+
+    let sync_diagram: SyncDiagram<f64> = diagram.into();
+    for cell in sync_diagram.cells().iter() {
+        assert_eq!(categories.get(cell.id()), Some(&cell.source_category()));
+    }
+    // every segment has one segment cell, its end points have their own cells
+    let segment_cells = categories
+        .values()
+        .filter(|c| **c == SourceCategory::Segment)
+        .count();
+    assert_eq!(segment_cells, segments.len());
+
+    let mut degree = VertexAttributes::<usize>::filled(sync_diagram.vertices().len(), 0);
+    for edge in sync_diagram.edges().iter() {
+        if let Some(v0) = edge.vertex0() {
+            *degree.get_mut(v0).unwrap() += 1;
+        }
+    }
+    assert_eq!(degree.len(), sync_diagram.vertices().len());
+    // every edge has a twin, so the degrees add up to the number of edges with a start vertex
+    let started = sync_diagram
+        .edges()
+        .iter()
+        .filter(|e| e.vertex0().is_some())
+        .count();
+    assert_eq!(degree.values().sum::<usize>(), started);
+    for (_, d) in degree.iter_mut() {
+        *d *= 2;
+    }
+    assert_eq!(degree.values().sum::<usize>(), 2 * started);
+    Ok(())
+}
+
+#[test]
+/// Collecting the cells around each vertex agrees with `Diagram::vertex_cells()`
+fn attributes_test_3() -> Result<(), BvError> {
+    let points: Vec<Point<i32>> = (0..30)
+        .map(|i| [(i * 37) % 101, (i * 61) % 103].into())
+        .collect();
+    let segments: Vec<Line<i32>> = vec![];
+    let diagram = diagram_of(&points, &segments)?;
+
+    let mut cells = VertexAttributes::<Vec<CellIndex>>::default();
+    for edge in diagram.edges().iter() {
+        let edge = edge.get();
+        if let Some(v0) = edge.vertex0() {
+            cells.get_or_insert_with(v0, Vec::new).push(edge.cell()?);
+        }
+    }
+    assert_eq!(cells.len(), diagram.vertices().len());
+    for (vertex_id, around) in cells.iter() {
+        let mut around = around.clone();
+        let mut expected = diagram.vertex_cells(vertex_id)?;
+        around.sort_by_key(|c| c.0);
+        expected.sort_by_key(|c| c.0);
+        assert_eq!(around, expected);
+    }
+
+    let mut extended = VertexAttributes::<usize>::new();
+    extended.extend(cells.iter().map(|(k, v)| (k, v.len())));
+    assert_eq!(
+        extended.keys().collect::<Vec<_>>(),
+        cells.keys().collect::<Vec<_>>()
+    );
+    assert!(extended.values().all(|n| *n >= 3));
+    assert!(format!("{:?}", extended).starts_with("{VertexIndex(0): "));
+    Ok(())
+}
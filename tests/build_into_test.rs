@@ -0,0 +1,65 @@
+use boostvoronoi::prelude::*;
+
+/// A summary of a diagram that can be compared
+fn summary(output: &Diagram<f64>) -> (Vec<(usize, usize)>, Vec<[u64; 2]>, usize) {
+    let cells = output
+        .cells()
+        .iter()
+        .map(|c| (c.get().source_index(), c.get().id().0))
+        .collect();
+    let vertices = output
+        .vertices()
+        .iter()
+        .map(|v| [v.get().x().to_bits(), v.get().y().to_bits()])
+        .collect();
+    (cells, vertices, output.edges().len())
+}
+
+#[test]
+/// Reusing the builder and the diagram should give the same result as fresh ones
+fn build_into_test_1() -> Result<(), BvError> {
+    let mut builder = Builder::<i32, f64>::default();
+    let mut output = Diagram::<f64>::default();
+    for i in 0..5 {
+        let points = [[50, 50 + i], [150, 20 - i]];
+        let segments = [[0, 0, 100, 0], [0, 100, 100 + i, 100]];
+        let expected = Builder::<i32, f64>::default()
+            .with_vertices(points)?
+            .with_segments(segments)?
+            .build()?;
+        builder = builder.with_vertices(points)?.with_segments(segments)?;
+        builder.build_into(&mut output)?;
+        assert_eq!(summary(&expected), summary(&output));
+    }
+    Ok(())
+}
+
+#[test]
+/// `reset()` should discard the input, including the polygon records and the site ids
+fn build_into_test_2() -> Result<(), BvError> {
+    let mut builder = Builder::<i32, f64, u32>::default()
+        .with_identified_vertices([(1, [5, 5])])?
+        .with_polylines([vec![[0, 0], [10, 0]]])?;
+    builder.reset();
+    let mut builder = builder.with_vertices([[0, 0], [10, 10]])?;
+    let mut output = Diagram::<f64>::default();
+    builder.build_into(&mut output)?;
+    assert_eq!(output.cells().len(), 2);
+    for cell in output.cells().iter() {
+        let id = cell.get().id();
+        assert!(output.cell_path_source(id)?.is_none());
+    }
+    // the builder is reset after build_into()
+    builder.build_into(&mut output)?;
+    assert!(output.cells().is_empty());
+
+    let mut builder =
+        Builder::<i32, f64, u32>::default().with_identified_vertices([(1, [5, 5]), (2, [7, 7])])?;
+    builder.reset();
+    let (output, ids) = builder
+        .with_vertices([[0, 0], [10, 10]])?
+        .build_identified()?;
+    assert_eq!(output.cells().len(), 2);
+    assert!(ids.is_empty());
+    Ok(())
+}
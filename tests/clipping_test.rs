@@ -0,0 +1,275 @@
+use boostvoronoi::prelude::*;
+use boostvoronoi::{Aabb2, ConvexRegion, DiagramClipper, PointLocator, SourceCategory};
+
+/// The signed area of a polygon, positive for counter clockwise polygons
+fn area(polygon: &[[f64; 2]]) -> f64 {
+    let n = polygon.len();
+    (0..n)
+        .map(|i| {
+            let (a, b) = (polygon[i], polygon[(i + 1) % n]);
+            a[0] * b[1] - a[1] * b[0]
+        })
+        .sum::<f64>()
+        * 0.5
+}
+
+/// Winding number test, points on the boundary may go either way
+fn contains(polygon: &[[f64; 2]], p: [f64; 2]) -> bool {
+    let n = polygon.len();
+    let mut winding = 0;
+    for i in 0..n {
+        let (a, b) = (polygon[i], polygon[(i + 1) % n]);
+        let side = (b[0] - a[0]) * (p[1] - a[1]) - (p[0] - a[0]) * (b[1] - a[1]);
+        if a[1] <= p[1] {
+            if b[1] > p[1] && side > 0.0 {
+                winding += 1;
+            }
+        } else if b[1] <= p[1] && side < 0.0 {
+            winding -= 1;
+        }
+    }
+    winding != 0
+}
+
+fn input() -> (Vec<Point<i32>>, Vec<Line<i32>>) {
+    let points: Vec<Point<i32>> = vec![
+        [5, 5].into(),
+        [50, 60].into(),
+        [80, 20].into(),
+        [30, 90].into(),
+        [95, 95].into(),
+    ];
+    let segments: Vec<Line<i32>> = vec![
+        [10, 30, 40, 40].into(),
+        [40, 40, 60, 10].into(),
+        [70, 70, 90, 50].into(),
+        [20, 60, 20, 80].into(),
+    ];
+    (points, segments)
+}
+
+#[test]
+/// The clipped cells should cover the region exactly, and agree with the point locator
+fn clipping_test_1() -> Result<(), BvError> {
+    let (points, segments) = input();
+    let diagram = Builder::<i32, f64>::default()
+        .with_vertices(points.iter())?
+        .with_segments(segments.iter())?
+        .build()?;
+    let locator = PointLocator::new(&diagram, &points, &segments)?;
+    for region in [
+        ConvexRegion::from_aabb(&Aabb2::new_from_i32::<i32>(-50, -40, 150, 130))?,
+        ConvexRegion::from_aabb(&Aabb2::new_from_i32::<i32>(20, 20, 70, 70))?,
+        ConvexRegion::new(vec![[0.0, 0.0], [60.0, 110.0], [120.0, -10.0]])?,
+    ] {
+        let region_area = area(region.vertices());
+        let clipper = DiagramClipper::new(&diagram, &points, &segments, region);
+        let polygons = clipper.cell_polygons(0.001)?;
+        assert_eq!(polygons.len(), diagram.cells().len());
+        let total: f64 = polygons.iter().map(|p| area(p)).sum();
+        assert!((total - region_area).abs() < 1e-3 * region_area);
+        for p in polygons.iter() {
+            assert!(area(p) >= 0.0);
+        }
+        for i in 0..50 {
+            for j in 0..50 {
+                let q = [i as f64 * 2.7 - 20.0, j as f64 * 2.9 - 30.0];
+                if !clipper.region().contains(q) {
+                    continue;
+                }
+                let cell = locator.locate(q[0], q[1]).unwrap();
+                let containing: Vec<usize> = (0..polygons.len())
+                    .filter(|c| contains(&polygons[*c], q))
+                    .collect();
+                // points exactly on the boundary may be claimed by none, or more than one cell
+                assert!(containing.len() <= 2, "{:?} {:?}", q, containing);
+                if containing.len() == 1 && containing[0] != cell.0 {
+                    // must be a point very close to the boundary, check the distance
+                    let other = &polygons[containing[0]];
+                    let near_boundary = (0..other.len()).any(|k| {
+                        let (a, b) = (other[k], other[(k + 1) % other.len()]);
+                        let ab = [b[0] - a[0], b[1] - a[1]];
+                        let t = (((q[0] - a[0]) * ab[0] + (q[1] - a[1]) * ab[1])
+                            / (ab[0] * ab[0] + ab[1] * ab[1]))
+                            .clamp(0.0, 1.0);
+                        (q[0] - a[0] - t * ab[0]).hypot(q[1] - a[1] - t * ab[1]) < 0.01
+                    });
+                    assert!(near_boundary, "{:?} {} {}", q, cell.0, containing[0]);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[test]
+/// Collinear points give infinite lines, a lone point owns the whole region
+fn clipping_test_2() -> Result<(), BvError> {
+    let region = ConvexRegion::from_aabb(&Aabb2::new_from_i32::<i32>(-10, -10, 40, 10))?;
+    let points: Vec<Point<i32>> = vec![[0, 0].into(), [10, 0].into(), [20, 0].into()];
+    let diagram = Builder::<i32, f64>::default()
+        .with_vertices(points.iter())?
+        .build()?;
+    let clipper = DiagramClipper::new(&diagram, &points, &[], region.clone());
+    let polygons = clipper.cell_polygons(0.1)?;
+    assert_eq!(polygons.len(), 3);
+    for (polygon, expected) in polygons.iter().zip([300.0, 200.0, 500.0]) {
+        assert!((area(polygon) - expected).abs() < 1e-9);
+    }
+
+    let points: Vec<Point<i32>> = vec![[100, 100].into()];
+    let diagram = Builder::<i32, f64>::default()
+        .with_vertices(points.iter())?
+        .build()?;
+    let clipper = DiagramClipper::new(&diagram, &points, &[], region.clone());
+    assert_eq!(
+        clipper.cell_polygons(0.1)?,
+        vec![region.vertices().to_vec()]
+    );
+    Ok(())
+}
+
+#[test]
+/// Invalid regions are rejected, clockwise regions are reversed
+fn clipping_test_3() -> Result<(), BvError> {
+    assert!(ConvexRegion::<f64>::new(vec![[0.0, 0.0], [1.0, 1.0]]).is_err());
+    assert!(ConvexRegion::<f64>::new(vec![[0.0, 0.0], [1.0, 1.0], [2.0, 2.0]]).is_err());
+    assert!(ConvexRegion::<f64>::new(vec![
+        [0.0, 0.0],
+        [2.0, 0.0],
+        [1.0, 0.5],
+        [2.0, 2.0],
+        [0.0, 2.0]
+    ])
+    .is_err());
+    assert!(ConvexRegion::<f64>::from_aabb(&Aabb2::default()).is_err());
+    let region = ConvexRegion::<f64>::new(vec![[0.0, 0.0], [0.0, 1.0], [1.0, 0.0]])?;
+    assert_eq!(region.vertices(), &[[1.0, 0.0], [0.0, 1.0], [0.0, 0.0]]);
+    Ok(())
+}
+
+/// The distance from `q` to the input site of `cell`
+fn distance_to_site(
+    q: [f64; 2],
+    cell: &Cell,
+    points: &[Point<i32>],
+    segments: &[Line<i32>],
+) -> f64 {
+    let to_f = |p: &Point<i32>| [p.x as f64, p.y as f64];
+    let (index, category) = cell.source_index_2();
+    let (a, b) = match category {
+        SourceCategory::SinglePoint => (to_f(&points[index]), to_f(&points[index])),
+        SourceCategory::SegmentStart => {
+            let p = to_f(&segments[index - points.len()].start);
+            (p, p)
+        }
+        SourceCategory::SegmentEnd => {
+            let p = to_f(&segments[index - points.len()].end);
+            (p, p)
+        }
+        SourceCategory::Segment => {
+            let l = &segments[index - points.len()];
+            (to_f(&l.start), to_f(&l.end))
+        }
+    };
+    let ab = [b[0] - a[0], b[1] - a[1]];
+    let length = ab[0] * ab[0] + ab[1] * ab[1];
+    let t = if length > 0.0 {
+        (((q[0] - a[0]) * ab[0] + (q[1] - a[1]) * ab[1]) / length).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    (q[0] - a[0] - t * ab[0]).hypot(q[1] - a[1] - t * ab[1])
+}
+
+#[test]
+/// Clipped edges stay inside the region, rays of infinite edges are equidistant to both sites
+fn clipping_test_4() -> Result<(), BvError> {
+    let (points, segments) = input();
+    let diagram = Builder::<i32, f64>::default()
+        .with_vertices(points.iter())?
+        .with_segments(segments.iter())?
+        .build()?;
+    let region = ConvexRegion::from_aabb(&Aabb2::new_from_i32::<i32>(0, 0, 100, 100))?;
+    let inside = |p: [f64; 2]| {
+        (-1e-9..=100.0 + 1e-9).contains(&p[0]) && (-1e-9..=100.0 + 1e-9).contains(&p[1])
+    };
+    let clipper = DiagramClipper::new(&diagram, &points, &segments, region);
+    let edges = clipper.clip_edges(0.01)?;
+    assert_eq!(edges.len(), diagram.edges().len());
+    let mut infinite_edges = 0;
+    for (edge_id, pieces) in edges.iter().enumerate() {
+        let edge_id = EdgeIndex(edge_id);
+        let edge = diagram.get_edge(edge_id)?.get();
+        let cell1 = diagram.get_cell(edge.cell()?)?.get();
+        let cell2 = diagram
+            .get_cell(diagram.edge_get_cell(edge.twin()?)?)?
+            .get();
+        let equidistant = |q: [f64; 2]| {
+            let d1 = distance_to_site(q, &cell1, &points, &segments);
+            let d2 = distance_to_site(q, &cell2, &points, &segments);
+            (d1 - d2).abs() < 1e-6 * d1.max(1.0)
+        };
+        for p in pieces.iter().flatten() {
+            assert!(inside(*p), "{:?}", p);
+        }
+        if !edge.is_curved() {
+            // the clipped straight edges are still on the bisector
+            for p in pieces.iter().flatten() {
+                assert!(equidistant(*p), "{:?}", p);
+            }
+        }
+        let vertex0 = edge.vertex0();
+        let vertex1 = diagram.edge_get_vertex1(edge_id)?;
+        match clipper.edge_ray(edge_id)? {
+            None => {
+                assert!(vertex0.is_some() && vertex1.is_some());
+                let v0 = diagram.vertex_get(vertex0.unwrap())?.get();
+                let v1 = diagram.vertex_get(vertex1.unwrap())?.get();
+                if !edge.is_curved() && inside([v0.x(), v0.y()]) && inside([v1.x(), v1.y()]) {
+                    assert_eq!(pieces, &vec![vec![[v0.x(), v0.y()], [v1.x(), v1.y()]]]);
+                }
+            }
+            Some(ray) => {
+                infinite_edges += 1;
+                if let Some(v) = vertex0.or(vertex1) {
+                    let v = diagram.vertex_get(v)?.get();
+                    assert_eq!(ray.origin, [v.x(), v.y()]);
+                }
+                for t in [0.0, 1.0, 10.0, 1000.0] {
+                    let q = [
+                        ray.origin[0] + t * ray.direction[0],
+                        ray.origin[1] + t * ray.direction[1],
+                    ];
+                    assert!(equidistant(q), "{:?}", q);
+                }
+            }
+        }
+    }
+    assert!(infinite_edges > 0);
+
+    // two points: a single line
+    let points: Vec<Point<i32>> = vec![[0, 0].into(), [10, 0].into()];
+    let diagram = Builder::<i32, f64>::default()
+        .with_vertices(points.iter())?
+        .build()?;
+    let region = ConvexRegion::new(vec![[0.0, -10.0], [10.0, 0.0], [0.0, 10.0], [-10.0, 0.0]])?;
+    let clipper = DiagramClipper::new(&diagram, &points, &[], region);
+    let ray = clipper.edge_ray(EdgeIndex(0))?.unwrap();
+    assert_eq!(ray.origin, [5.0, 0.0]);
+    assert_eq!(ray.direction[0], 0.0);
+    let pieces = clipper.clip_edge(EdgeIndex(0), 0.1)?;
+    assert_eq!(pieces.len(), 1);
+    assert_eq!(pieces[0].len(), 2);
+    for (p, y) in pieces[0]
+        .iter()
+        .zip([-5.0, 5.0].iter().map(|y| y * ray.direction[1].signum()))
+    {
+        assert!(
+            (p[0] - 5.0).abs() < 1e-9 && (p[1] - y).abs() < 1e-9,
+            "{:?}",
+            p
+        );
+    }
+    Ok(())
+}
@@ -119,3 +119,59 @@ pub fn to_points<I: InputType>(points: &[[I; 2]]) -> Vec<Point<I>> {
 pub fn to_segments<I: InputType>(segments: &[[I; 4]]) -> Vec<Line<I>> {
     segments.iter().map(|l| l.into()).collect()
 }
+
+#[allow(dead_code)]
+/// A tiny deterministic pseudo random generator
+pub struct Lcg(pub u64);
+
+#[allow(dead_code)]
+impl Lcg {
+    /// Returns a number in `0..range`
+    pub fn next(&mut self, range: i32) -> i32 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        ((self.0 >> 33) % range as u64) as i32
+    }
+}
+
+#[allow(dead_code)]
+/// The distance from `q` to the closest point of the segment `a`-`b`
+pub fn distance_to_segment(q: [f64; 2], a: [i32; 2], b: [i32; 2]) -> f64 {
+    let (ax, ay) = (a[0] as f64, a[1] as f64);
+    let (bx, by) = (b[0] as f64 - ax, b[1] as f64 - ay);
+    let t = (((q[0] - ax) * bx + (q[1] - ay) * by) / (bx * bx + by * by)).clamp(0.0, 1.0);
+    (q[0] - ax - t * bx).hypot(q[1] - ay - t * by)
+}
+
+#[allow(dead_code)]
+/// The distance to the closest point of any ring
+pub fn clearance<R: AsRef<[[i32; 2]]>>(q: [f64; 2], rings: &[R]) -> f64 {
+    rings
+        .iter()
+        .map(|ring| ring.as_ref())
+        .flat_map(|ring| {
+            (0..ring.len())
+                .map(move |i| distance_to_segment(q, ring[i], ring[(i + 1) % ring.len()]))
+        })
+        .fold(f64::MAX, f64::min)
+}
+
+#[allow(dead_code)]
+/// Even-odd test
+pub fn inside<R: AsRef<[[i32; 2]]>>(q: [f64; 2], rings: &[R]) -> bool {
+    let mut inside = false;
+    for ring in rings.iter().map(|ring| ring.as_ref()) {
+        for i in 0..ring.len() {
+            let (a, b) = (ring[i], ring[(i + 1) % ring.len()]);
+            let (ay, by) = (a[1] as f64, b[1] as f64);
+            if (ay > q[1]) != (by > q[1])
+                && q[0] < a[0] as f64 + (q[1] - ay) / (by - ay) * (b[0] - a[0]) as f64
+            {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
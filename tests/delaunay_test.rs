@@ -0,0 +1,120 @@
+use boostvoronoi::prelude::*;
+use boostvoronoi::{DelaunayTriangulation, SyncDiagram};
+
+mod common;
+use common::Lcg;
+
+/// Twice the signed area of the triangle
+fn orientation(a: [i64; 2], b: [i64; 2], c: [i64; 2]) -> i64 {
+    (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+}
+
+/// Positive if `d` is strictly inside the circumcircle of the counter clockwise triangle `abc`
+fn in_circle(a: [i64; 2], b: [i64; 2], c: [i64; 2], d: [i64; 2]) -> i128 {
+    let row = |p: [i64; 2]| {
+        let (x, y) = ((p[0] - d[0]) as i128, (p[1] - d[1]) as i128);
+        [x, y, x * x + y * y]
+    };
+    let (a, b, c) = (row(a), row(b), row(c));
+    a[0] * (b[1] * c[2] - b[2] * c[1]) - a[1] * (b[0] * c[2] - b[2] * c[0])
+        + a[2] * (b[0] * c[1] - b[1] * c[0])
+}
+
+/// Checks that the triangulation is a valid Delaunay triangulation of `points`
+fn check(points: &[[i64; 2]], delaunay: &DelaunayTriangulation) {
+    let hull = delaunay.convex_hull();
+    let n = hull.len();
+    // the hull is convex, counter clockwise, and every point is inside it
+    for i in 0..n {
+        let (a, b) = (points[hull[i]], points[hull[(i + 1) % n]]);
+        assert!(orientation(a, b, points[hull[(i + 2) % n]]) >= 0);
+        for p in points.iter() {
+            assert!(orientation(a, b, *p) >= 0);
+        }
+    }
+    // a triangulation of n points with h points on the hull has 2n - h - 2 triangles
+    assert_eq!(delaunay.triangles().len(), 2 * points.len() - n - 2);
+    let hull_area: i64 = (0..n)
+        .map(|i| orientation(points[hull[0]], points[hull[i]], points[hull[(i + 1) % n]]))
+        .sum();
+    let mut triangle_area = 0;
+    for t in delaunay.triangles() {
+        let (a, b, c) = (points[t[0]], points[t[1]], points[t[2]]);
+        let area = orientation(a, b, c);
+        assert!(area > 0, "{:?}", t);
+        triangle_area += area;
+        for p in points.iter() {
+            assert!(in_circle(a, b, c, *p) <= 0, "{:?} {:?}", t, p);
+        }
+    }
+    assert_eq!(triangle_area, hull_area);
+}
+
+#[test]
+/// Random points
+fn delaunay_test_1() -> Result<(), BvError> {
+    let mut rnd = Lcg(3);
+    let mut points: Vec<[i64; 2]> = (0..500)
+        .map(|_| [rnd.next(10000) as i64, rnd.next(10000) as i64])
+        .collect();
+    points.sort_unstable();
+    points.dedup();
+    let input: Vec<Point<i64>> = points.iter().map(|p| (*p).into()).collect();
+    let diagram = Builder::<i64, f64>::default()
+        .with_vertices(input.iter())?
+        .build()?;
+    let delaunay = DelaunayTriangulation::new(&diagram)?;
+    check(&points, &delaunay);
+    let diagram: SyncDiagram<f64> = diagram.into();
+    assert_eq!(DelaunayTriangulation::new_sync(&diagram)?, delaunay);
+    Ok(())
+}
+
+#[test]
+/// A grid, every voronoi vertex has four cocircular sites
+fn delaunay_test_2() -> Result<(), BvError> {
+    let points: Vec<[i64; 2]> = (0..5)
+        .flat_map(|x| (0..4).map(move |y| [x * 10, y * 10]))
+        .collect();
+    let input: Vec<Point<i64>> = points.iter().map(|p| (*p).into()).collect();
+    let diagram = Builder::<i64, f64>::default()
+        .with_vertices(input.iter())?
+        .build()?;
+    let delaunay = DelaunayTriangulation::new(&diagram)?;
+    check(&points, &delaunay);
+    assert_eq!(delaunay.triangles().len(), 2 * 4 * 3);
+    assert_eq!(delaunay.convex_hull().len(), 14);
+    Ok(())
+}
+
+#[test]
+/// Collinear points, a single point and segments
+fn delaunay_test_3() -> Result<(), BvError> {
+    let diagram = Builder::<i32, f64>::default()
+        .with_vertices([[20, 20], [0, 0], [30, 30], [10, 10]])?
+        .build()?;
+    let delaunay = DelaunayTriangulation::new(&diagram)?;
+    assert!(delaunay.triangles().is_empty());
+    let hull = delaunay.convex_hull();
+    assert!(hull == [1, 3, 0, 2] || hull == [2, 0, 3, 1], "{:?}", hull);
+
+    let diagram = Builder::<i32, f64>::default()
+        .with_vertices([[20, 20]])?
+        .build()?;
+    let delaunay = DelaunayTriangulation::new(&diagram)?;
+    assert!(delaunay.triangles().is_empty());
+    assert_eq!(delaunay.convex_hull(), &[0]);
+
+    let diagram = Builder::<i32, f64>::default().build()?;
+    assert_eq!(
+        DelaunayTriangulation::new(&diagram)?,
+        DelaunayTriangulation::default()
+    );
+
+    let diagram = Builder::<i32, f64>::default()
+        .with_vertices([[20, 20]])?
+        .with_segments([[0, 0, 10, 0]])?
+        .build()?;
+    assert!(DelaunayTriangulation::new(&diagram).is_err());
+    Ok(())
+}
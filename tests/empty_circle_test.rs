@@ -0,0 +1,111 @@
+use boostvoronoi::prelude::*;
+use boostvoronoi::{
+    largest_empty_circle, maximum_inscribed_circle, ConvexRegion, EmptyCircle, Polygon,
+};
+
+mod common;
+use common::{clearance, inside, Lcg};
+
+#[test]
+/// Random points, compared to a brute force search
+fn empty_circle_test_1() -> Result<(), BvError> {
+    let mut rnd = Lcg(11);
+    let points: Vec<Point<i32>> = (0..200)
+        .map(|_| [rnd.next(1000), rnd.next(1000)].into())
+        .collect();
+    let diagram = Builder::<i32, f64>::default()
+        .with_vertices(points.iter())?
+        .build()?;
+    let bounds = ConvexRegion::new(vec![
+        [100.0, 0.0],
+        [900.0, 100.0],
+        [800.0, 900.0],
+        [0.0, 700.0],
+    ])?;
+    let circle = largest_empty_circle(&diagram, &points, &bounds)?.unwrap();
+    let nearest = |q: [f64; 2]| {
+        points
+            .iter()
+            .map(|p| (q[0] - p.x as f64).hypot(q[1] - p.y as f64))
+            .fold(f64::MAX, f64::min)
+    };
+    assert!((nearest(circle.center) - circle.radius).abs() < 1e-6);
+    let (x0, y0, x1, y1) = (0.0, 0.0, 900.0, 900.0);
+    assert!(circle.center[0] >= x0 && circle.center[1] >= y0);
+    assert!(circle.center[0] <= x1 && circle.center[1] <= y1);
+    for i in 0..=100 {
+        for j in 0..=100 {
+            let q = [i as f64 * 9.0, j as f64 * 9.0];
+            if bounds.contains(q) {
+                assert!(nearest(q) <= circle.radius + 1e-6, "{:?}", q);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[test]
+/// The largest empty circle requires points, and is undefined without them
+fn empty_circle_test_2() -> Result<(), BvError> {
+    let bounds = ConvexRegion::new(vec![[0.0, 0.0], [10.0, 0.0], [0.0, 10.0]])?;
+    let diagram = Builder::<i32, f64>::default().build()?;
+    assert!(largest_empty_circle::<i32, f64>(&diagram, &[], &bounds)?.is_none());
+
+    let points: Vec<Point<i32>> = vec![[20, 20].into()];
+    let diagram = Builder::<i32, f64>::default()
+        .with_vertices(points.iter())?
+        .with_segments([[0, 0, 10, 0]])?
+        .build()?;
+    assert!(largest_empty_circle(&diagram, &points, &bounds).is_err());
+
+    // a single point outside the bounds
+    let diagram = Builder::<i32, f64>::default()
+        .with_vertices(points.iter())?
+        .build()?;
+    let circle = largest_empty_circle(&diagram, &points, &bounds)?.unwrap();
+    assert_eq!(circle.center, [0.0, 0.0]);
+    Ok(())
+}
+
+/// Checks the circle against a brute force search on the integer grid
+fn check_inscribed(circle: &EmptyCircle<f64>, rings: &[&[[i32; 2]]]) {
+    assert!(inside(circle.center, rings));
+    assert!((clearance(circle.center, rings) - circle.radius).abs() < 1e-6);
+    for i in 0..=100 {
+        for j in 0..=100 {
+            let q = [i as f64, j as f64];
+            if inside(q, rings) {
+                assert!(clearance(q, rings) <= circle.radius + 1e-6, "{:?}", q);
+            }
+        }
+    }
+}
+
+#[test]
+/// The maximum inscribed circle of a polygon with a hole, and of an L shape
+fn empty_circle_test_3() -> Result<(), BvError> {
+    // the hole is larger than the band around it, its center must not be picked
+    let exterior = [[0, 0], [100, 0], [100, 100], [0, 100]];
+    let hole = [[20, 20], [80, 20], [80, 80], [20, 80]];
+    let polygons = [Polygon::new(
+        exterior.iter().map(|p| (*p).into()).collect(),
+        vec![hole.iter().map(|p| (*p).into()).collect()],
+    )];
+    let diagram = Builder::<i32, f64>::default()
+        .with_polygons(polygons.iter().cloned())?
+        .build()?;
+    let circle = maximum_inscribed_circle(&diagram, &polygons)?.unwrap();
+    // in the corners, touching two sides and a corner of the hole
+    let expected = 20.0 * 2.0_f64.sqrt() / (1.0 + 2.0_f64.sqrt());
+    assert!((circle.radius - expected).abs() < 1e-9, "{:?}", circle);
+    check_inscribed(&circle, &[&exterior, &hole]);
+
+    let ring = [[0, 0], [100, 0], [100, 30], [30, 30], [30, 100], [0, 100]];
+    let polygons = [Polygon::from(ring.to_vec())];
+    let diagram = Builder::<i32, f64>::default()
+        .with_polygons(polygons.iter().cloned())?
+        .build()?;
+    let circle = maximum_inscribed_circle(&diagram, &polygons)?.unwrap();
+    check_inscribed(&circle, &[&ring]);
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use boostvoronoi as BV;
+use boostvoronoi::BvError;
+
+#[test]
+fn float_builder_1() -> Result<(), BvError> {
+    // four points in a square, the only vertex is at the center
+    let points = [[1.25_f64, 1.25], [1.75, 1.25], [1.75, 1.75], [1.25, 1.75]];
+    let builder = BV::FloatBuilder::<i32, f64>::default().with_vertices(points.iter().copied())?;
+    let transform = builder.transform();
+    let diagram = builder.build()?;
+    assert_eq!(diagram.vertices().len(), 1);
+    let v = diagram.vertices()[0].get();
+    approx::assert_ulps_eq!(v.x(), 1.5);
+    approx::assert_ulps_eq!(v.y(), 1.5);
+
+    // the input was mapped onto the full safe integer range
+    let low = transform.reverse_transform::<i32>(1.25, 1.25)?;
+    let high = transform.reverse_transform::<i32>(1.75, 1.75)?;
+    assert_eq!(low, [-(1 << 30), -(1 << 30)]);
+    assert_eq!(high, [1 << 30, 1 << 30]);
+    assert_eq!(transform.transform_i(high), [1.75, 1.75]);
+    Ok(())
+}
+
+#[test]
+fn float_builder_2() -> Result<(), BvError> {
+    // a segment and a point, in tiny world coordinates
+    let builder = BV::FloatBuilder::<i64, f64>::default()
+        .with_vertices([[0.0_f64, 1e-9]].iter().copied())?
+        .with_segments([[-1e-9_f64, 0.0, 1e-9, 0.0]].iter().copied())?;
+    let diagram = builder.build()?;
+    for v in diagram.vertices() {
+        let v = v.get();
+        assert!(v.x().abs() <= 1e-9 && v.y().abs() <= 1e-9, "{:?}", v);
+    }
+    assert_eq!(diagram.cells().len(), 4);
+    Ok(())
+}
+
+#[test]
+fn float_builder_3() {
+    let rv =
+        BV::FloatBuilder::<i32, f32>::default().with_vertices([[f32::NAN, 1.0]].iter().copied());
+    assert!(rv.is_err());
+}
@@ -0,0 +1,237 @@
+use boostvoronoi::prelude::*;
+use boostvoronoi::{FillRule, InsideOutside, Location, SourceCategory};
+
+mod common;
+use common::inside;
+
+fn expected(q: [f64; 2], rings: &[Vec<[i32; 2]>]) -> Location {
+    if inside(q, rings) {
+        Location::Interior
+    } else {
+        Location::Exterior
+    }
+}
+
+fn ring_segments(rings: &[Vec<[i32; 2]>]) -> Vec<Line<i32>> {
+    rings
+        .iter()
+        .flat_map(|ring| {
+            (0..ring.len()).map(move |i| {
+                let (a, b) = (ring[i], ring[(i + 1) % ring.len()]);
+                Line::new(a.into(), b.into())
+            })
+        })
+        .collect()
+}
+
+/// Compares every vertex, and the middle of every finite straight edge, with the even-odd test
+fn check(
+    diagram: &Diagram<f64>,
+    classification: &InsideOutside,
+    rings: &[Vec<[i32; 2]>],
+) -> Result<(), BvError> {
+    for vertex in diagram.vertices().iter() {
+        let vertex = vertex.get();
+        let location = classification.vertex_location(vertex.get_id())?;
+        if vertex.is_site_point() {
+            assert_eq!(location, Location::Boundary);
+        } else {
+            assert_eq!(location, expected([vertex.x(), vertex.y()], rings));
+        }
+    }
+    for edge in diagram.edges().iter() {
+        let edge = edge.get();
+        let location = classification.edge_location(edge.id())?;
+        assert_ne!(location, Location::Boundary);
+        if let (Some(v0), Some(v1)) = (edge.vertex0(), diagram.edge_get_vertex1(edge.id())?) {
+            if edge.is_linear() {
+                let (v0, v1) = (diagram.vertex_get(v0)?.get(), diagram.vertex_get(v1)?.get());
+                let middle = [(v0.x() + v1.x()) * 0.5, (v0.y() + v1.y()) * 0.5];
+                assert_eq!(location, expected(middle, rings), "{:?}", edge);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[test]
+/// A square with a hole: the hole is exterior even though it is not connected to infinity
+fn inside_outside_test_1() -> Result<(), BvError> {
+    let exterior = vec![[0, 0], [300, 0], [300, 300], [0, 300]];
+    let hole = vec![[100, 100], [100, 200], [200, 200], [200, 100]];
+    let rings = [exterior.clone(), hole.clone()];
+    let polygons = [Polygon::<i32>::new(
+        exterior.iter().map(|p| (*p).into()).collect(),
+        vec![hole.iter().map(|p| (*p).into()).collect()],
+    )];
+    let diagram = Builder::<i32, f64>::default()
+        .with_polygons(polygons.iter().cloned())?
+        .build()?;
+    for rule in [FillRule::EvenOdd, FillRule::NonZero] {
+        let classification = InsideOutside::from_polygons(&diagram, &polygons, rule)?;
+        check(&diagram, &classification, &rings)?;
+
+        // the corners of the outer ring are convex, the corners of the hole are reflex
+        for cell in diagram.cells().iter() {
+            let cell = cell.get();
+            let location = classification.cell_location(cell.id())?;
+            if cell.source_category() == SourceCategory::Segment {
+                assert_eq!(location, Location::Boundary);
+            } else if cell.source_index() < exterior.len() {
+                assert_eq!(location, Location::Exterior);
+            } else {
+                assert_eq!(location, Location::Interior);
+            }
+        }
+    }
+
+    // the old edge coloring does not reach the inside of the hole
+    diagram.color_exterior_edges(1);
+    let classification = InsideOutside::from_polygons(&diagram, &polygons, FillRule::EvenOdd)?;
+    let missed = classification
+        .edges()
+        .iter()
+        .filter(|(e, l)| **l == Location::Exterior && diagram.edge_get_color(*e).unwrap() == 0)
+        .count();
+    assert!(missed > 0);
+    Ok(())
+}
+
+#[test]
+/// Nested rings with the same orientation, the fill rule decides, and the cells are colored
+fn inside_outside_test_2() -> Result<(), BvError> {
+    let outer = vec![[0, 0], [400, 0], [400, 400], [0, 400]];
+    let inner = vec![[100, 100], [300, 100], [300, 300], [100, 300]];
+    let rings = [outer.clone(), inner.clone()];
+    let points: Vec<Point<i32>> = vec![[200, 200].into(), [50, 200].into(), [500, 200].into()];
+    let mut segments = ring_segments(&rings);
+    // a zero length segment is a point site
+    segments.push([200, 50, 200, 50].into());
+    let diagram = Builder::<i32, f64>::default()
+        .with_vertices(points.iter())?
+        .with_segments(segments.iter())?
+        .build()?;
+
+    let even_odd = InsideOutside::new(&diagram, &points, &segments, FillRule::EvenOdd)?;
+    check(&diagram, &even_odd, &rings)?;
+    let non_zero = InsideOutside::new(&diagram, &points, &segments, FillRule::NonZero)?;
+    let point_cells: Vec<_> = diagram
+        .cells()
+        .iter()
+        .map(|c| c.get())
+        .filter(|c| c.source_category() == SourceCategory::SinglePoint)
+        .collect();
+    assert_eq!(point_cells.len(), points.len() + 1);
+    for cell in point_cells.iter() {
+        let p = if cell.source_index() < points.len() {
+            points[cell.source_index()]
+        } else {
+            segments[cell.source_index() - points.len()].start
+        };
+        let (e, n) = (
+            even_odd.cell_location(cell.id())?,
+            non_zero.cell_location(cell.id())?,
+        );
+        match [p.x, p.y] {
+            // the inner ring is counted twice
+            [200, 200] => assert_eq!((e, n), (Location::Exterior, Location::Interior)),
+            [50, 200] | [200, 50] => {
+                assert_eq!((e, n), (Location::Interior, Location::Interior))
+            }
+            _ => assert_eq!((e, n), (Location::Exterior, Location::Exterior)),
+        }
+    }
+    for (vertex_id, location) in non_zero.vertices().iter() {
+        let v = diagram.vertex_get(vertex_id)?.get();
+        if *location != Location::Boundary {
+            let inside_outer = v.x() > 0.0 && v.x() < 400.0 && v.y() > 0.0 && v.y() < 400.0;
+            assert_eq!(*location == Location::Interior, inside_outer, "{:?}", v);
+        }
+    }
+
+    let categories: Vec<SourceCategory> = diagram
+        .cells()
+        .iter()
+        .map(|c| c.get().source_category())
+        .collect();
+    non_zero.color_diagram(&diagram, 1, 2)?;
+    for (cell_id, location) in non_zero.cells().iter() {
+        let color = diagram.cell_get_color(cell_id)?;
+        let expected = match location {
+            Location::Interior => 1,
+            Location::Exterior => 2,
+            Location::Boundary => 0,
+        };
+        assert_eq!(color, expected);
+        // the reserved bits are intact
+        assert_eq!(
+            diagram.get_cell(cell_id)?.get().source_category(),
+            categories[cell_id.0]
+        );
+    }
+    for (edge_id, location) in non_zero.edges().iter() {
+        let color = diagram.edge_get_color(edge_id)?;
+        assert_eq!(
+            color,
+            if *location == Location::Interior {
+                1
+            } else {
+                2
+            }
+        );
+    }
+    assert!(matches!(
+        non_zero.cell_location(CellIndex(usize::MAX)),
+        Err(BvError::IdError(_))
+    ));
+    assert!(matches!(
+        diagram.vertex_try_or_color(VertexIndex(usize::MAX), 1),
+        Err(BvError::IdError(_))
+    ));
+    Ok(())
+}
+
+#[test]
+/// A star shaped polygon with a hole, and a second polygon, in both ring orientations
+fn inside_outside_test_3() -> Result<(), BvError> {
+    let star: Vec<[i32; 2]> = (0..14)
+        .map(|i| {
+            let angle = i as f64 * std::f64::consts::PI * 2.0 / 14.0;
+            let r = if i % 2 == 0 { 400.0 } else { 170.0 };
+            [
+                500 + (r * angle.cos()).round() as i32,
+                500 + (r * angle.sin()).round() as i32,
+            ]
+        })
+        .collect();
+    let hole = vec![[460, 460], [540, 460], [540, 540], [460, 540]];
+    let triangle = vec![[1000, 0], [1300, 0], [1150, 250]];
+    let rings = [star.clone(), hole.clone(), triangle.clone()];
+    let reversed = |ring: &Vec<[i32; 2]>| -> Vec<Point<i32>> {
+        ring.iter().rev().map(|p| (*p).into()).collect()
+    };
+    let forward =
+        |ring: &Vec<[i32; 2]>| -> Vec<Point<i32>> { ring.iter().map(|p| (*p).into()).collect() };
+    for flip in [false, true] {
+        let orient = if flip { reversed } else { forward };
+        let polygons = [
+            Polygon::new(orient(&star), vec![orient(&hole)]),
+            Polygon::new(orient(&triangle), vec![]),
+        ];
+        let diagram = Builder::<i32, f64>::default()
+            .with_polygons(polygons.iter().cloned())?
+            .build()?;
+        for rule in [FillRule::EvenOdd, FillRule::NonZero] {
+            let classification = InsideOutside::from_polygons(&diagram, &polygons, rule)?;
+            check(&diagram, &classification, &rings)?;
+            let interior_cells = classification
+                .cells()
+                .values()
+                .filter(|l| **l == Location::Interior)
+                .count();
+            // the 7 reflex corners of the star, and the 4 corners of the hole
+            assert_eq!(interior_cells, 11);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,82 @@
+use boostvoronoi::prelude::*;
+use boostvoronoi::{PathElement, PathSource, SourceCategory};
+
+/// Returns the (source index, source category) of every cell, sorted
+fn cell_sources(output: &Diagram<f64>) -> Vec<(usize, SourceCategory)> {
+    let mut rv: Vec<_> = output
+        .cells()
+        .iter()
+        .map(|c| {
+            let c = c.get();
+            (c.source_index(), c.source_category())
+        })
+        .collect();
+    rv.sort_by_key(|(i, c)| (*i, *c as u8));
+    rv
+}
+
+#[test]
+/// Interleaved insertion should give the same diagram as vertices-first insertion
+fn interleaved_input_test_1() -> Result<(), BvError> {
+    let p = [Point::new(50, 50), Point::new(150, 20)];
+    let s = [Line::from([0, 0, 100, 0]), Line::from([0, 100, 100, 100])];
+    let ordered = Builder::<i32, f64>::default()
+        .with_vertices(p.iter())?
+        .with_segments(s.iter())?
+        .build()?;
+    let interleaved = Builder::<i32, f64>::default()
+        .with_segments(s[..1].iter())?
+        .with_vertices(p[..1].iter())?
+        .with_segments(s[1..].iter())?
+        .with_vertices(p[1..].iter())?
+        .build()?;
+    assert_eq!(cell_sources(&ordered), cell_sources(&interleaved));
+    assert_eq!(ordered.vertices().len(), interleaved.vertices().len());
+    assert_eq!(ordered.edges().len(), interleaved.edges().len());
+    // the vertices are numbered first
+    assert_eq!(
+        cell_sources(&interleaved)[..2],
+        [
+            (0, SourceCategory::SinglePoint),
+            (1, SourceCategory::SinglePoint)
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+/// Paths and site ids follow the segments when vertices are added afterwards
+fn interleaved_input_test_2() -> Result<(), BvError> {
+    let (output, ids) = Builder::<i32, f64, &str>::default()
+        .with_polylines([vec![[0, 0], [100, 0], [100, 100]]])?
+        .with_identified_segments([("s", [200, 0, 200, 100])])?
+        .with_identified_vertices([("p", [50, 50])])?
+        .build_identified()?;
+    for cell in output.cells().iter() {
+        let cell = cell.get();
+        let id = ids.get(cell.id()).copied();
+        let source = output.cell_path_source(cell.id())?;
+        match cell.source_index() {
+            0 => {
+                assert_eq!(id, Some("p"));
+                assert!(source.is_none());
+            }
+            1 | 2 => {
+                assert_eq!(id, None);
+                assert!(matches!(
+                    source,
+                    Some(PathSource::Polyline {
+                        polyline: 0,
+                        element: PathElement::Vertex(_) | PathElement::Edge(_),
+                    })
+                ));
+            }
+            3 => {
+                assert_eq!(id, Some("s"));
+                assert!(source.is_none());
+            }
+            _ => unreachable!(),
+        }
+    }
+    Ok(())
+}
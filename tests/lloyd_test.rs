@@ -0,0 +1,116 @@
+use boostvoronoi::prelude::*;
+use boostvoronoi::{Aabb2, ConvexRegion, DiagramClipper, LloydRelaxation};
+
+/// A deterministic pseudo random point set, clustered in one corner of the region
+fn clustered_points(n: usize) -> Vec<Point<i32>> {
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        (state % 300) as i32
+    };
+    let mut points = Vec::<Point<i32>>::new();
+    while points.len() < n {
+        let p: Point<i32> = [next(), next()].into();
+        if !points.contains(&p) {
+            points.push(p);
+        }
+    }
+    points
+}
+
+#[test]
+/// The energy decreases monotonically and the points spread out over the region
+fn lloyd_test_1() -> Result<(), BvError> {
+    let region = ConvexRegion::from_aabb(&Aabb2::new_from_i32::<i32>(0, 0, 1000, 1000))?;
+    let input = clustered_points(40);
+    let result = LloydRelaxation::<i32, f64>::new(region.clone())
+        .with_max_iterations(60)
+        .relax(input.iter())?;
+    assert_eq!(result.points.len(), input.len());
+    assert!(!result.statistics.is_empty() && result.statistics.len() <= 60);
+    for w in result.statistics.windows(2) {
+        // the rounding to integers may cost a tiny bit of energy
+        assert!(
+            w[1].energy <= w[0].energy * (1.0 + 1e-3),
+            "{:?}",
+            result.statistics
+        );
+    }
+    let first = result.statistics.first().unwrap();
+    let last = result.statistics.last().unwrap();
+    assert!(last.energy < first.energy * 0.1, "{:?} {:?}", first, last);
+
+    // all points are unique and inside the region
+    let mut sorted = result.points.clone();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    sorted.dedup();
+    assert_eq!(sorted.len(), input.len());
+    for p in result.points.iter() {
+        assert!(region.contains([p.x as f64, p.y as f64]), "{:?}", p);
+    }
+
+    // the reported energy matches the cells of the input configuration
+    let diagram = Builder::<i32, f64>::default()
+        .with_vertices(input.iter())?
+        .build()?;
+    let clipper = DiagramClipper::new(&diagram, &input, &[], region);
+    let mut energy = 0.0;
+    for (cell, m) in diagram.cells().iter().zip(clipper.cell_measures()?) {
+        let m = m.unwrap();
+        let site = input[cell.get().source_index()];
+        let (dx, dy) = (m.centroid[0] - site.x as f64, m.centroid[1] - site.y as f64);
+        energy += m.inertia + m.area * (dx * dx + dy * dy);
+    }
+    assert!((first.energy - energy).abs() < 1e-9 * energy);
+    Ok(())
+}
+
+#[test]
+/// Two points in a rectangle converge to the centers of the two halves
+fn lloyd_test_2() -> Result<(), BvError> {
+    let region = ConvexRegion::from_aabb(&Aabb2::new_from_i32::<i32>(0, 0, 200, 100))?;
+    let input: Vec<Point<i32>> = vec![[10, 40].into(), [30, 60].into()];
+    let result = LloydRelaxation::<i32, f64>::new(region).relax(input.iter())?;
+    assert!(result.converged);
+    let mut points: Vec<[i32; 2]> = result.points.iter().map(|p| [p.x, p.y]).collect();
+    points.sort();
+    assert_eq!(points, vec![[50, 50], [150, 50]]);
+    let last = result.statistics.last().unwrap();
+    assert_eq!(last.moved, 0);
+    // two 100x100 squares, each with the moment 100² * (100² + 100²) / 12
+    assert!((last.energy - 2.0 * 1e4 * 2e4 / 12.0).abs() < 1e-6);
+    Ok(())
+}
+
+#[test]
+/// The iteration limit, the tolerance and duplicate input
+fn lloyd_test_3() -> Result<(), BvError> {
+    let region = ConvexRegion::from_aabb(&Aabb2::new_from_i32::<i32>(0, 0, 1000, 1000))?;
+    let input = clustered_points(20);
+    let limited = LloydRelaxation::<i32, f64>::new(region.clone())
+        .with_max_iterations(3)
+        .relax(input.iter())?;
+    assert_eq!(limited.statistics.len(), 3);
+    assert!(!limited.converged);
+
+    let loose = LloydRelaxation::<i32, f64>::new(region.clone())
+        .with_tolerance(1e6)
+        .relax(input.iter())?;
+    assert_eq!(loose.statistics.len(), 1);
+    assert!(loose.converged);
+
+    let none = LloydRelaxation::<i32, f64>::new(region.clone())
+        .with_max_iterations(0)
+        .relax(input.iter())?;
+    assert!(none.statistics.is_empty());
+    assert_eq!(none.points, input);
+
+    let duplicated: Vec<Point<i32>> = vec![[1, 1].into(), [5, 5].into(), [1, 1].into()];
+    assert!(matches!(
+        LloydRelaxation::<i32, f64>::new(region).relax(duplicated.iter()),
+        Err(BvError::ValueError(_))
+    ));
+    Ok(())
+}
@@ -0,0 +1,166 @@
+use boostvoronoi::prelude::*;
+use boostvoronoi::{Aabb2, ConvexRegion, DiagramClipper};
+
+/// The signed area and the perimeter of a polygon
+fn area_and_perimeter(polygon: &[[f64; 2]]) -> (f64, f64) {
+    let n = polygon.len();
+    (0..n).fold((0.0, 0.0), |(area, perimeter), i| {
+        let (a, b) = (polygon[i], polygon[(i + 1) % n]);
+        (
+            area + (a[0] * b[1] - a[1] * b[0]) * 0.5,
+            perimeter + (b[0] - a[0]).hypot(b[1] - a[1]),
+        )
+    })
+}
+
+#[test]
+/// The measured cells tile the region, and agree with finely discretized cell polygons
+fn measure_test_1() -> Result<(), BvError> {
+    let points: Vec<Point<i32>> = vec![
+        [5, 5].into(),
+        [50, 60].into(),
+        [80, 20].into(),
+        [30, 90].into(),
+        [95, 95].into(),
+    ];
+    let segments: Vec<Line<i32>> = vec![
+        [10, 30, 40, 40].into(),
+        [40, 40, 60, 10].into(),
+        [70, 70, 90, 50].into(),
+        [20, 60, 20, 80].into(),
+    ];
+    let diagram = Builder::<i32, f64>::default()
+        .with_vertices(points.iter())?
+        .with_segments(segments.iter())?
+        .build()?;
+    for region in [
+        ConvexRegion::from_aabb(&Aabb2::new_from_i32::<i32>(-50, -40, 150, 130))?,
+        ConvexRegion::from_aabb(&Aabb2::new_from_i32::<i32>(20, 20, 70, 70))?,
+        ConvexRegion::new(vec![[0.0, 0.0], [60.0, 110.0], [120.0, -10.0]])?,
+    ] {
+        let (region_area, _) = area_and_perimeter(region.vertices());
+        let clipper = DiagramClipper::new(&diagram, &points, &segments, region);
+        let measures = clipper.cell_measures()?;
+        let polygons = clipper.cell_polygons(1e-6)?;
+        assert_eq!(measures.len(), diagram.cells().len());
+        let mut total = 0.0;
+        let mut moment = [0.0, 0.0];
+        for (measure, polygon) in measures.iter().zip(polygons.iter()) {
+            let (area, perimeter) = area_and_perimeter(polygon);
+            match measure {
+                Some(m) => {
+                    assert!((m.area - area).abs() < 1e-4, "{:?} {}", m, area);
+                    assert!(
+                        (m.perimeter - perimeter).abs() < 1e-4,
+                        "{:?} {}",
+                        m,
+                        perimeter
+                    );
+                    total += m.area;
+                    moment[0] += m.area * m.centroid[0];
+                    moment[1] += m.area * m.centroid[1];
+                }
+                None => assert!(area < 1e-9),
+            }
+        }
+        assert!((total - region_area).abs() < 1e-9 * region_area);
+        // the cells add up to the centroid of the region
+        let n = clipper.region().vertices().len() as f64;
+        let vertex_sum = clipper
+            .region()
+            .vertices()
+            .iter()
+            .fold([0.0, 0.0], |s, v| [s[0] + v[0], s[1] + v[1]]);
+        if n == 4.0 {
+            // the average of the corners of a rectangle is its centroid
+            assert!((moment[0] / total - vertex_sum[0] / n).abs() < 1e-9);
+            assert!((moment[1] / total - vertex_sum[1] / n).abs() < 1e-9);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+/// A point above a segment: the cells are separated by the parabola `y = x²/20 + 5`
+fn measure_test_2() -> Result<(), BvError> {
+    let points: Vec<Point<i32>> = vec![[0, 10].into()];
+    let segments: Vec<Line<i32>> = vec![[-100, 0, 100, 0].into()];
+    let diagram = Builder::<i32, f64>::default()
+        .with_vertices(points.iter())?
+        .with_segments(segments.iter())?
+        .build()?;
+    let region = ConvexRegion::new(vec![[-20.0, 1.0], [20.0, 1.0], [20.0, 40.0], [-20.0, 40.0]])?;
+    let clipper = DiagramClipper::new(&diagram, &points, &segments, region);
+    let mut below = None;
+    let mut above = None;
+    for (c, measure) in clipper.cell_measures()?.into_iter().enumerate() {
+        let cell = diagram.get_cell(CellIndex(c))?.get();
+        match (cell.contains_point(), measure) {
+            (true, Some(m)) => above = Some(m),
+            (false, Some(m)) if cell.contains_segment() => below = Some(m),
+            (_, None) => (),
+            (_, m) => panic!("{:?}", m),
+        }
+    }
+    let (below, above) = (below.unwrap(), above.unwrap());
+    // ∫(x²/20 + 4)dx from -20 to 20
+    let area = 16000.0 / 60.0 + 160.0;
+    assert!((below.area - area).abs() < 1e-9, "{:?}", below);
+    assert!(
+        (above.area - (40.0 * 39.0 - area)).abs() < 1e-9,
+        "{:?}",
+        above
+    );
+    // ∫((x²/20 + 5)² - 1)/2 dx from -20 to 20
+    let moment = (2.0 * (1600.0 + 8000.0 / 6.0 + 500.0) - 40.0) * 0.5;
+    assert!(below.centroid[0].abs() < 1e-9);
+    assert!(
+        (below.centroid[1] - moment / area).abs() < 1e-9,
+        "{:?}",
+        below
+    );
+    // the arc length of the parabola, and the three straight sides
+    let arc = 10.0 * (2.0 * 5.0_f64.sqrt() + 2.0_f64.asinh());
+    assert!((below.perimeter - (arc + 40.0 + 2.0 * 24.0)).abs() < 1e-9);
+    assert!((above.perimeter - (arc + 40.0 + 2.0 * 15.0)).abs() < 1e-9);
+    Ok(())
+}
+
+#[test]
+/// Collinear points give infinite strips, a lone point owns the whole region
+fn measure_test_3() -> Result<(), BvError> {
+    let region = ConvexRegion::from_aabb(&Aabb2::new_from_i32::<i32>(-10, -10, 40, 10))?;
+    let points: Vec<Point<i32>> = vec![[0, 0].into(), [10, 0].into(), [20, 0].into()];
+    let diagram = Builder::<i32, f64>::default()
+        .with_vertices(points.iter())?
+        .build()?;
+    let clipper = DiagramClipper::new(&diagram, &points, &[], region.clone());
+    let measures: Vec<_> = clipper
+        .cell_measures()?
+        .into_iter()
+        .map(|m| m.unwrap())
+        .collect();
+    for (m, (area, x)) in measures
+        .iter()
+        .zip([(300.0, -2.5), (200.0, 10.0), (500.0, 27.5)])
+    {
+        assert!((m.area - area).abs() < 1e-9, "{:?}", m);
+        assert!((m.centroid[0] - x).abs() < 1e-9 && m.centroid[1].abs() < 1e-9);
+        assert!((m.perimeter - (2.0 * area / 20.0 + 40.0)).abs() < 1e-9);
+        // a w*h rectangle has the polar moment A(w² + h²)/12
+        let width = area / 20.0;
+        let inertia = area * (width * width + 400.0) / 12.0;
+        assert!((m.inertia - inertia).abs() < 1e-9 * inertia, "{:?}", m);
+    }
+
+    let points: Vec<Point<i32>> = vec![[100, 100].into()];
+    let diagram = Builder::<i32, f64>::default()
+        .with_vertices(points.iter())?
+        .build()?;
+    let clipper = DiagramClipper::new(&diagram, &points, &[], region);
+    let m = clipper.cell_measure(CellIndex(0))?.unwrap();
+    assert!((m.area - 1000.0).abs() < 1e-9 && (m.perimeter - 140.0).abs() < 1e-9);
+    assert!((m.centroid[0] - 15.0).abs() < 1e-9 && m.centroid[1].abs() < 1e-9);
+    assert!((m.inertia - 1000.0 * 2900.0 / 12.0).abs() < 1e-6);
+    Ok(())
+}
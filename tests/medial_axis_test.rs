@@ -0,0 +1,108 @@
+use boostvoronoi::prelude::*;
+use boostvoronoi::{MedialAxis, Polygon};
+
+mod common;
+use common::{clearance, inside};
+
+/// Checks the clearance of all vertices, and that every edge is inside the polygon
+fn check(medial_axis: &MedialAxis<i32, f64>, rings: &[&[[i32; 2]]]) {
+    for v in medial_axis.vertices() {
+        assert!(
+            (v.radius - clearance(v.position, rings)).abs() < 1e-6,
+            "{:?}",
+            v
+        );
+    }
+    for e in medial_axis.edges() {
+        let (p0, p1) = (
+            medial_axis.vertices()[e.vertices[0]].position,
+            medial_axis.vertices()[e.vertices[1]].position,
+        );
+        let middle = match &e.curve {
+            Some(arc) => {
+                assert_eq!((arc.start, arc.end), (p0, p1));
+                arc.to_bezier().point_at(0.5)
+            }
+            None => [(p0[0] + p1[0]) * 0.5, (p0[1] + p1[1]) * 0.5],
+        };
+        assert!(inside(middle, rings), "{:?}", e);
+    }
+}
+
+#[test]
+/// A rectangle: the corner bisectors and the middle line
+fn medial_axis_test_1() -> Result<(), BvError> {
+    let ring = [[0, 0], [100, 0], [100, 50], [0, 50]];
+    for ring in [ring.to_vec(), ring.iter().rev().copied().collect()] {
+        let medial_axis =
+            MedialAxis::<i32, f64>::from_polygons([Polygon::from(ring.clone())], false)?;
+        check(&medial_axis, &[&ring]);
+        assert_eq!(medial_axis.vertices().len(), 6);
+        assert_eq!(medial_axis.edges().len(), 5);
+        assert!(medial_axis.edges().iter().all(|e| e.curve.is_none()));
+        let mut inner: Vec<[f64; 2]> = medial_axis
+            .vertices()
+            .iter()
+            .filter(|v| v.radius > 0.0)
+            .map(|v| v.position)
+            .collect();
+        inner.sort_by(|a, b| a[0].total_cmp(&b[0]));
+        assert_eq!(inner, vec![[25.0, 25.0], [75.0, 25.0]]);
+    }
+    Ok(())
+}
+
+#[test]
+/// An L shape: the reflex corner is only touched by secondary edges
+fn medial_axis_test_2() -> Result<(), BvError> {
+    let ring = [[0, 0], [100, 0], [100, 40], [40, 40], [40, 100], [0, 100]];
+    let reflex = [40.0, 40.0];
+    let medial_axis = MedialAxis::<i32, f64>::from_polygons([Polygon::from(ring.to_vec())], false)?;
+    check(&medial_axis, &[&ring]);
+    assert!(medial_axis.vertices().iter().all(|v| v.position != reflex));
+    assert!(medial_axis.edges().iter().all(|e| !e.is_secondary));
+    assert!(medial_axis.edges().iter().any(|e| e.curve.is_some()));
+
+    let with_secondary =
+        MedialAxis::<i32, f64>::from_polygons([Polygon::from(ring.to_vec())], true)?;
+    check(&with_secondary, &[&ring]);
+    let secondary: Vec<_> = with_secondary
+        .edges()
+        .iter()
+        .filter(|e| e.is_secondary)
+        .collect();
+    assert_eq!(secondary.len(), 2);
+    for e in secondary {
+        assert!(e
+            .vertices
+            .iter()
+            .any(|v| with_secondary.vertices()[*v].position == reflex));
+    }
+    assert_eq!(with_secondary.edges().len(), medial_axis.edges().len() + 2);
+    Ok(())
+}
+
+#[test]
+/// A square with a hole, and a second polygon
+fn medial_axis_test_3() -> Result<(), BvError> {
+    let exterior = [[0, 0], [100, 0], [100, 100], [0, 100]];
+    let hole = [[30, 30], [60, 30], [70, 60], [30, 70]];
+    let triangle = [[200, 0], [300, 0], [250, 80]];
+    let polygons = [
+        Polygon::new(
+            exterior.iter().map(|p| (*p).into()).collect(),
+            vec![hole.iter().map(|p| (*p).into()).collect()],
+        ),
+        Polygon::from(triangle.to_vec()),
+    ];
+    let medial_axis = MedialAxis::<i32, f64>::from_polygons(polygons, false)?;
+    check(&medial_axis, &[&exterior, &hole, &triangle]);
+    // the triangle has one inner vertex, the incenter
+    let in_triangle = medial_axis
+        .vertices()
+        .iter()
+        .filter(|v| v.position[0] > 150.0 && v.radius > 0.0)
+        .count();
+    assert_eq!(in_triangle, 1);
+    Ok(())
+}
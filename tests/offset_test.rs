@@ -0,0 +1,181 @@
+use boostvoronoi::prelude::*;
+use boostvoronoi::{OffsetElement, OffsetLoop, Offsetter, Polygon};
+
+mod common;
+use common::clearance;
+
+/// The signed area of a loop, counter clockwise is positive
+fn area(offset_loop: &OffsetLoop<f64>) -> f64 {
+    offset_loop
+        .elements
+        .iter()
+        .map(|e| {
+            let (s, t) = (e.start(), e.end());
+            let chord = (s[0] * t[1] - t[0] * s[1]) * 0.5;
+            match *e {
+                OffsetElement::Line { .. } => chord,
+                OffsetElement::Arc { center, ccw, .. } => {
+                    // add the circular segment between the chord and the arc
+                    let r = (s[0] - center[0]).hypot(s[1] - center[1]);
+                    let a0 = (s[1] - center[1]).atan2(s[0] - center[0]);
+                    let a1 = (t[1] - center[1]).atan2(t[0] - center[0]);
+                    let mut sweep = if ccw { a1 - a0 } else { a0 - a1 };
+                    if sweep < 0.0 {
+                        sweep += std::f64::consts::TAU;
+                    }
+                    let segment = r * r * (sweep - sweep.sin()) * 0.5;
+                    chord + if ccw { segment } else { -segment }
+                }
+            }
+        })
+        .sum()
+}
+
+/// Checks that the loops are closed, and that every element end point is at the offset
+/// distance from the rings
+fn check(loops: &[OffsetLoop<f64>], distance: f64, rings: &[&[[i32; 2]]]) {
+    for offset_loop in loops {
+        let n = offset_loop.elements.len();
+        for i in 0..n {
+            let (e, next) = (offset_loop.elements[i], offset_loop.elements[(i + 1) % n]);
+            assert_eq!(e.end(), next.start());
+            assert!(
+                (clearance(e.start(), rings) - distance.abs()).abs() < 1e-6,
+                "{:?}",
+                e
+            );
+            if let OffsetElement::Arc {
+                start, end, center, ..
+            } = e
+            {
+                let r0 = (start[0] - center[0]).hypot(start[1] - center[1]);
+                let r1 = (end[0] - center[0]).hypot(end[1] - center[1]);
+                assert!((r0 - distance.abs()).abs() < 1e-6 && (r1 - r0).abs() < 1e-6);
+            }
+        }
+    }
+}
+
+#[test]
+/// A square, offset inwards and outwards
+fn offset_test_1() -> Result<(), BvError> {
+    let ring = [[0, 0], [100, 0], [100, 100], [0, 100]];
+    for ring in [ring.to_vec(), ring.iter().rev().copied().collect()] {
+        let offsetter = Offsetter::<i32, f64>::from_polygons([Polygon::from(ring.clone())])?;
+        let inwards = offsetter.offset(-10.0)?;
+        check(&inwards, -10.0, &[&ring]);
+        assert_eq!(inwards.len(), 1);
+        assert!(inwards[0]
+            .elements
+            .iter()
+            .all(|e| matches!(e, OffsetElement::Line { .. })));
+        assert!((area(&inwards[0]) - 80.0 * 80.0).abs() < 1e-6);
+        for e in inwards[0].elements.iter() {
+            for c in e.start() {
+                assert!(
+                    (c - 10.0).abs() < 1e-9 || (c - 90.0).abs() < 1e-9,
+                    "{:?}",
+                    e
+                );
+            }
+        }
+
+        let outwards = offsetter.offset(10.0)?;
+        check(&outwards, 10.0, &[&ring]);
+        assert_eq!(outwards.len(), 1);
+        let expected = 100.0 * 100.0 + 4.0 * 100.0 * 10.0 + std::f64::consts::PI * 10.0 * 10.0;
+        assert!((area(&outwards[0]) - expected).abs() < 1e-6);
+
+        assert!(offsetter.offset(-50.5)?.is_empty());
+        assert!(offsetter.offset(0.0).is_err());
+        assert!(offsetter.offset(f64::NAN).is_err());
+    }
+    Ok(())
+}
+
+#[test]
+/// A dumbbell, the inward offset splits into two loops when the neck is too thin
+fn offset_test_2() -> Result<(), BvError> {
+    let ring = [
+        [0, 0],
+        [100, 0],
+        [100, 40],
+        [200, 40],
+        [200, 0],
+        [300, 0],
+        [300, 100],
+        [200, 100],
+        [200, 60],
+        [100, 60],
+        [100, 100],
+        [0, 100],
+    ];
+    let offsetter = Offsetter::<i32, f64>::from_polygons([Polygon::from(ring.to_vec())])?;
+    let loops = offsetter.offset(-5.0)?;
+    check(&loops, -5.0, &[&ring]);
+    assert_eq!(loops.len(), 1);
+    assert!(area(&loops[0]) > 0.0);
+    // the inward offset turns clockwise around the four reflex corners
+    let clockwise = loops[0]
+        .elements
+        .iter()
+        .filter(|e| matches!(e, OffsetElement::Arc { ccw: false, .. }))
+        .count();
+    assert_eq!(clockwise, 4);
+
+    let loops = offsetter.offset(-15.0)?;
+    check(&loops, -15.0, &[&ring]);
+    assert_eq!(loops.len(), 2);
+    // the two halves are mirror images, bulging a bit into the neck
+    let (a0, a1) = (area(&loops[0]), area(&loops[1]));
+    assert!((a0 - a1).abs() < 1e-6, "{} {}", a0, a1);
+    assert!(a0 > 70.0 * 70.0 && a0 < 70.0 * 85.0);
+
+    // the outward offset only rounds the convex corners
+    let loops = offsetter.offset(15.0)?;
+    check(&loops, 15.0, &[&ring]);
+    assert_eq!(loops.len(), 1);
+    let arcs: Vec<_> = loops[0]
+        .elements
+        .iter()
+        .filter_map(|e| match e {
+            OffsetElement::Arc { ccw, .. } => Some(*ccw),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(arcs, vec![true; 8]);
+    Ok(())
+}
+
+#[test]
+/// A square with a hole: the hole shrinks outwards and grows inwards
+fn offset_test_3() -> Result<(), BvError> {
+    let exterior = [[0, 0], [100, 0], [100, 100], [0, 100]];
+    let hole = [[40, 40], [60, 40], [60, 60], [40, 60]];
+    let polygon = Polygon::new(
+        exterior.iter().map(|p| (*p).into()).collect(),
+        vec![hole.iter().map(|p| (*p).into()).collect()],
+    );
+    let offsetter = Offsetter::<i32, f64>::from_polygons([polygon])?;
+
+    let mut loops = offsetter.offset(-10.0)?;
+    check(&loops, -10.0, &[&exterior, &hole]);
+    assert_eq!(loops.len(), 2);
+    loops.sort_by(|a, b| area(a).total_cmp(&area(b)));
+    // the hole grows into a rounded square, clockwise
+    let expected = 20.0 * 20.0 + 4.0 * 20.0 * 10.0 + std::f64::consts::PI * 10.0 * 10.0;
+    assert!((area(&loops[0]) + expected).abs() < 1e-6);
+    assert!((area(&loops[1]) - 80.0 * 80.0).abs() < 1e-6);
+
+    let mut loops = offsetter.offset(5.0)?;
+    check(&loops, 5.0, &[&exterior, &hole]);
+    assert_eq!(loops.len(), 2);
+    loops.sort_by(|a, b| area(a).total_cmp(&area(b)));
+    assert!((area(&loops[0]) + 10.0 * 10.0).abs() < 1e-6);
+
+    // the hole disappears when offset outwards past its half width
+    let loops = offsetter.offset(11.0)?;
+    check(&loops, 11.0, &[&exterior, &hole]);
+    assert_eq!(loops.len(), 1);
+    Ok(())
+}
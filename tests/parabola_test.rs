@@ -0,0 +1,115 @@
+use boostvoronoi::prelude::*;
+use boostvoronoi::{ParabolicArc, QuadraticBezier, SimpleAffine, VoronoiVisualUtils};
+
+fn distance_to_line(p: [f64; 2], line: [[f64; 2]; 2]) -> f64 {
+    let d = [line[1][0] - line[0][0], line[1][1] - line[0][1]];
+    ((p[0] - line[0][0]) * d[1] - (p[1] - line[0][1]) * d[0]).abs() / d[0].hypot(d[1])
+}
+
+fn distance(a: [f64; 2], b: [f64; 2]) -> f64 {
+    (a[0] - b[0]).hypot(a[1] - b[1])
+}
+
+#[test]
+/// The Bézier curves of the curved edges should follow the parabolas exactly
+fn parabola_test_1() -> Result<(), BvError> {
+    let points: Vec<Point<i32>> = vec![[50, 50].into(), [20, 80].into(), [300, 10].into()];
+    let segments: Vec<Line<i32>> = vec![
+        [0, 0, 100, 0].into(),
+        [0, 100, 100, 100].into(),
+        [100, 0, 100, 100].into(),
+        [250, 40, 350, 30].into(),
+    ];
+    let diagram = Builder::<i32, f64>::default()
+        .with_vertices(points.iter())?
+        .with_segments(segments.iter())?
+        .build()?;
+    let mut curved_edges = 0;
+    for edge_id in (0..diagram.edges().len()).map(EdgeIndex) {
+        let arc = match ParabolicArc::from_edge(&diagram, edge_id, &points, &segments)? {
+            Some(arc) => arc,
+            None => {
+                assert!(!diagram.get_edge(edge_id)?.get().is_curved());
+                continue;
+            }
+        };
+        curved_edges += 1;
+        let edge = diagram.get_edge(edge_id)?.get();
+        let v0 = diagram.vertex_get(edge.vertex0().unwrap())?.get();
+        assert_eq!(arc.start, [v0.x(), v0.y()]);
+
+        let bezier = arc.to_bezier();
+        assert_eq!(bezier.point_at(0.0), arc.start);
+        assert_eq!(bezier.point_at(1.0), arc.end);
+        let c = arc.conic();
+        let scale = distance(arc.start, arc.end).max(1.0);
+        for t in (0..=20).map(|t| t as f64 / 20.0) {
+            let p = bezier.point_at(t);
+            let d1 = distance(p, arc.focus);
+            let d2 = distance_to_line(p, arc.directrix);
+            assert!((d1 - d2).abs() < 1e-9 * scale, "{:?} {} {}", p, d1, d2);
+            let implicit = c[0] * p[0] * p[0]
+                + c[1] * p[0] * p[1]
+                + c[2] * p[1] * p[1]
+                + c[3] * p[0]
+                + c[4] * p[1]
+                + c[5];
+            assert!(implicit.abs() < 1e-6 * scale * scale, "{}", implicit);
+        }
+
+        // the discretized edge should be close to the curve
+        let mut discretization = vec![arc.start, arc.end];
+        let focus: Point<i32> = [arc.focus[0] as i32, arc.focus[1] as i32].into();
+        let directrix: Line<i32> = [
+            arc.directrix[0][0] as i32,
+            arc.directrix[0][1] as i32,
+            arc.directrix[1][0] as i32,
+            arc.directrix[1][1] as i32,
+        ]
+        .into();
+        VoronoiVisualUtils::discretize(
+            &focus,
+            &directrix,
+            0.01,
+            &SimpleAffine::default(),
+            &mut discretization,
+        );
+        for p in discretization {
+            let closest = (0..=1000)
+                .map(|t| distance(p, bezier.point_at(t as f64 / 1000.0)))
+                .fold(f64::MAX, f64::min);
+            assert!(closest < 0.02 + scale / 1000.0, "{:?} {}", p, closest);
+        }
+    }
+    assert!(curved_edges > 0);
+    Ok(())
+}
+
+#[test]
+/// Rational Bézier evaluation, and invalid parabolas
+fn parabola_test_2() -> Result<(), BvError> {
+    // a quarter circle
+    let w = std::f64::consts::FRAC_1_SQRT_2;
+    let bezier = QuadraticBezier {
+        control_points: [[1.0, 0.0], [1.0, 1.0], [0.0, 1.0]],
+        weights: [1.0, w, 1.0],
+    };
+    for t in (0..=10).map(|t| t as f64 / 10.0) {
+        let p = bezier.point_at(t);
+        assert!((p[0].hypot(p[1]) - 1.0).abs() < 1e-12);
+    }
+
+    let focus: Point<i32> = [0, 1].into();
+    let directrix: Line<i32> = [-1, 0, 1, 0].into();
+    let arc = ParabolicArc::new(&focus, &directrix, [-2.0, 2.5], [2.0, 2.5])?;
+    // y = (x² + 1) / 2
+    assert_eq!(arc.to_bezier().control_points[1], [0.0, -1.5]);
+    assert_eq!(arc.to_bezier().point_at(0.5), [0.0, 0.5]);
+    assert_eq!(arc.conic(), [1.0, 0.0, 0.0, 0.0, -2.0, 1.0]);
+
+    let on_the_line: Point<i32> = [5, 0].into();
+    assert!(ParabolicArc::new(&on_the_line, &directrix, [0.0, 0.0], [1.0, 1.0]).is_err());
+    let zero_length: Line<i32> = [1, 0, 1, 0].into();
+    assert!(ParabolicArc::new(&focus, &zero_length, [0.0, 0.0], [1.0, 1.0]).is_err());
+    Ok(())
+}
@@ -0,0 +1,185 @@
+use boostvoronoi::prelude::*;
+use boostvoronoi::PathPlanner;
+
+fn distance_to_segment(q: [f64; 2], a: [f64; 2], b: [f64; 2]) -> f64 {
+    let (bx, by) = (b[0] - a[0], b[1] - a[1]);
+    let length = bx * bx + by * by;
+    let t = if length > 0.0 {
+        (((q[0] - a[0]) * bx + (q[1] - a[1]) * by) / length).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    (q[0] - a[0] - t * bx).hypot(q[1] - a[1] - t * by)
+}
+
+/// The smallest distance between the sampled polyline and any site
+fn clearance(polyline: &[[f64; 2]], points: &[Point<i32>], segments: &[Line<i32>]) -> f64 {
+    let mut sites: Vec<([f64; 2], [f64; 2])> = points
+        .iter()
+        .map(|p| ([p.x as f64, p.y as f64], [p.x as f64, p.y as f64]))
+        .collect();
+    sites.extend(segments.iter().map(|s| {
+        (
+            [s.start.x as f64, s.start.y as f64],
+            [s.end.x as f64, s.end.y as f64],
+        )
+    }));
+    let mut rv = f64::MAX;
+    for w in polyline.windows(2) {
+        for i in 0..=100 {
+            let t = i as f64 / 100.0;
+            let q = [
+                w[0][0] + (w[1][0] - w[0][0]) * t,
+                w[0][1] + (w[1][1] - w[0][1]) * t,
+            ];
+            for (a, b) in sites.iter() {
+                rv = rv.min(distance_to_segment(q, *a, *b));
+            }
+        }
+    }
+    rv
+}
+
+fn polyline_length(polyline: &[[f64; 2]]) -> f64 {
+    polyline
+        .windows(2)
+        .map(|w| (w[1][0] - w[0][0]).hypot(w[1][1] - w[0][1]))
+        .sum()
+}
+
+#[test]
+/// A corridor with a wall in the middle, the robot passes on either side of the wall
+fn path_planning_test_1() -> Result<(), BvError> {
+    let points: Vec<Point<i32>> = vec![];
+    let segments: Vec<Line<i32>> = vec![
+        [0, 0, 100, 0].into(),
+        [0, 30, 50, 30].into(),
+        [50, 30, 100, 30].into(),
+        [50, 8, 50, 30].into(),
+    ];
+    let diagram = Builder::<i32, f64>::default()
+        .with_segments(segments.iter())?
+        .build()?;
+    let planner = PathPlanner::new(&diagram, &points, &segments)?;
+    let (start, goal) = ([10.0, 15.0], [90.0, 15.0]);
+    for radius in [0.5, 2.0, 3.9] {
+        let path = planner.shortest_path(start, goal, radius, 0.01)?.unwrap();
+        assert_eq!(path.points.first(), Some(&start));
+        assert_eq!(path.points.last(), Some(&goal));
+        assert!(path.clearance >= radius);
+        assert!(clearance(&path.points, &points, &segments) >= radius - 1e-9);
+        assert!(path.length >= 80.0);
+        // the polyline is a chord approximation of the exact length
+        let approximation = polyline_length(&path.points);
+        assert!(approximation <= path.length + 1e-9);
+        assert!(path.length - approximation < 0.1, "{:?}", path);
+        // the robot has to go below the wall
+        assert!(path
+            .points
+            .iter()
+            .all(|p| (p[0] - 50.0).abs() > 1.0 || p[1] < 8.0));
+    }
+    // the gap below the wall is 8 wide, the robot has to leave the corridor and go around it
+    let path = planner.shortest_path(start, goal, 4.1, 0.01)?.unwrap();
+    assert!(path.clearance >= 4.1);
+    assert!(clearance(&path.points, &points, &segments) >= 4.1 - 1e-9);
+    assert!(path.points.iter().any(|p| p[1] < 0.0 || p[1] > 30.0));
+    // the open ends of the corridor are 30 wide
+    assert!(planner.shortest_path(start, goal, 15.1, 0.01)?.is_none());
+    Ok(())
+}
+
+#[test]
+/// Point obstacles: a straight line when nothing is in the way, otherwise the roadmap
+fn path_planning_test_2() -> Result<(), BvError> {
+    let mut points = Vec::<Point<i32>>::new();
+    for x in 0..6 {
+        for y in 0..6 {
+            points.push([x * 20, y * 20].into());
+        }
+    }
+    let segments: Vec<Line<i32>> = vec![];
+    let diagram = Builder::<i32, f64>::default()
+        .with_vertices(points.iter())?
+        .build()?;
+    let planner = PathPlanner::new(&diagram, &points, &segments)?;
+
+    // start and goal see each other
+    let path = planner
+        .shortest_path([10.0, 10.0], [12.0, 11.0], 5.0, 0.1)?
+        .unwrap();
+    assert_eq!(path.points, vec![[10.0, 10.0], [12.0, 11.0]]);
+
+    // the grid cells are 20 wide, the diagram edges are in the middle of the rows and columns
+    let (start, goal) = ([10.0, 10.0], [90.0, 70.0]);
+    let path = planner.shortest_path(start, goal, 9.0, 0.1)?.unwrap();
+    assert!((path.length - 140.0).abs() < 1e-9, "{:?}", path);
+    assert!(path.clearance >= 9.0);
+    assert!(clearance(&path.points, &points, &segments) >= 9.0 - 1e-9);
+    assert!(planner.shortest_path(start, goal, 10.5, 0.1)?.is_none());
+
+    assert!(matches!(
+        planner.shortest_path(start, goal, -1.0, 0.1),
+        Err(BvError::ValueError(_))
+    ));
+    Ok(())
+}
+
+#[test]
+/// A point above a wall makes the roadmap follow a parabola
+fn path_planning_test_3() -> Result<(), BvError> {
+    let points: Vec<Point<i32>> = vec![[50, 40].into()];
+    let segments: Vec<Line<i32>> = vec![
+        [0, 0, 100, 0].into(),
+        [0, 0, 0, 100].into(),
+        [100, 0, 100, 100].into(),
+    ];
+    let diagram = Builder::<i32, f64>::default()
+        .with_vertices(points.iter())?
+        .with_segments(segments.iter())?
+        .build()?;
+    let planner = PathPlanner::new(&diagram, &points, &segments)?;
+    // the straight line passes too close to the point
+    let (start, goal) = ([20.0, 45.0], [80.0, 45.0]);
+    let path = planner.shortest_path(start, goal, 12.0, 0.001)?.unwrap();
+    // the path passes below the point, over the apex of the parabola at (50, 20)
+    assert!(path.points.len() > 10);
+    let apex = path
+        .points
+        .iter()
+        .filter(|p| (p[0] - 50.0).abs() < 2.0)
+        .map(|p| p[1])
+        .fold(f64::MAX, f64::min);
+    assert!((apex - 20.0).abs() < 0.01, "{:?}", path);
+    assert!(path.clearance >= 12.0 && path.clearance <= 20.0 + 1e-9);
+    assert!(clearance(&path.points, &points, &segments) >= 12.0 - 1e-9);
+    let approximation = polyline_length(&path.points);
+    assert!(approximation <= path.length + 1e-9);
+    assert!(path.length - approximation < 0.01, "{:?}", path);
+    Ok(())
+}
+
+#[test]
+/// Single walls, the robot goes around the outside of the input
+fn path_planning_test_4() -> Result<(), BvError> {
+    let points: Vec<Point<i32>> = vec![];
+    for segments in [
+        vec![Line::from([0, 0, 100, 0])],
+        vec![Line::from([0, 0, 100, 0]), Line::from([100, 0, 100, 100])],
+    ] {
+        let diagram = Builder::<i32, f64>::default()
+            .with_segments(segments.iter())?
+            .build()?;
+        let planner = PathPlanner::new(&diagram, &points, &segments)?;
+        let (start, goal) = ([50.0, 10.0], [50.0, -10.0]);
+        let path = planner.shortest_path(start, goal, 1.0, 0.01)?.unwrap();
+        assert_eq!(path.points.first(), Some(&start));
+        assert_eq!(path.points.last(), Some(&goal));
+        assert!(path.clearance >= 1.0);
+        assert!(clearance(&path.points, &points, &segments) >= 1.0 - 1e-9);
+        assert!((polyline_length(&path.points) - path.length).abs() < 1e-9);
+        // the path passes one of the ends of the wall
+        assert!(path.points.iter().any(|p| p[0] < 0.0 || p[0] > 100.0));
+    }
+    Ok(())
+}
@@ -0,0 +1,217 @@
+use boostvoronoi::prelude::*;
+use boostvoronoi::{
+    ConvexRegion, DiagramClipper, PathPlanner, PointLocator, SourceCategory, SyncDiagram,
+};
+
+mod common;
+use common::Lcg;
+
+fn distance_squared_to_point(q: [f64; 2], p: &Point<i32>) -> f64 {
+    let dx = q[0] - p.x as f64;
+    let dy = q[1] - p.y as f64;
+    dx * dx + dy * dy
+}
+
+fn distance_squared_to_segment(q: [f64; 2], l: &Line<i32>) -> f64 {
+    let (ax, ay) = (l.start.x as f64, l.start.y as f64);
+    let (bx, by) = (l.end.x as f64 - ax, l.end.y as f64 - ay);
+    let length = bx * bx + by * by;
+    let t = if length > 0.0 {
+        (((q[0] - ax) * bx + (q[1] - ay) * by) / length).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let dx = q[0] - ax - t * bx;
+    let dy = q[1] - ay - t * by;
+    dx * dx + dy * dy
+}
+
+/// The distance from `q` to the input site of `cell`
+fn distance_squared_to_cell(
+    q: [f64; 2],
+    cell: &Cell,
+    points: &[Point<i32>],
+    segments: &[Line<i32>],
+) -> f64 {
+    let (index, category) = cell.source_index_2();
+    match category {
+        SourceCategory::SinglePoint if index >= points.len() => {
+            distance_squared_to_point(q, &segments[index - points.len()].start)
+        }
+        SourceCategory::SinglePoint => distance_squared_to_point(q, &points[index]),
+        SourceCategory::SegmentStart => {
+            distance_squared_to_point(q, &segments[index - points.len()].start)
+        }
+        SourceCategory::SegmentEnd => {
+            distance_squared_to_point(q, &segments[index - points.len()].end)
+        }
+        SourceCategory::Segment => distance_squared_to_segment(q, &segments[index - points.len()]),
+    }
+}
+
+fn brute_force(q: [f64; 2], points: &[Point<i32>], segments: &[Line<i32>]) -> f64 {
+    points
+        .iter()
+        .map(|p| distance_squared_to_point(q, p))
+        .chain(segments.iter().map(|l| distance_squared_to_segment(q, l)))
+        .fold(f64::MAX, f64::min)
+}
+
+/// Random points on even rows, random horizontal segments on odd rows and a zigzag polyline
+fn input(rnd: &mut Lcg) -> (Vec<Point<i32>>, Vec<Line<i32>>) {
+    let points: Vec<Point<i32>> = (0..200)
+        .map(|_| [rnd.next(1000), 20 * rnd.next(50)].into())
+        .collect();
+    let mut segments: Vec<Line<i32>> = (0..50)
+        .map(|i| {
+            let x = rnd.next(900);
+            [x, 20 * i + 10, x + 1 + rnd.next(100), 20 * i + 10].into()
+        })
+        .collect();
+    let zigzag = [[0, -100], [100, -50], [200, -100], [300, -50], [400, -100]];
+    segments.extend(
+        zigzag
+            .windows(2)
+            .map(|w| Line::from([w[0][0], w[0][1], w[1][0], w[1][1]])),
+    );
+    (points, segments)
+}
+
+#[test]
+/// The located cell should always belong to (one of) the nearest input site(s)
+fn point_location_test_1() -> Result<(), BvError> {
+    let mut rnd = Lcg(7);
+    let (points, segments) = input(&mut rnd);
+    let diagram = Builder::<i32, f64>::default()
+        .with_vertices(points.iter())?
+        .with_segments(segments.iter())?
+        .build()?;
+    let locator = PointLocator::new(&diagram, &points, &segments)?;
+    assert_eq!(locator.num_cells(), diagram.cells().len());
+    let mut hint = CellIndex(0);
+    for _ in 0..5000 {
+        let q = [
+            (rnd.next(14000) - 2000) as f64 / 10.0,
+            (rnd.next(14000) - 3000) as f64 / 10.0,
+        ];
+        let expected = brute_force(q, &points, &segments);
+        let cell = locator.locate(q[0], q[1]).unwrap();
+        let d = distance_squared_to_cell(q, &diagram.get_cell(cell)?.get(), &points, &segments);
+        assert!((d - expected).abs() <= 1e-9 * expected.max(1.0), "{:?}", q);
+
+        hint = locator.locate_from(hint, q[0], q[1]).unwrap();
+        let d = distance_squared_to_cell(q, &diagram.get_cell(hint)?.get(), &points, &segments);
+        assert!((d - expected).abs() <= 1e-9 * expected.max(1.0), "{:?}", q);
+    }
+    Ok(())
+}
+
+#[test]
+/// Points beyond the end of a segment belong to the cell of the endpoint
+fn point_location_test_2() -> Result<(), BvError> {
+    let points: Vec<Point<i32>> = vec![];
+    let segments: Vec<Line<i32>> = vec![[0, 0, 10, 0].into(), [10, 0, 10, 10].into()];
+    let diagram: SyncDiagram<f64> = Builder::<i32, f64>::default()
+        .with_segments(segments.iter())?
+        .build_sync()?;
+    let locator = PointLocator::new_sync(&diagram, &points, &segments)?;
+    let category = |x: f64, y: f64| {
+        let cell = locator.locate(x, y).unwrap();
+        diagram.cell_get(cell).unwrap().source_index_2()
+    };
+    assert_eq!(category(-5.0, 1.0), (0, SourceCategory::SegmentStart));
+    assert_eq!(category(5.0, -1.0), (0, SourceCategory::Segment));
+    assert_eq!(category(15.0, -5.0), (0, SourceCategory::SegmentEnd));
+    assert_eq!(category(11.0, 5.0), (1, SourceCategory::Segment));
+    assert_eq!(category(5.0, 15.0), (1, SourceCategory::SegmentEnd));
+    Ok(())
+}
+
+#[test]
+/// Empty diagrams and single sites
+fn point_location_test_3() -> Result<(), BvError> {
+    let diagram = Builder::<i32, f64>::default().build()?;
+    let locator = PointLocator::<i32, f64>::new(&diagram, &[], &[])?;
+    assert!(locator.locate(1.0, 1.0).is_none());
+
+    let points: Vec<Point<i32>> = vec![[5, 5].into()];
+    let diagram = Builder::<i32, f64>::default()
+        .with_vertices(points.iter())?
+        .build()?;
+    let locator = PointLocator::<i32, f64>::new(&diagram, &points, &[])?;
+    assert_eq!(locator.locate(-100.0, 100.0), Some(CellIndex(0)));
+
+    // the input does not match the diagram
+    assert!(PointLocator::<i32, f64>::new(&diagram, &[], &[]).is_err());
+    Ok(())
+}
+
+#[test]
+/// A zero length segment is a point site, numbered as a segment
+fn point_location_test_4() -> Result<(), BvError> {
+    let points: Vec<Point<i32>> = vec![[50, 50].into()];
+    let segments: Vec<Line<i32>> = vec![[0, 0, 10, 0].into(), [20, 20, 20, 20].into()];
+    let diagram = Builder::<i32, f64>::default()
+        .with_vertices(points.iter())?
+        .with_segments(segments.iter())?
+        .build()?;
+    let locator = PointLocator::new(&diagram, &points, &segments)?;
+    let cell = locator.locate(21.0, 19.0).unwrap();
+    assert_eq!(
+        diagram.get_cell(cell)?.get().source_index_2(),
+        (2, SourceCategory::SinglePoint)
+    );
+    let mut rnd = Lcg(3);
+    for _ in 0..1000 {
+        let q = [rnd.next(800) as f64 / 10.0, rnd.next(800) as f64 / 10.0];
+        let cell = locator.locate(q[0], q[1]).unwrap();
+        let d = distance_squared_to_cell(q, &diagram.get_cell(cell)?.get(), &points, &segments);
+        let expected = brute_force(q, &points, &segments);
+        assert!((d - expected).abs() <= 1e-9 * expected.max(1.0), "{:?}", q);
+    }
+
+    // the other users of the input geometry accept the same input
+    let region = ConvexRegion::new(vec![
+        [-10.0, -10.0],
+        [70.0, -10.0],
+        [70.0, 70.0],
+        [-10.0, 70.0],
+    ])?;
+    let clipper = DiagramClipper::new(&diagram, &points, &segments, region);
+    let cell = locator.locate(20.0, 20.0).unwrap();
+    assert!(!clipper.cell_polygon(cell, 0.1)?.is_empty());
+    assert!(PathPlanner::new(&diagram, &points, &segments).is_ok());
+    Ok(())
+}
+
+#[test]
+/// A grid of cocircular sites, queried at the centres of the grid squares, where four sites are
+/// at the same distance
+fn point_location_test_5() -> Result<(), BvError> {
+    let points: Vec<Point<i32>> = (0..30)
+        .flat_map(|x| (0..30).map(move |y| [x * 10, y * 10].into()))
+        .collect();
+    let diagram = Builder::<i32, f64>::default()
+        .with_vertices(points.iter())?
+        .build()?;
+    let locator = PointLocator::new(&diagram, &points, &[])?;
+    let mut hint = locator.locate(0.0, 0.0).unwrap();
+    for x in 0..29 {
+        for y in 0..29 {
+            let q = [x as f64 * 10.0 + 5.0, y as f64 * 10.0 + 5.0];
+            let expected = brute_force(q, &points, &[]);
+            let cell = locator.locate(q[0], q[1]).unwrap();
+            let d = distance_squared_to_cell(q, &diagram.get_cell(cell)?.get(), &points, &[]);
+            assert!((d - expected).abs() <= 1e-9 * expected, "{:?}", q);
+
+            // start far away, on the other side of the grid
+            hint = locator
+                .locate_from(hint, 290.0 - q[0], 290.0 - q[1])
+                .unwrap();
+            let cell = locator.locate_from(hint, q[0], q[1]).unwrap();
+            let d = distance_squared_to_cell(q, &diagram.get_cell(cell)?.get(), &points, &[]);
+            assert!((d - expected).abs() <= 1e-9 * expected, "{:?}", q);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,103 @@
+use boostvoronoi::prelude::*;
+use boostvoronoi::{PathElement, PathSource, SourceCategory, SyncDiagram};
+
+#[test]
+/// A square with a square hole, followed by a polyline
+fn polygon_test_1() -> Result<(), BvError> {
+    let exterior = vec![
+        Point::new(0, 0),
+        Point::new(100, 0),
+        Point::new(100, 100),
+        Point::new(0, 100),
+        // an explicitly closed ring
+        Point::new(0, 0),
+    ];
+    let hole = vec![
+        Point::new(40, 40),
+        Point::new(40, 60),
+        Point::new(60, 60),
+        Point::new(60, 40),
+    ];
+    let output = Builder::<i32, f64>::default()
+        .with_vertices([Point::new(20, 20)].iter())?
+        .with_polygons([Polygon::new(exterior, vec![hole])])?
+        .with_polylines([vec![[200, 0], [210, 10], [220, 0]]])?
+        .build()?;
+
+    let mut found = Vec::new();
+    for cell in output.cells().iter() {
+        let cell = cell.get();
+        let source = output.cell_path_source(cell.id())?;
+        match cell.source_index_2() {
+            (0, _) => assert_eq!(source, None),
+            (source_index, category) => {
+                let expected = match source_index {
+                    1..=4 => (0, source_index - 1),
+                    5..=8 => (1, source_index - 5),
+                    _ => (2, source_index - 9),
+                };
+                let element = match category {
+                    SourceCategory::Segment => PathElement::Edge(expected.1),
+                    SourceCategory::SegmentStart => PathElement::Vertex(expected.1),
+                    SourceCategory::SegmentEnd if expected.0 < 2 => {
+                        PathElement::Vertex((expected.1 + 1) % 4)
+                    }
+                    _ => PathElement::Vertex(expected.1 + 1),
+                };
+                let expected = if expected.0 < 2 {
+                    PathSource::Polygon {
+                        polygon: 0,
+                        ring: expected.0,
+                        element,
+                    }
+                } else {
+                    PathSource::Polyline {
+                        polyline: 0,
+                        element,
+                    }
+                };
+                assert_eq!(source, Some(expected));
+                found.push(expected);
+            }
+        }
+    }
+    // every ring vertex and edge is found exactly once
+    assert_eq!(found.len(), 8 + 8 + 5);
+    found.sort_by_key(|f| format!("{:?}", f));
+    found.dedup();
+    assert_eq!(found.len(), 8 + 8 + 5);
+
+    // the same information is available from the SyncDiagram
+    let cell_id = output.cells()[1].get().id();
+    let expected = output.cell_path_source(cell_id)?;
+    let sync_output = SyncDiagram::from(output);
+    assert_eq!(sync_output.cell_path_source(cell_id)?, expected);
+    Ok(())
+}
+
+#[test]
+fn polygon_test_2() {
+    // a ring must have at least three vertices
+    let rv = Builder::<i32, f64>::default().with_polygons([vec![[0, 0], [10, 0], [0, 0]]]);
+    assert!(rv.is_err());
+    let rv = Builder::<i32, f64>::default().with_polylines([vec![[0, 0]]]);
+    assert!(rv.is_err());
+}
+
+#[cfg(feature = "geo")]
+#[test]
+/// `with_polygons()` and `with_polylines()` accepts `geo` types
+fn polygon_test_3() -> Result<(), BvError> {
+    use boostvoronoi::geo;
+    let polygon = geo::Polygon::new(
+        geo::LineString::from(vec![(0, 0), (10, 0), (10, 10), (0, 0)]),
+        vec![],
+    );
+    let polyline = geo::LineString::from(vec![(20, 0), (30, 10)]);
+    let output = Builder::<i32, f64>::default()
+        .with_polygons([&polygon])?
+        .with_polylines([&polyline])?
+        .build()?;
+    assert_eq!(output.cells().len(), 3 + 3 + 3);
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use boostvoronoi::prelude::*;
+use boostvoronoi::{BuildProgress, SyncDiagram};
+use std::cell::RefCell;
+use std::ops::ControlFlow;
+use std::rc::Rc;
+
+fn points() -> Vec<[i32; 2]> {
+    (0..500).map(|i| [i * 7 % 503, i * 13 % 509]).collect()
+}
+
+#[test]
+/// The progress should be reported monotonically, ending with all site events processed
+fn progress_test_1() -> Result<(), BvError> {
+    let reports = Rc::new(RefCell::new(Vec::<BuildProgress>::new()));
+    let reports_c = Rc::clone(&reports);
+    let output = Builder::<i32, f64>::default()
+        .with_vertices(points())?
+        .with_progress(50, move |p| {
+            reports_c.borrow_mut().push(*p);
+            ControlFlow::Continue(())
+        })
+        .build()?;
+    let reports = reports.borrow();
+    assert!(reports.len() > 2);
+    for w in reports.windows(2) {
+        assert!(w[0].site_events_processed <= w[1].site_events_processed);
+        assert!(w[0].circle_events_processed <= w[1].circle_events_processed);
+    }
+    let last = reports.last().unwrap();
+    assert_eq!(last.site_events_total, 500);
+    assert_eq!(last.site_events_processed, 500);
+    assert!(last.circle_events_processed > 0);
+    assert_eq!(output.cells().len(), 500);
+    Ok(())
+}
+
+#[test]
+/// Cancelling should abort the build, and the builder should still be usable afterwards
+fn progress_test_2() -> Result<(), BvError> {
+    let calls = Rc::new(RefCell::new(0_usize));
+    let calls_c = Rc::clone(&calls);
+    let mut builder = Builder::<i32, f64>::default()
+        .with_vertices(points())?
+        .with_progress(10, move |_| {
+            *calls_c.borrow_mut() += 1;
+            ControlFlow::Break(())
+        });
+    let mut output = Diagram::<f64>::default();
+    assert!(matches!(
+        builder.build_into(&mut output),
+        Err(BvError::Cancelled)
+    ));
+    assert_eq!(*calls.borrow(), 1);
+    assert!(output.cells().is_empty());
+
+    // a cancelled build leaves nothing behind in a `SyncDiagram` either
+    let mut sync_output: SyncDiagram<f64> = Builder::<i32, f64>::default()
+        .with_vertices([[0, 0], [10, 10]])?
+        .build_sync()?;
+    assert_eq!(sync_output.cells().len(), 2);
+    builder = builder.with_vertices(points())?;
+    assert!(matches!(
+        builder.build_sync_into(&mut sync_output),
+        Err(BvError::Cancelled)
+    ));
+    assert!(sync_output.cells().is_empty());
+    assert!(sync_output.edges().is_empty());
+    assert!(sync_output.vertices().is_empty());
+
+    let output = builder
+        .with_vertices([[0, 0], [10, 10]])?
+        .with_progress(10, |_| ControlFlow::Continue(()))
+        .build()?;
+    assert_eq!(output.cells().len(), 2);
+    Ok(())
+}
+
+#[test]
+/// Cancelling from the final report, when all the events are processed, should not fail the build
+fn progress_test_3() -> Result<(), BvError> {
+    let calls = Rc::new(RefCell::new(0_usize));
+    let calls_c = Rc::clone(&calls);
+    let output = Builder::<i32, f64>::default()
+        .with_vertices(points())?
+        .with_progress(usize::MAX, move |p| {
+            *calls_c.borrow_mut() += 1;
+            assert_eq!(p.site_events_processed, p.site_events_total);
+            ControlFlow::Break(())
+        })
+        .build()?;
+    assert_eq!(*calls.borrow(), 1);
+    assert_eq!(output.cells().len(), 500);
+    Ok(())
+}
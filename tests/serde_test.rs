@@ -26,3 +26,63 @@ fn serde_test_1() -> Result<(), boostvoronoi::BvError> {
     assert_eq!(output_serde.vertices().len(), output.vertices().len());
     Ok(())
 }
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_test_2() -> Result<(), boostvoronoi::BvError> {
+    use boostvoronoi::prelude::*;
+    use boostvoronoi::CellAttributes;
+
+    let mut attributes = CellAttributes::<String>::new();
+    let _ = attributes.insert(CellIndex(1), "one".to_string());
+    let _ = attributes.insert(CellIndex(3), "three".to_string());
+    let _ = attributes.remove(CellIndex(3));
+    let json = serde_json::to_string(&attributes).unwrap();
+    assert_eq!(json, r#"[null,"one",null,null]"#);
+    let attributes_serde: CellAttributes<String> = serde_json::from_str(&json).unwrap();
+    assert_eq!(attributes_serde.len(), 1);
+    assert_eq!(attributes_serde, attributes);
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_test_3() -> Result<(), boostvoronoi::BvError> {
+    use boostvoronoi::prelude::*;
+
+    let output: boostvoronoi::SyncDiagram<f64> = Builder::<i32, f64>::default()
+        .with_vertices([[0, 0], [10, 0], [0, 10]])?
+        .build()?
+        .into();
+    assert_eq!(output.vertices().len(), 1);
+    assert!(output.vertices()[0].radius() > 0.0);
+    // data written before the vertex radius was stored
+    let mut json: serde_json::Value = serde_json::to_value(&output).unwrap();
+    for vertex in json["vertices_"].as_array_mut().unwrap() {
+        assert!(vertex.as_object_mut().unwrap().remove("radius_").is_some());
+    }
+    let output_serde: boostvoronoi::SyncDiagram<f64> = serde_json::from_value(json).unwrap();
+    assert_eq!(output_serde.vertices().len(), 1);
+    assert_eq!(output_serde.vertices()[0].radius(), 0.0);
+    assert_eq!(output_serde.vertices()[0].x(), output.vertices()[0].x());
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_test_4() -> Result<(), boostvoronoi::BvError> {
+    use boostvoronoi::prelude::*;
+    use boostvoronoi::CellAttributes;
+
+    let (output, ids) = Builder::<i32, f64, String>::default()
+        .with_identified_vertices([("a".to_string(), [0, 0]), ("b".to_string(), [10, 10])])?
+        .build_sync_identified()?;
+    let cell_id = output.cells()[0].id();
+    assert!(ids.get(cell_id).is_some());
+    // the site ids are kept apart from the diagram, and can be serialized on their own
+    let ids_serde: CellAttributes<String> =
+        serde_json::from_str(serde_json::to_string(&ids).unwrap().as_str()).unwrap();
+    assert_eq!(ids_serde, ids);
+    assert_eq!(ids_serde.get(cell_id), ids.get(cell_id));
+    Ok(())
+}
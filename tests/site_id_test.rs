@@ -0,0 +1,70 @@
+use boostvoronoi::prelude::*;
+use std::rc::Rc;
+
+#[derive(Debug, Clone, PartialEq)]
+struct Obstacle {
+    name: &'static str,
+}
+
+#[test]
+/// Every cell should report the id of the site it was created from
+fn site_id_test_1() -> Result<(), BvError> {
+    let builder = || {
+        Builder::<i32, f64, Obstacle>::default()
+            .with_identified_vertices([
+                (Obstacle { name: "p0" }, [0, 0]),
+                (Obstacle { name: "p1" }, [50, 50]),
+            ])?
+            .with_vertices([[100, 0]].iter())?
+            .with_identified_segments([(Obstacle { name: "s0" }, Line::from([10, 100, 90, 100]))])
+    };
+    let (output, ids) = builder()?.build_identified()?;
+
+    let mut names = Vec::new();
+    for cell in output.cells().iter() {
+        let cell = cell.get();
+        let id = ids.get(cell.id());
+        match cell.source_index() {
+            0 => assert_eq!(id.unwrap().name, "p0"),
+            1 => assert_eq!(id.unwrap().name, "p1"),
+            2 => assert!(id.is_none()),
+            3 => assert_eq!(id.unwrap().name, "s0"),
+            _ => unreachable!(),
+        }
+        if let Some(id) = id {
+            names.push(id.name);
+        }
+    }
+    // the segment owns three cells: the two endpoints and the segment itself
+    names.sort_unstable();
+    assert_eq!(names, vec!["p0", "p1", "s0", "s0", "s0"]);
+
+    // the same ids when building a SyncDiagram
+    let (sync, sync_ids) = builder()?.build_sync_identified()?;
+    assert_eq!(sync_ids, ids);
+    for cell in sync.cells().iter() {
+        if cell.source_index() == 3 {
+            assert_eq!(sync_ids.get(cell.id()).unwrap().name, "s0");
+        }
+    }
+    Ok(())
+}
+
+#[test]
+/// The ids need not be `Send` or `Sync`, and sites without an id have no entry
+fn site_id_test_2() -> Result<(), BvError> {
+    let (output, ids) = Builder::<i32, f64, Rc<str>>::default()
+        .with_identified_vertices([(Rc::from("a"), [0, 0]), (Rc::from("b"), [10, 10])])?
+        .build_identified()?;
+    let cell_id = output.cells()[0].get().id();
+    assert_eq!(ids.get(cell_id).map(|id| id.as_ref()), Some("a"));
+    assert_eq!(ids.len(), 2);
+
+    // no ids at all
+    let (output, ids) = Builder::<i32, f64, u32>::default()
+        .with_vertices([[0, 0], [10, 10]].iter())?
+        .build_identified()?;
+    assert_eq!(output.cells().len(), 2);
+    assert!(ids.is_empty());
+    Ok(())
+}
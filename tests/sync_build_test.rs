@@ -0,0 +1,75 @@
+use boostvoronoi::prelude::*;
+use boostvoronoi::SyncDiagram;
+
+type Summary = (Vec<(usize, Option<usize>)>, Vec<[u64; 2]>, Vec<String>);
+
+/// A summary of a diagram that can be compared
+fn summary(output: &SyncDiagram<f64>) -> Summary {
+    let cells = output
+        .cells()
+        .iter()
+        .map(|c| (c.source_index(), c.get_incident_edge().map(|e| e.0)))
+        .collect();
+    let vertices = output
+        .vertices()
+        .iter()
+        .map(|v| [v.x().to_bits(), v.y().to_bits()])
+        .collect();
+    let edges = output.edges().iter().map(|e| format!("{:?}", e)).collect();
+    (cells, vertices, edges)
+}
+
+fn builder() -> Result<Builder<i32, f64>, BvError> {
+    Builder::<i32, f64>::default()
+        .with_vertices([[50, 50], [150, 20], [60, 55]])?
+        .with_segments([[0, 0, 100, 0], [0, 100, 100, 100], [100, 0, 100, 100]])
+}
+
+#[test]
+/// `build_sync()` should give the same diagram as `build()` converted into a `SyncDiagram`
+fn sync_build_test_1() -> Result<(), BvError> {
+    let expected: SyncDiagram<f64> = builder()?.build()?.into();
+    let output = builder()?.build_sync()?;
+    assert_eq!(summary(&expected), summary(&output));
+    assert_eq!(output.cells().len(), 10);
+    Ok(())
+}
+
+#[test]
+/// The diagram can be built on a worker thread and shared between threads
+fn sync_build_test_2() -> Result<(), BvError> {
+    let expected = builder()?.build_sync()?;
+    let output = std::thread::spawn(|| builder()?.build_sync())
+        .join()
+        .unwrap()?;
+    let output = std::sync::Arc::new(output);
+    let edges = {
+        let output = std::sync::Arc::clone(&output);
+        std::thread::spawn(move || output.edges().len())
+            .join()
+            .unwrap()
+    };
+    assert_eq!(edges, expected.edges().len());
+    assert_eq!(summary(&expected), summary(&output));
+    Ok(())
+}
+
+#[test]
+/// Reusing the builder and the `SyncDiagram` should give the same result as fresh ones
+fn sync_build_test_3() -> Result<(), BvError> {
+    let mut builder = Builder::<i32, f64>::default();
+    let mut output = SyncDiagram::<f64>::default();
+    for i in 0..5 {
+        let points = [[50, 50 + i], [150, 20 - i]];
+        let segments = [[0, 0, 100, 0], [0, 100, 100 + i, 100]];
+        let expected: SyncDiagram<f64> = Builder::<i32, f64>::default()
+            .with_vertices(points)?
+            .with_segments(segments)?
+            .build()?
+            .into();
+        builder = builder.with_vertices(points)?.with_segments(segments)?;
+        builder.build_sync_into(&mut output)?;
+        assert_eq!(summary(&expected), summary(&output));
+    }
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use boostvoronoi::prelude::*;
+use boostvoronoi::SyncDiagram;
+
+#[test]
+/// Random points and segments, as a `Diagram` and as a `SyncDiagram`
+fn topology_test_1() -> Result<(), BvError> {
+    let points: Vec<Point<i32>> = (0..40)
+        .map(|i| [(i * 37) % 101, (i * 61) % 103 + 200].into())
+        .collect();
+    let segments: Vec<Line<i32>> = (0..20)
+        .map(|i| [i * 10, (i * 17) % 50, i * 10 + 7, (i * 23) % 50 + 60].into())
+        .collect();
+    let builder = || -> Result<Builder<i32, f64>, BvError> {
+        Builder::<i32, f64>::default()
+            .with_vertices(points.iter())?
+            .with_segments(segments.iter())
+    };
+    assert!(builder()?.validate().is_valid());
+
+    let diagram = builder()?.build()?;
+    let report = diagram.validate_topology();
+    assert!(report.is_valid(), "{:?}", report);
+
+    let sync_diagram = builder()?.build_sync()?;
+    assert!(sync_diagram.validate_topology().is_valid());
+    // the conversions keep the topology intact
+    let sync_diagram: SyncDiagram<f64> = diagram.into();
+    assert!(sync_diagram.validate_topology().is_valid());
+    let diagram: Diagram<f64> = sync_diagram.into();
+    assert!(diagram.validate_topology().is_valid());
+    Ok(())
+}
+
+#[test]
+/// Polygons with holes
+fn topology_test_2() -> Result<(), BvError> {
+    let square = |x: i32, y: i32, size: i32| -> Vec<Point<i32>> {
+        vec![
+            [x, y].into(),
+            [x + size, y].into(),
+            [x + size, y + size].into(),
+            [x, y + size].into(),
+        ]
+    };
+    let polygons = [
+        Polygon::new(square(0, 0, 300), vec![square(100, 100, 100)]),
+        Polygon::new(square(400, 0, 50), vec![]),
+    ];
+    let diagram = Builder::<i32, f64>::default()
+        .with_polygons(polygons.iter().cloned())?
+        .build()?;
+    let report = diagram.validate_topology();
+    assert!(report.is_valid(), "{:?}", report);
+    Ok(())
+}
+
+#[test]
+/// Degenerate input: a single point has no edges, collinear points have no vertices
+fn topology_test_3() -> Result<(), BvError> {
+    let single: Vec<Point<i32>> = vec![[5, 5].into()];
+    let diagram = Builder::<i32, f64>::default()
+        .with_vertices(single.iter())?
+        .build()?;
+    assert!(diagram.edges().is_empty());
+    assert!(diagram.validate_topology().is_valid());
+
+    let collinear: Vec<Point<i32>> = (0..5).map(|i| [i * 10, i * 5].into()).collect();
+    let diagram = Builder::<i32, f64>::default()
+        .with_vertices(collinear.iter())?
+        .build()?;
+    assert!(diagram.vertices().is_empty());
+    // the parallel edges all meet at infinity
+    let report = diagram.validate_topology();
+    assert!(report.is_valid(), "{:?}", report);
+    Ok(())
+}
@@ -0,0 +1,85 @@
+use boostvoronoi::prelude::*;
+use boostvoronoi::{SourceCategory, SweepEvent};
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+type Trace = Vec<SweepEvent<i32, f64>>;
+type Events = Rc<RefCell<Trace>>;
+
+fn traced_build(
+    points: &[[i32; 2]],
+    segments: &[[i32; 4]],
+) -> Result<(Diagram<f64>, Trace), BvError> {
+    let events: Events = Rc::default();
+    let recorder = Rc::clone(&events);
+    let output = Builder::<i32, f64>::default()
+        .with_vertices(points.iter().copied())?
+        .with_segments(segments.iter().copied())?
+        .with_observer(move |e: &SweepEvent<i32, f64>| recorder.borrow_mut().push(*e))
+        .build()?;
+    let events = events.take();
+    Ok((output, events))
+}
+
+#[test]
+/// The trace should be consistent with the resulting diagram
+fn trace_test_1() -> Result<(), BvError> {
+    let points = [[5, 5], [50, 20], [20, 70]];
+    let segments = [[0, 0, 100, 0], [100, 0, 100, 100]];
+    let (output, events) = traced_build(&points, &segments)?;
+
+    let mut sites = 0;
+    let mut created = HashSet::new();
+    let mut deactivated = HashSet::new();
+    let mut processed = HashSet::new();
+    let mut vertices = 0;
+    for e in events.iter() {
+        match e {
+            SweepEvent::SiteEventProcessed { site } => {
+                sites += 1;
+                if site.source_index >= points.len() {
+                    assert_ne!(site.source_category, SourceCategory::SinglePoint);
+                }
+            }
+            SweepEvent::CircleEventCreated { id, .. } => assert!(created.insert(*id)),
+            SweepEvent::CircleEventDeactivated { id } => {
+                assert!(created.contains(id));
+                assert!(!processed.contains(id));
+                assert!(deactivated.insert(*id));
+            }
+            SweepEvent::CircleEventProcessed { id, .. } => {
+                assert!(created.contains(id));
+                assert!(!deactivated.contains(id));
+                assert!(processed.insert(*id));
+            }
+            SweepEvent::VertexCreated { .. } => vertices += 1,
+            _ => (),
+        }
+    }
+    // three sites per segment (the end points are shared)
+    assert_eq!(sites, points.len() + 3 * segments.len() - 1);
+    assert_eq!(created.len(), deactivated.len() + processed.len());
+    assert_eq!(vertices, processed.len());
+    assert_eq!(vertices, output.vertices().len());
+    assert_eq!(
+        events.last(),
+        Some(&SweepEvent::Finished {
+            num_cells: output.cells().len(),
+            num_edges: output.edges().len(),
+            num_vertices: output.vertices().len(),
+        })
+    );
+    Ok(())
+}
+
+#[test]
+/// Two runs over the same input should give identical traces
+fn trace_test_2() -> Result<(), BvError> {
+    let points: Vec<[i32; 2]> = (0..50).map(|i| [i * 37 % 101, i * 59 % 103]).collect();
+    let (_, events1) = traced_build(&points, &[])?;
+    let (_, events2) = traced_build(&points, &[])?;
+    assert!(events1.len() > points.len());
+    assert_eq!(events1, events2);
+    Ok(())
+}
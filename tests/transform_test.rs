@@ -45,3 +45,26 @@ fn transform_test_2() -> Result<(), BvError> {
 
     Ok(())
 }
+
+#[test]
+fn transform_test_3() -> Result<(), BvError> {
+    let mut aabb_source = BV::Aabb2::<f64>::default();
+    let mut aabb_dest = BV::Aabb2::<f64>::default();
+
+    // source is (-100,-100)-(100,100)
+    aabb_source.update_vertex(-100., -100.);
+    aabb_source.update_vertex(100., 100.);
+
+    // dest is (0,0)-(800,800.)
+    aabb_dest.update_vertex(0., 0.);
+    aabb_dest.update_vertex(800., 800.);
+
+    let transform = BV::SimpleAffine::new::<i32>(&aabb_source, &aabb_dest)?;
+    // the float reverse transform is not rounded to the input type
+    assert_eq!(transform.reverse_transform_f([2., 6.]), [-99.5, -98.5]);
+    assert_eq!(transform.reverse_transform::<i32>(2., 6.)?, [-100, -99]);
+    let p = [12.25, -31.5];
+    assert_eq!(transform.reverse_transform_f(transform.transform_f(p)), p);
+
+    Ok(())
+}
@@ -0,0 +1,120 @@
+use boostvoronoi::prelude::*;
+use boostvoronoi::{FloatBuilder, SourceCategory, SyncDiagram};
+
+mod common;
+use common::Lcg;
+
+/// The distance from `p` to the segment `a`-`b`
+fn distance_to_segment(p: [f64; 2], a: Point<i32>, b: Point<i32>) -> f64 {
+    let (ax, ay) = (a.x as f64, a.y as f64);
+    let (dx, dy) = (b.x as f64 - ax, b.y as f64 - ay);
+    let length = dx * dx + dy * dy;
+    let t = if length > 0.0 {
+        (((p[0] - ax) * dx + (p[1] - ay) * dy) / length).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    (p[0] - ax - t * dx).hypot(p[1] - ay - t * dy)
+}
+
+#[test]
+/// Every site around a vertex is at the radius of the vertex
+fn vertex_test_1() -> Result<(), BvError> {
+    let mut rnd = Lcg(5);
+    let points: Vec<Point<i32>> = (0..100)
+        .map(|_| [rnd.next(10000), rnd.next(10000)].into())
+        .collect();
+    // disjoint horizontal segments, above the points
+    let segments: Vec<Line<i32>> = (0..20)
+        .map(|i| {
+            let (x, y) = (rnd.next(9000), 11000 + i * 100);
+            Line::from([x, y, x + 1 + rnd.next(1000), y + rnd.next(50)])
+        })
+        .collect();
+    let diagram = Builder::<i32, f64>::default()
+        .with_vertices(points.iter())?
+        .with_segments(segments.iter())?
+        .build()?;
+    assert!(!diagram.vertices().is_empty());
+    for vertex in diagram.vertices() {
+        let vertex = vertex.get();
+        let p = [vertex.x(), vertex.y()];
+        let cells = diagram.vertex_cells(vertex.get_id())?;
+        assert!(cells.len() >= 3, "{:?}", vertex);
+        for cell_id in cells {
+            let (index, category) = diagram.get_cell(cell_id)?.get().source_index_2();
+            let distance = match category {
+                SourceCategory::SinglePoint => distance_to_segment(p, points[index], points[index]),
+                SourceCategory::SegmentStart => {
+                    let s = segments[index - points.len()];
+                    distance_to_segment(p, s.start, s.start)
+                }
+                SourceCategory::SegmentEnd => {
+                    let s = segments[index - points.len()];
+                    distance_to_segment(p, s.end, s.end)
+                }
+                SourceCategory::Segment => {
+                    let s = segments[index - points.len()];
+                    distance_to_segment(p, s.start, s.end)
+                }
+            };
+            assert!(
+                (distance - vertex.radius()).abs() < 1e-6 * (1.0 + distance),
+                "{:?} {:?} {}",
+                vertex,
+                category,
+                distance
+            );
+        }
+    }
+
+    let expected = diagram
+        .vertices()
+        .iter()
+        .map(|v| {
+            let v = v.get();
+            Ok((v.radius(), diagram.vertex_cells(v.get_id())?))
+        })
+        .collect::<Result<Vec<_>, BvError>>()?;
+    let sync_diagram: SyncDiagram<f64> = diagram.into();
+    for (vertex, (radius, cells)) in sync_diagram.vertices().iter().zip(expected) {
+        assert_eq!(vertex.radius(), radius);
+        assert_eq!(sync_diagram.vertex_cells(vertex.get_id())?, cells);
+    }
+    Ok(())
+}
+
+#[test]
+/// Vertices at segment end points have a zero radius
+fn vertex_test_2() -> Result<(), BvError> {
+    let diagram = Builder::<i32, f64>::default()
+        .with_segments([[0, 0, 100, 0], [100, 0, 100, 100]])?
+        .build()?;
+    for vertex in diagram.vertices() {
+        let vertex = vertex.get();
+        if vertex.is_site_point() {
+            assert_eq!(vertex.radius(), 0.0);
+        } else {
+            assert!(vertex.radius() > 0.0);
+        }
+    }
+    assert!(diagram.vertices().iter().any(|v| v.get().is_site_point()));
+    assert!(diagram
+        .vertex_cells(VertexIndex(diagram.vertices().len()))
+        .is_err());
+    Ok(())
+}
+
+#[test]
+/// The radius is scaled back to world coordinates by the `FloatBuilder`
+fn vertex_test_3() -> Result<(), BvError> {
+    let points = [[1.25_f64, 1.25], [1.75, 1.25], [1.75, 1.75], [1.25, 1.75]];
+    let diagram = FloatBuilder::<i32, f64>::default()
+        .with_vertices(points.iter().copied())?
+        .build()?;
+    assert_eq!(diagram.vertices().len(), 1);
+    let vertex = diagram.vertices()[0].get();
+    approx::assert_ulps_eq!(vertex.radius(), 0.25 * 2.0_f64.sqrt());
+    assert_eq!(diagram.vertex_cells(vertex.get_id())?.len(), 4);
+    Ok(())
+}
@@ -0,0 +1,276 @@
+//          Copyright Eadf (github.com/eadf) 2021.
+// Distributed under the Boost Software License, Version 1.0.
+//    (See accompanying file LICENSE_1_0.txt or copy at
+//          http://www.boost.org/LICENSE_1_0.txt)
+
+//! Typed per-element user data, stored next to the diagram instead of inside it.
+//!
+//! The `color` of cells, edges and vertices shares its bits with the internal flags of the
+//! diagram, so it can only hold a few bits of user data. An [`AttributeMap`] holds any value type
+//! for any number of elements, indexed by `CellIndex`, `EdgeIndex` or `VertexIndex`. The map works
+//! the same for a `Diagram` and a `SyncDiagram`, since the element indices are the same.
+
+use crate::diagram::{CellIndex, EdgeIndex, VertexIndex};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::marker::PhantomData;
+
+/// The typed index of a diagram element, `CellIndex`, `EdgeIndex` or `VertexIndex`
+pub trait ElementIndex: Copy + fmt::Debug {
+    /// Creates the typed index from a raw index
+    fn from_index(index: usize) -> Self;
+    /// Returns the raw index
+    fn index(self) -> usize;
+}
+
+impl ElementIndex for CellIndex {
+    #[inline(always)]
+    fn from_index(index: usize) -> Self {
+        CellIndex(index)
+    }
+
+    #[inline(always)]
+    fn index(self) -> usize {
+        self.0
+    }
+}
+
+impl ElementIndex for EdgeIndex {
+    #[inline(always)]
+    fn from_index(index: usize) -> Self {
+        EdgeIndex(index)
+    }
+
+    #[inline(always)]
+    fn index(self) -> usize {
+        self.0
+    }
+}
+
+impl ElementIndex for VertexIndex {
+    #[inline(always)]
+    fn from_index(index: usize) -> Self {
+        VertexIndex(index)
+    }
+
+    #[inline(always)]
+    fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// Attributes of `Cell`s
+pub type CellAttributes<V> = AttributeMap<CellIndex, V>;
+/// Attributes of `Edge`s
+pub type EdgeAttributes<V> = AttributeMap<EdgeIndex, V>;
+/// Attributes of `Vertex`es
+pub type VertexAttributes<V> = AttributeMap<VertexIndex, V>;
+
+/// A map from diagram elements of one kind to values of type `V`.
+///
+/// The values are stored in a vector indexed by the element index, so lookups are constant time.
+/// The map grows as needed when values are inserted. Two maps are equal if they hold equal values
+/// for the same elements, regardless of how far each has grown.
+/// ```
+/// # use boostvoronoi_core::attributes::EdgeAttributes;
+/// # use boostvoronoi_core::builder::Builder;
+/// # use boostvoronoi_core::geometry::Point;
+/// # use boostvoronoi_core::BvError;
+/// let points: Vec<Point<i32>> = vec![[0, 0].into(), [10, 0].into(), [0, 10].into()];
+/// let diagram = Builder::<i32, f64>::default()
+///     .with_vertices(points.iter())?
+///     .build()?;
+/// let mut infinite = EdgeAttributes::<bool>::filled(diagram.edges().len(), false);
+/// for edge in diagram.edges().iter() {
+///     let edge_id = edge.get().id();
+///     let _ = infinite.insert(edge_id, !diagram.edge_is_finite(edge_id)?);
+/// }
+/// assert_eq!(infinite.values().filter(|v| **v).count(), 6);
+/// # Ok::<(), BvError>(())
+/// ```
+#[derive(Clone)]
+pub struct AttributeMap<K: ElementIndex, V> {
+    values_: Vec<Option<V>>,
+    len_: usize,
+    pd_: PhantomData<K>,
+}
+
+impl<K: ElementIndex, V> Default for AttributeMap<K, V> {
+    fn default() -> Self {
+        Self {
+            values_: Vec::new(),
+            len_: 0,
+            pd_: PhantomData,
+        }
+    }
+}
+
+impl<K: ElementIndex, V: PartialEq> PartialEq for AttributeMap<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len_ == other.len_
+            && self
+                .iter()
+                .map(|(k, v)| (k.index(), v))
+                .eq(other.iter().map(|(k, v)| (k.index(), v)))
+    }
+}
+
+impl<K: ElementIndex, V: Eq> Eq for AttributeMap<K, V> {}
+
+/// Serialized as the sequence of optional values, the number of values is recomputed when
+/// deserialized.
+#[cfg(feature = "serde")]
+impl<K: ElementIndex, V: Serialize> Serialize for AttributeMap<K, V> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.values_.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, K: ElementIndex, V: Deserialize<'de>> Deserialize<'de> for AttributeMap<K, V> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let values: Vec<Option<V>> = Vec::deserialize(deserializer)?;
+        Ok(Self {
+            len_: values.iter().filter(|v| v.is_some()).count(),
+            values_: values,
+            pd_: PhantomData,
+        })
+    }
+}
+
+impl<K: ElementIndex, V: fmt::Debug> fmt::Debug for AttributeMap<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K: ElementIndex, V> AttributeMap<K, V> {
+    /// Creates an empty map
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a map where each of the first `len` elements has the value `value`, e.g.
+    /// `CellAttributes::filled(diagram.cells().len(), 0)`.
+    pub fn filled(len: usize, value: V) -> Self
+    where
+        V: Clone,
+    {
+        Self {
+            values_: vec![Some(value); len],
+            len_: len,
+            pd_: PhantomData,
+        }
+    }
+
+    /// Returns the number of elements with a value
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len_
+    }
+
+    /// Returns true if no element has a value
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len_ == 0
+    }
+
+    /// Returns true if the element has a value
+    #[inline]
+    pub fn contains_key(&self, key: K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Returns the value of the element
+    #[inline]
+    pub fn get(&self, key: K) -> Option<&V> {
+        self.values_.get(key.index()).and_then(|v| v.as_ref())
+    }
+
+    /// Returns the value of the element, for modification
+    #[inline]
+    pub fn get_mut(&mut self, key: K) -> Option<&mut V> {
+        self.values_.get_mut(key.index()).and_then(|v| v.as_mut())
+    }
+
+    /// Returns the value of the element, inserting the result of `default` if there was none
+    pub fn get_or_insert_with<D: FnOnce() -> V>(&mut self, key: K, default: D) -> &mut V {
+        if !self.contains_key(key) {
+            let _ = self.insert(key, default());
+        }
+        self.values_[key.index()].as_mut().unwrap()
+    }
+
+    /// Sets the value of the element, returns the previous value
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let index = key.index();
+        if index >= self.values_.len() {
+            self.values_.resize_with(index + 1, || None);
+        }
+        let rv = self.values_[index].replace(value);
+        if rv.is_none() {
+            self.len_ += 1;
+        }
+        rv
+    }
+
+    /// Removes the value of the element, returns the previous value
+    pub fn remove(&mut self, key: K) -> Option<V> {
+        let rv = self.values_.get_mut(key.index()).and_then(|v| v.take());
+        if rv.is_some() {
+            self.len_ -= 1;
+        }
+        rv
+    }
+
+    /// Removes all values
+    pub fn clear(&mut self) {
+        self.values_.clear();
+        self.len_ = 0;
+    }
+
+    /// Iterates over the elements with a value, in index order
+    pub fn iter(&self) -> impl Iterator<Item = (K, &V)> + '_ {
+        self.values_
+            .iter()
+            .enumerate()
+            .filter_map(|(i, v)| v.as_ref().map(|v| (K::from_index(i), v)))
+    }
+
+    /// Iterates over the elements with a value, in index order, for modification
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (K, &mut V)> + '_ {
+        self.values_
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(i, v)| v.as_mut().map(|v| (K::from_index(i), v)))
+    }
+
+    /// Iterates over the elements with a value, in index order
+    pub fn keys(&self) -> impl Iterator<Item = K> + '_ {
+        self.iter().map(|(k, _)| k)
+    }
+
+    /// Iterates over the values, in index order
+    pub fn values(&self) -> impl Iterator<Item = &V> + '_ {
+        self.values_.iter().filter_map(|v| v.as_ref())
+    }
+}
+
+impl<K: ElementIndex, V> FromIterator<(K, V)> for AttributeMap<K, V> {
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        let mut rv = Self::new();
+        for (k, v) in iter {
+            let _ = rv.insert(k, v);
+        }
+        rv
+    }
+}
+
+impl<K: ElementIndex, V> Extend<(K, V)> for AttributeMap<K, V> {
+    fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
+        for (k, v) in iter {
+            let _ = self.insert(k, v);
+        }
+    }
+}
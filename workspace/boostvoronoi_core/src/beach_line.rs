@@ -19,8 +19,6 @@ mod tests1;
 
 use crate::circle_event as VC;
 use crate::diagram as VD;
-#[cfg(feature = "console_debug")]
-use crate::predicate as VP;
 use crate::site_event as VSE;
 
 #[allow(unused_imports)]
@@ -253,7 +251,7 @@ impl<I: InputType, F: OutputType> BeachLine<I, F> {
     pub(crate) fn debug_cmp_all(&self, key: BeachLineNodeKey<I, F>) {
         for (i, (v, _)) in self.beach_line_.borrow().iter().rev().enumerate() {
             t!("#{}:", i);
-            let _rv = VP::node_comparison_predicate::node_comparison::<I, F>(v, &key);
+            let _rv = node_comparison_predicate::node_comparison::<I, F>(v, &key);
         }
     }
 
@@ -5,6 +5,7 @@ use super::super::diagram as VD;
 use super::super::geometry::{Line, Point};
 use super::super::predicate as VP;
 use super::super::site_event as VSE;
+use super::super::sync_diagram as SD;
 use super::super::BvError;
 use super::{BeachLine, BeachLineIndex, BeachLineNodeData, BeachLineNodeKey};
 use std::cmp::Ordering;
@@ -67,7 +68,7 @@ fn beachline_2() -> Result<(), BvError> {
         Point::new(4, 21),
         Point::new(8, 62),
     ];
-    let mut output: VD::Diagram<F> = VD::Diagram::<F>::default();
+    let mut output = SD::SyncDiagram::<F>::default();
 
     let mut b = Builder::<I, F>::default().with_vertices(_v.iter()).unwrap();
     let mut site_event_iterator_: VSE::SiteEventIndexType = b.init_sites_queue();
@@ -103,7 +104,7 @@ fn beachline_3() -> Result<(), BvError> {
 
     {
         let _s = vec![Line::new(Point { x: 10, y: 10 }, Point { x: 50, y: 50 })];
-        let mut output: VD::Diagram<F> = VD::Diagram::<F>::default();
+        let mut output = SD::SyncDiagram::<F>::default();
 
         let mut b = Builder::<I, F>::default().with_segments(_s.iter()).unwrap();
         let mut site_event_iterator_: VSE::SiteEventIndexType = b.init_sites_queue();
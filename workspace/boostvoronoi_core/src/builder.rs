@@ -11,21 +11,27 @@
 
 //! Contains the builder code.
 
+use crate::attributes::CellAttributes;
 use crate::beach_line as VB;
 use crate::circle_event as VC;
 use crate::diagram as VD;
 use crate::end_point as VEP;
 use crate::predicate as VP;
 use crate::site_event as VSE;
+use crate::sync_diagram as SD;
 #[cfg(feature = "console_debug")]
 use crate::t;
+use crate::trace as VT;
+use crate::validation as VV;
 use crate::{
-    geometry::{Line, Point},
+    cast,
+    geometry::{Line, Point, Polygon},
     tln, BvError, InputType, OutputType,
 };
 
 use cpp_map::PIterator;
 use std::collections::BinaryHeap;
+use std::ops::ControlFlow;
 use std::rc::Rc;
 
 #[cfg(test)]
@@ -74,119 +80,610 @@ mod tests;
 ///     .build()?;
 /// # Ok::<(), BvError>(())
 /// ```
-pub struct Builder<I: InputType, F: OutputType> {
+/// `ID` is the type of the optional user defined site ids, see `with_identified_vertices()`.
+pub struct Builder<I: InputType, F: OutputType, ID = ()> {
     pub(crate) site_events_: Vec<VSE::SiteEvent<I, F>>,
     circle_events_: VC::CircleEventQueue,
     end_points_: BinaryHeap<VEP::EndPointPair<I>>,
     pub(crate) beach_line_: VB::BeachLine<I, F>,
-    // The number of input sites if points and segments are counted as one.
-    // (segments generates two site events so we can't use the length of the list)
-    index_: usize,
-    segments_added_: bool, // make sure eventual vertices are added before segments
+    // The site events of the segments, the source indices are relative to the first segment
+    // until they are merged into `site_events_` by `init_sites_queue()`.
+    segment_site_events_: Vec<VSE::SiteEvent<I, F>>,
+    // Vertices get the source indices `0..num_vertices_`, segments the indices after that.
+    // (segments generates several site events so we can't use the length of the lists)
+    num_vertices_: usize,
+    num_segments_: usize,
+    // the source index ranges of the polygon rings and polylines, relative to the first segment
+    path_records_: Vec<VD::PathRecord>,
+    polygons_added_: usize,
+    polylines_added_: usize,
+    // the user defined ids
+    site_ids_: SiteIdStore<ID>,
+    // called every now and then by the main loop of the build
+    progress_: Option<ProgressObserver>,
+    // receives every step of the sweep-line algorithm
+    observer_: Observer<I, F>,
     #[cfg(feature = "console_debug")]
     debug_circle_counter_: isize, // Just for debugging purposes
     #[cfg(feature = "console_debug")]
     debug_site_counter_: isize, // Just for debugging purposes
 }
 
-impl<I: InputType, F: OutputType> Default for Builder<I, F> {
+/// The progress of a running `Builder::build()`, see `Builder::with_progress()`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BuildProgress {
+    /// The number of site events processed so far
+    pub site_events_processed: usize,
+    /// The total number of site events (after removal of duplicates)
+    pub site_events_total: usize,
+    /// The number of circle events processed so far.
+    /// The total number of circle events is not known in advance.
+    pub circle_events_processed: usize,
+}
+
+/// A progress callback, returning `ControlFlow::Break` cancels the build
+type ProgressCallback = Box<dyn FnMut(&BuildProgress) -> ControlFlow<()>>;
+
+/// The optional observer of the sweep-line events, see `Builder::with_observer()`
+type Observer<I, F> = Option<Box<dyn VT::SweepObserver<I, F>>>;
+
+/// The progress callback and how often it should be called
+struct ProgressObserver {
+    interval_: usize,
+    callback_: ProgressCallback,
+}
+
+/// The user defined ids of the vertices and segments, kept apart until the source indices are
+/// known.
+struct SiteIdStore<ID> {
+    vertices_: Vec<Option<ID>>,
+    segments_: Vec<Option<ID>>,
+}
+
+impl<ID> Default for SiteIdStore<ID> {
+    fn default() -> Self {
+        Self {
+            vertices_: Vec::new(),
+            segments_: Vec::new(),
+        }
+    }
+}
+
+impl<ID> SiteIdStore<ID> {
+    /// Merges the ids into a `Vec<Option<ID>>` indexed by source index, and empties the store
+    fn take_by_source_index(&mut self, num_vertices: usize) -> Vec<Option<ID>> {
+        let mut ids = std::mem::take(&mut self.vertices_);
+        ids.resize_with(num_vertices, || None);
+        ids.append(&mut self.segments_);
+        ids
+    }
+
+    /// Returns the ids of the cells, `cells` yields the id and the source index of each cell.
+    /// The ids are indexed by source index.
+    fn into_cell_ids(
+        ids: Vec<Option<ID>>,
+        cells: impl Iterator<Item = (VD::CellIndex, VD::SourceIndex)>,
+    ) -> CellAttributes<ID>
+    where
+        ID: Clone,
+    {
+        let mut rv = CellAttributes::new();
+        for (cell_id, source_index) in cells {
+            if let Some(Some(id)) = ids.get(source_index) {
+                let _ = rv.insert(cell_id, id.clone());
+            }
+        }
+        rv
+    }
+}
+
+impl<I: InputType, F: OutputType, ID> Default for Builder<I, F, ID> {
     fn default() -> Self {
         Self {
             site_events_: Vec::new(),
             beach_line_: VB::BeachLine::default(),
-            index_: 0,
+            segment_site_events_: Vec::new(),
+            num_vertices_: 0,
+            num_segments_: 0,
             end_points_: BinaryHeap::new(),
             circle_events_: VC::CircleEventQueue::default(),
             #[cfg(feature = "console_debug")]
             debug_circle_counter_: 0,
             #[cfg(feature = "console_debug")]
             debug_site_counter_: 0,
-            segments_added_: false,
+            path_records_: Vec::new(),
+            polygons_added_: 0,
+            polylines_added_: 0,
+            site_ids_: SiteIdStore::default(),
+            progress_: None,
+            observer_: None,
         }
     }
 }
 
-impl<I: InputType, F: OutputType> Builder<I, F> {
+impl<I: InputType, F: OutputType, ID> Builder<I, F, ID> {
     /// Inserts vertices.
-    /// This should be done before inserting segments.
+    /// Vertices and segments may be inserted in any order, but the vertices are always numbered
+    /// first: vertex number `n` gets the source index `n` and segment number `n` gets the source
+    /// index `number_of_vertices + n`.
     /// This method accepts iterators of anything that implements `Into<boostvoronoi::geometry::Point>`
     pub fn with_vertices<T, IT>(mut self, vertices: T) -> Result<Self, BvError>
     where
         T: IntoIterator<Item = IT>,
         IT: Copy + Into<Point<I>>,
     {
-        if self.segments_added_ {
-            return Err(BvError::VerticesGoesFirst(
-                "Vertices should be added before segments".to_string(),
-            ));
-        }
         for v in vertices.into_iter().map(|v| -> Point<I> { v.into() }) {
-            let mut s = VSE::SiteEvent::<I, F>::new(VSE::Site::Point(v), self.index_);
-            s.or_source_category(VD::ColorBits::SINGLE_POINT__BIT);
-            self.site_events_.push(s);
-            self.index_ += 1;
+            self.push_vertex_(v);
+        }
+        Ok(self)
+    }
+
+    /// Inserts vertices together with an user defined id of the type `ID`.
+    /// The id of each cell is returned by `build_identified()` and `build_sync_identified()`, so
+    /// that there is no need to keep track of the source indices.
+    /// ```
+    /// # use boostvoronoi_core::builder::Builder;
+    /// # use boostvoronoi_core::BvError;
+    /// let (diagram, ids) = Builder::<i32, f64, &str>::default()
+    ///     .with_identified_vertices([("a", [0, 0]), ("b", [10, 10])])?
+    ///     .build_identified()?;
+    /// for cell in diagram.cells().iter() {
+    ///     let cell = cell.get();
+    ///     assert_eq!(ids.get(cell.id()), Some(&["a", "b"][cell.source_index()]));
+    /// }
+    /// # Ok::<(), BvError>(())
+    /// ```
+    pub fn with_identified_vertices<T, IT>(mut self, vertices: T) -> Result<Self, BvError>
+    where
+        T: IntoIterator<Item = (ID, IT)>,
+        IT: Into<Point<I>>,
+    {
+        for (id, v) in vertices.into_iter() {
+            self.push_site_id_(id, true);
+            self.push_vertex_(v.into());
         }
         Ok(self)
     }
 
     /// Inserts segments.
-    /// This should be done after inserting vertices.
+    /// The source indices of the segments are numbered after all the vertices, see
+    /// `with_vertices()`.
     /// This method accepts iterators of anything that implements `Into<boostvoronoi::geometry::Line>`
     pub fn with_segments<T, IT>(mut self, segments: T) -> Result<Self, BvError>
     where
         T: IntoIterator<Item = IT>,
         IT: Copy + Into<Line<I>>,
     {
-        type Cb = VD::ColorBits;
         for line in segments.into_iter().map(|s| -> Line<I> { s.into() }) {
-            #[allow(clippy::branches_sharing_code)]
-            let se = if line.start == line.end {
-                // take care of the case when a line is actually a point
-                let mut se = VSE::SiteEvent::<I, F>::new(VSE::Site::Point(line.start), self.index_);
-                se.or_source_category(Cb::SINGLE_POINT__BIT);
-                se
-            } else {
-                // the line was a line with unique endpoints
-                let mut s1 = VSE::SiteEvent::<I, F>::new(VSE::Site::Point(line.start), self.index_);
-                s1.or_source_category(Cb::SEGMENT_START_POINT__BIT);
-
-                let mut s2 = VSE::SiteEvent::<I, F>::new(VSE::Site::Point(line.end), self.index_);
-                s2.or_source_category(Cb::SEGMENT_END_POINT__BIT);
+            self.push_segment_(line);
+        }
+        Ok(self)
+    }
 
-                self.site_events_.push(s1);
-                self.site_events_.push(s2);
-                let site = VSE::Site::from(line);
+    /// Inserts segments together with an user defined id of the type `ID`.
+    /// The id of each cell is returned by `build_identified()` and `build_sync_identified()`. The
+    /// two cells of the segment endpoints will get the same id as the segment cell.
+    pub fn with_identified_segments<T, IT>(mut self, segments: T) -> Result<Self, BvError>
+    where
+        T: IntoIterator<Item = (ID, IT)>,
+        IT: Into<Line<I>>,
+    {
+        for (id, line) in segments.into_iter() {
+            self.push_site_id_(id, false);
+            self.push_segment_(line.into());
+        }
+        Ok(self)
+    }
 
-                if VP::point_comparison::point_comparison(line.start, line.end) {
-                    let mut s3 = VSE::SiteEvent::<I, F>::new(site, self.index_);
-                    s3.or_source_category(Cb::INITIAL_SEGMENT);
-                    s3
-                } else {
-                    let mut s3 = VSE::SiteEvent::<I, F>::new(site.reverse(), self.index_);
-                    s3.or_source_category(Cb::REVERSE_SEGMENT);
-                    s3
+    /// Inserts polygons, each ring is inserted as a closed chain of segments.
+    /// The origin of each resulting cell, polygon, ring and vertex/edge of that ring, can be
+    /// retrieved with `Diagram::cell_path_source()`.
+    /// This method accepts iterators of anything that implements `Into<boostvoronoi::geometry::Polygon>`
+    /// ```
+    /// # use boostvoronoi_core::geometry::Polygon;
+    /// # use boostvoronoi_core::builder::Builder;
+    /// # use boostvoronoi_core::diagram::{PathElement, PathSource};
+    /// # use boostvoronoi_core::BvError;
+    /// let square = Polygon::<i32>::from(vec![[0, 0], [10, 0], [10, 10], [0, 10]]);
+    /// let diagram = Builder::<i32, f64>::default()
+    ///     .with_polygons([square])?
+    ///     .build()?;
+    /// for cell in diagram.cells().iter() {
+    ///     let cell = cell.get();
+    ///     if let Some(PathSource::Polygon { polygon, ring, element }) =
+    ///         diagram.cell_path_source(cell.id())?
+    ///     {
+    ///         assert_eq!((polygon, ring), (0, 0));
+    ///         assert_eq!(cell.contains_segment(), matches!(element, PathElement::Edge(_)));
+    ///     } else {
+    ///         unreachable!()
+    ///     }
+    /// }
+    /// # Ok::<(), BvError>(())
+    /// ```
+    /// # Errors
+    /// Will return `BvError::ValueError` if a ring has less than three vertices.
+    pub fn with_polygons<T, IT>(mut self, polygons: T) -> Result<Self, BvError>
+    where
+        T: IntoIterator<Item = IT>,
+        IT: Into<Polygon<I>>,
+    {
+        for polygon in polygons.into_iter().map(|p| -> Polygon<I> { p.into() }) {
+            let path = self.polygons_added_;
+            for (ring_index, ring) in polygon.rings().enumerate() {
+                // a repeated first point closes the ring explicitly, skip it
+                let ring = match ring.split_last() {
+                    Some((last, rest)) if ring.len() > 1 && *last == ring[0] => rest,
+                    _ => ring.as_slice(),
+                };
+                if ring.len() < 3 {
+                    return Err(BvError::ValueError(format!(
+                        "Ring {} of polygon {} has less than three vertices",
+                        ring_index, path
+                    )));
                 }
-            };
-            self.site_events_.push(se);
-            self.index_ += 1;
+                self.path_records_.push(VD::PathRecord {
+                    first_: self.num_segments_,
+                    len_: ring.len(),
+                    path_: path,
+                    ring_: Some(ring_index),
+                });
+                for (i, start) in ring.iter().enumerate() {
+                    self.push_segment_(Line::new(*start, ring[(i + 1) % ring.len()]));
+                }
+            }
+            self.polygons_added_ += 1;
         }
-        self.segments_added_ = true;
         Ok(self)
     }
 
+    /// Inserts polylines, i.e. open chains of segments.
+    /// The origin of each resulting cell, polyline and vertex/edge of that polyline, can be
+    /// retrieved with `Diagram::cell_path_source()`.
+    /// This method accepts iterators of iterators of anything that implements
+    /// `Into<boostvoronoi::geometry::Point>`, e.g. `geo::LineString`.
+    /// # Errors
+    /// Will return `BvError::ValueError` if a polyline has less than two vertices.
+    pub fn with_polylines<T, L, IT>(mut self, polylines: T) -> Result<Self, BvError>
+    where
+        T: IntoIterator<Item = L>,
+        L: IntoIterator<Item = IT>,
+        IT: Into<Point<I>>,
+    {
+        for polyline in polylines.into_iter() {
+            let polyline: Vec<Point<I>> = polyline.into_iter().map(|p| p.into()).collect();
+            if polyline.len() < 2 {
+                return Err(BvError::ValueError(format!(
+                    "Polyline {} has less than two vertices",
+                    self.polylines_added_
+                )));
+            }
+            self.path_records_.push(VD::PathRecord {
+                first_: self.num_segments_,
+                len_: polyline.len() - 1,
+                path_: self.polylines_added_,
+                ring_: None,
+            });
+            for w in polyline.windows(2) {
+                self.push_segment_(Line::new(w[0], w[1]));
+            }
+            self.polylines_added_ += 1;
+        }
+        Ok(self)
+    }
+
+    /// Registers a callback that will be called from the main loop of `build()` every
+    /// `interval` processed events, and once when all the events have been processed.
+    /// If the callback returns `ControlFlow::Break` the build is aborted and `build()` returns
+    /// `BvError::Cancelled`. The result of the last call is ignored, as there is no work left to
+    /// abort.
+    /// The callback is kept when the builder is `reset()`.
+    /// ```
+    /// # use boostvoronoi_core::builder::Builder;
+    /// # use boostvoronoi_core::BvError;
+    /// use std::ops::ControlFlow;
+    /// let points: Vec<[i32; 2]> = (0..1000).map(|i| [i, (i * i) % 997]).collect();
+    /// let result = Builder::<i32, f64>::default()
+    ///     .with_vertices(points.iter().copied())?
+    ///     .with_progress(100, |progress| {
+    ///         if progress.site_events_processed > progress.site_events_total / 2 {
+    ///             ControlFlow::Break(())
+    ///         } else {
+    ///             ControlFlow::Continue(())
+    ///         }
+    ///     })
+    ///     .build();
+    /// assert!(matches!(result, Err(BvError::Cancelled)));
+    /// # Ok::<(), BvError>(())
+    /// ```
+    pub fn with_progress<P>(mut self, interval: usize, callback: P) -> Self
+    where
+        P: FnMut(&BuildProgress) -> ControlFlow<()> + 'static,
+    {
+        self.progress_ = Some(ProgressObserver {
+            interval_: interval.max(1),
+            callback_: Box::new(callback),
+        });
+        self
+    }
+
+    /// Registers an observer that will receive a `SweepEvent` for every step of the sweep-line
+    /// algorithm, see the `trace` module.
+    /// The observer is kept when the builder is `reset()`.
+    /// ```
+    /// # use boostvoronoi_core::builder::Builder;
+    /// # use boostvoronoi_core::trace::SweepEvent;
+    /// # use boostvoronoi_core::BvError;
+    /// use std::cell::RefCell;
+    /// use std::rc::Rc;
+    /// let vertices_created = Rc::new(RefCell::new(0));
+    /// let counter = Rc::clone(&vertices_created);
+    /// let diagram = Builder::<i32, f64>::default()
+    ///     .with_vertices([[0, 0], [10, 0], [0, 10], [10, 10]])?
+    ///     .with_observer(move |event: &SweepEvent<i32, f64>| {
+    ///         if let SweepEvent::VertexCreated { .. } = event {
+    ///             *counter.borrow_mut() += 1;
+    ///         }
+    ///     })
+    ///     .build()?;
+    /// assert_eq!(*vertices_created.borrow(), 2);
+    /// # Ok::<(), BvError>(())
+    /// ```
+    pub fn with_observer<O>(mut self, observer: O) -> Self
+    where
+        O: VT::SweepObserver<I, F> + 'static,
+    {
+        self.observer_ = Some(Box::new(observer));
+        self
+    }
+
+    /// Passes an event to the observer, if there is one. Takes the observer field rather than
+    /// `self`, so that events can be built from borrowed builder state.
+    #[inline(always)]
+    fn trace_(observer: &mut Observer<I, F>, event: impl FnOnce() -> VT::SweepEvent<I, F>) {
+        if let Some(observer) = observer.as_mut() {
+            observer.on_event(&event());
+        }
+    }
+
+    /// Creates the site event of a vertex
+    fn push_vertex_(&mut self, v: Point<I>) {
+        let mut s = VSE::SiteEvent::<I, F>::new(VSE::Site::Point(v), self.num_vertices_);
+        s.or_source_category(VD::ColorBits::SINGLE_POINT__BIT);
+        self.site_events_.push(s);
+        self.num_vertices_ += 1;
+    }
+
+    /// Stores the id of the next vertex or segment to be inserted
+    fn push_site_id_(&mut self, id: ID, vertex: bool) {
+        let (ids, index) = if vertex {
+            (&mut self.site_ids_.vertices_, self.num_vertices_)
+        } else {
+            (&mut self.site_ids_.segments_, self.num_segments_)
+        };
+        ids.resize_with(index, || None);
+        ids.push(Some(id));
+    }
+
+    /// Creates the site events of a segment
+    fn push_segment_(&mut self, line: Line<I>) {
+        type Cb = VD::ColorBits;
+        #[allow(clippy::branches_sharing_code)]
+        let se = if line.start == line.end {
+            // take care of the case when a line is actually a point
+            let mut se =
+                VSE::SiteEvent::<I, F>::new(VSE::Site::Point(line.start), self.num_segments_);
+            se.or_source_category(Cb::SINGLE_POINT__BIT);
+            se
+        } else {
+            // the line was a line with unique endpoints
+            let mut s1 =
+                VSE::SiteEvent::<I, F>::new(VSE::Site::Point(line.start), self.num_segments_);
+            s1.or_source_category(Cb::SEGMENT_START_POINT__BIT);
+
+            let mut s2 =
+                VSE::SiteEvent::<I, F>::new(VSE::Site::Point(line.end), self.num_segments_);
+            s2.or_source_category(Cb::SEGMENT_END_POINT__BIT);
+
+            self.segment_site_events_.push(s1);
+            self.segment_site_events_.push(s2);
+            let site = VSE::Site::from(line);
+
+            if VP::point_comparison::point_comparison(line.start, line.end) {
+                let mut s3 = VSE::SiteEvent::<I, F>::new(site, self.num_segments_);
+                s3.or_source_category(Cb::INITIAL_SEGMENT);
+                s3
+            } else {
+                let mut s3 = VSE::SiteEvent::<I, F>::new(site.reverse(), self.num_segments_);
+                s3.or_source_category(Cb::REVERSE_SEGMENT);
+                s3
+            }
+        };
+        self.segment_site_events_.push(se);
+        self.num_segments_ += 1;
+    }
+
+    /// Checks the input geometry added so far against the contract of the algorithm:
+    /// segments may only intersect at their endpoints, and points may not lie inside segments.
+    /// All predicates are exact, the offending input is reported by source index.
+    /// ```
+    /// # use boostvoronoi_core::geometry::Line;
+    /// # use boostvoronoi_core::builder::Builder;
+    /// # use boostvoronoi_core::BvError;
+    /// let s = vec![Line::from([0_i32, 0, 10, 10]), Line::from([0, 10, 10, 0])];
+    /// let builder = Builder::<i32, f64>::default().with_segments(s.iter())?;
+    /// let report = builder.validate();
+    /// assert!(!report.is_valid());
+    /// assert_eq!(report.intersecting_segments, vec![(0, 1)]);
+    /// # Ok::<(), BvError>(())
+    /// ```
+    pub fn validate(&self) -> VV::ValidationReport {
+        // zero length segments are point sites
+        let points: Vec<_> = self
+            .site_events_
+            .iter()
+            .map(|s| (s.initial_index(), s.point0()))
+            .chain(
+                self.segment_site_events_
+                    .iter()
+                    .filter(|s| {
+                        VD::SourceCategory::from(s.source_category())
+                            == VD::SourceCategory::SinglePoint
+                    })
+                    .map(|s| (self.num_vertices_ + s.initial_index(), s.point0())),
+            )
+            .collect();
+        let segments: Vec<_> = self
+            .segment_site_events_
+            .iter()
+            .filter(|s| s.is_segment())
+            .map(|s| {
+                (
+                    self.num_vertices_ + s.initial_index(),
+                    Line::new(s.point0(), s.point1()),
+                )
+            })
+            .collect();
+        VV::validate(&points, &segments)
+    }
+
     /// Run sweep-line algorithm and fill output data structure.
     pub fn build(mut self) -> Result<VD::Diagram<F>, BvError> {
-        let mut output: VD::Diagram<F> = VD::Diagram::<F>::new(self.site_events_.len());
+        let mut output = VD::Diagram::<F>::new(0);
+        self.build_into(&mut output)?;
+        Ok(output)
+    }
+
+    /// Run sweep-line algorithm and fill a `SyncDiagram` directly, without the `Rc<Cell>`
+    /// wrapped intermediate `Diagram`. The result is `Send` and `Sync`, so it can be built on a
+    /// worker thread and handed over to other threads.
+    /// ```
+    /// # use boostvoronoi_core::builder::Builder;
+    /// # use boostvoronoi_core::BvError;
+    /// let handle = std::thread::spawn(|| {
+    ///     Builder::<i32, f64>::default()
+    ///         .with_vertices([[0, 0], [10, 0], [0, 10]])?
+    ///         .build_sync()
+    /// });
+    /// let diagram = handle.join().unwrap()?;
+    /// assert_eq!(diagram.cells().len(), 3);
+    /// # Ok::<(), BvError>(())
+    /// ```
+    pub fn build_sync(mut self) -> Result<SD::SyncDiagram<F>, BvError> {
+        let mut output = SD::SyncDiagram::<F>::default();
+        self.build_sync_into(&mut output)?;
+        Ok(output)
+    }
+
+    /// Run sweep-line algorithm, and return the diagram together with the user defined id of
+    /// each cell, see `with_identified_vertices()` and `with_identified_segments()`.
+    /// Cells of sites inserted without an id are missing from the id table.
+    pub fn build_identified(mut self) -> Result<(VD::Diagram<F>, CellAttributes<ID>), BvError>
+    where
+        ID: Clone,
+    {
+        let ids = self.site_ids_.take_by_source_index(self.num_vertices_);
+        let diagram = self.build()?;
+        let cells = diagram.cells().iter().map(|c| {
+            let c = c.get();
+            (c.id(), c.source_index())
+        });
+        let ids = SiteIdStore::into_cell_ids(ids, cells);
+        Ok((diagram, ids))
+    }
+
+    /// Run sweep-line algorithm, and return a `SyncDiagram` together with the user defined id
+    /// of each cell, see `build_identified()`.
+    pub fn build_sync_identified(
+        mut self,
+    ) -> Result<(SD::SyncDiagram<F>, CellAttributes<ID>), BvError>
+    where
+        ID: Clone,
+    {
+        let ids = self.site_ids_.take_by_source_index(self.num_vertices_);
+        let diagram = self.build_sync()?;
+        let cells = diagram.cells().iter().map(|c| (c.id(), c.source_index()));
+        let ids = SiteIdStore::into_cell_ids(ids, cells);
+        Ok((diagram, ids))
+    }
+    /// Removes all the input geometry from the builder, but keeps the allocated memory.
+    pub fn reset(&mut self) {
+        self.site_events_.clear();
+        self.segment_site_events_.clear();
+        self.circle_events_.clear();
+        self.end_points_.clear();
+        self.beach_line_.clear();
+        self.num_vertices_ = 0;
+        self.num_segments_ = 0;
+        self.path_records_.clear();
+        self.polygons_added_ = 0;
+        self.polylines_added_ = 0;
+        self.site_ids_.vertices_.clear();
+        self.site_ids_.segments_.clear();
+        #[cfg(feature = "console_debug")]
+        {
+            self.debug_circle_counter_ = 0;
+            self.debug_site_counter_ = 0;
+        }
+    }
 
+    /// Run sweep-line algorithm and fill an existing output data structure, the previous content
+    /// of `output` is cleared. The builder is `reset()` afterwards, so both the builder and the
+    /// diagram can be reused without reallocating their memory.
+    /// If the build fails, or is cancelled, `output` is left empty.
+    /// ```
+    /// # use boostvoronoi_core::builder::Builder;
+    /// # use boostvoronoi_core::diagram::Diagram;
+    /// # use boostvoronoi_core::BvError;
+    /// let mut builder = Builder::<i32, f64>::default();
+    /// let mut diagram = Diagram::<f64>::default();
+    /// for i in 0..10 {
+    ///     builder = builder.with_vertices([[0, 0], [10, i], [i, 10]])?;
+    ///     builder.build_into(&mut diagram)?;
+    ///     assert_eq!(diagram.cells().len(), 3);
+    /// }
+    /// # Ok::<(), BvError>(())
+    /// ```
+    pub fn build_into(&mut self, output: &mut VD::Diagram<F>) -> Result<(), BvError> {
+        self.build_into_(output)
+    }
+
+    /// Run sweep-line algorithm and fill an existing `SyncDiagram`, the previous content of
+    /// `output` is cleared. The builder is `reset()` afterwards, see `Builder::build_into()`.
+    /// If the build fails, or is cancelled, `output` is left empty.
+    pub fn build_sync_into(&mut self, output: &mut SD::SyncDiagram<F>) -> Result<(), BvError> {
+        self.build_into_(output)
+    }
+
+    fn build_into_<O: VD::DiagramOutput<F>>(&mut self, output: &mut O) -> Result<(), BvError> {
+        output.clear_();
+        let rv = self.sweep_(output);
+        if rv.is_err() {
+            // don't leave a half built diagram behind
+            output.clear_();
+        }
+        self.reset();
+        rv
+    }
+
+    fn sweep_<O: VD::DiagramOutput<F>>(&mut self, output: &mut O) -> Result<(), BvError> {
         let mut site_event_iterator_: VSE::SiteEventIndexType = self.init_sites_queue();
 
+        output.reserve_(self.site_events_.len());
+        output.path_records_mut_().append(&mut self.path_records_);
+
         tln!("********************************************************************************");
         tln!("->build()");
         tln!("********************************************************************************");
 
-        self.init_beach_line(&mut site_event_iterator_, &mut output)?;
+        self.init_beach_line(&mut site_event_iterator_, output)?;
         #[cfg(feature = "console_debug")]
         let mut i = 0;
+        let mut progress = BuildProgress {
+            site_events_total: self.site_events_.len(),
+            ..BuildProgress::default()
+        };
+        let mut events_until_progress = self.progress_.as_ref().map_or(0, |p| p.interval_);
 
         // The algorithm stops when there are no events to process.
         while !self.circle_events_.is_empty() || (site_event_iterator_ != self.site_events_.len()) {
@@ -196,7 +693,7 @@ impl<I: InputType, F: OutputType> Builder<I, F> {
                 tln!(
                     "loop:{} circle_events_:{} num_vertices:{} beach_line:{} debug_site_counter:{} debug_circle_counter:{}",
                     i,self.circle_events_.len(),
-                    output.num_vertices(),
+                    output.num_vertices_(),
                     self.beach_line_.len(),
                     self.debug_site_counter_,
                     self.debug_circle_counter_,
@@ -209,29 +706,64 @@ impl<I: InputType, F: OutputType> Builder<I, F> {
                 i += 1;
             }
             if self.circle_events_.is_empty() {
-                self.process_site_event(&mut site_event_iterator_, &mut output)?;
+                self.process_site_event(&mut site_event_iterator_, output)?;
             } else if site_event_iterator_ == self.site_events_.len() {
-                self.process_circle_event(&mut output)?;
+                self.process_circle_event(output)?;
+                progress.circle_events_processed += 1;
             } else if VP::event_comparison_predicate::event_comparison_bif::<I, F>(
                 &self.site_events_[site_event_iterator_],
                 // we checked with !is_empty(), unwrap is safe
                 self.circle_events_.peek().unwrap(),
             ) {
-                self.process_site_event(&mut site_event_iterator_, &mut output)?;
+                self.process_site_event(&mut site_event_iterator_, output)?;
             } else {
-                self.process_circle_event(&mut output)?;
+                self.process_circle_event(output)?;
+                progress.circle_events_processed += 1;
             }
             self.circle_events_.pop_inactive_at_top()?;
+
+            if let Some(observer) = self.progress_.as_mut() {
+                events_until_progress -= 1;
+                if events_until_progress == 0 {
+                    events_until_progress = observer.interval_;
+                    progress.site_events_processed = site_event_iterator_;
+                    if (observer.callback_)(&progress).is_break() {
+                        return Err(BvError::Cancelled);
+                    }
+                }
+            }
+        }
+        if let Some(observer) = self.progress_.as_mut() {
+            progress.site_events_processed = site_event_iterator_;
+            // all the events are processed, there is nothing left to cancel
+            let _ = (observer.callback_)(&progress);
         }
 
         self.beach_line_.clear();
 
         // Finish the diagram construction.
         output.finish();
-        Ok(output)
+        Self::trace_(&mut self.observer_, || VT::SweepEvent::Finished {
+            num_cells: output.num_cells_(),
+            num_edges: output.num_edges_(),
+            num_vertices: output.num_vertices_(),
+        });
+        Ok(())
     }
 
     pub(crate) fn init_sites_queue(&mut self) -> VSE::SiteEventIndexType {
+        // Number the segments after the vertices. The vertices goes first in the list, so they
+        // take precedence over segment endpoints when duplicates are removed.
+        let num_vertices = self.num_vertices_;
+        self.site_events_
+            .extend(self.segment_site_events_.drain(..).map(|mut s| {
+                s.set_initial_index(num_vertices + s.initial_index());
+                s
+            }));
+        for r in self.path_records_.iter_mut() {
+            r.first_ += num_vertices;
+        }
+
         // Sort site events.
         self.site_events_
             .sort_by(VP::event_comparison_predicate::event_comparison_ii::<I, F>);
@@ -253,18 +785,21 @@ impl<I: InputType, F: OutputType> Builder<I, F> {
         site_event_iterator_
     }
 
-    pub(crate) fn init_beach_line(
+    pub(crate) fn init_beach_line<O: VD::DiagramOutput<F>>(
         &mut self,
         site_event_iterator_: &mut VSE::SiteEventIndexType,
-        output: &mut VD::Diagram<F>,
+        output: &mut O,
     ) -> Result<(), BvError> {
         if self.site_events_.is_empty() {
             return Ok(());
         }
         if self.site_events_.len() == 1 {
             // Handle single site event case.
-            let site = &self.site_events_[0];
-            output.process_single_site_(site); //site.sorted_index(), site.initial_index(), site.source_category());
+            let site = self.site_events_[0];
+            Self::trace_(&mut self.observer_, || VT::SweepEvent::SiteEventProcessed {
+                site: (&site).into(),
+            });
+            output.process_single_site_(&site); //site.sorted_index(), site.initial_index(), site.source_category());
             *site_event_iterator_ += 1;
         } else {
             let mut skip = 0;
@@ -280,6 +815,15 @@ impl<I: InputType, F: OutputType> Builder<I, F> {
                 skip += 1;
             }
 
+            // The first two sites, or all the collinear vertical sites, are processed together
+            let processed = if skip == 1 { 2 } else { skip };
+            for i in 0..processed {
+                let site = self.site_events_[i];
+                Self::trace_(&mut self.observer_, || VT::SweepEvent::SiteEventProcessed {
+                    site: (&site).into(),
+                });
+            }
+
             if skip == 1 {
                 // Init beach line with the first two sites.
                 self.init_beach_line_default(site_event_iterator_, output)?;
@@ -293,10 +837,10 @@ impl<I: InputType, F: OutputType> Builder<I, F> {
 
     /// Init beach line with the two first sites.
     /// The first site is always a point.
-    fn init_beach_line_default(
+    fn init_beach_line_default<O: VD::DiagramOutput<F>>(
         &mut self,
         site_event_iterator_: &mut VSE::SiteEventIndexType,
-        output: &mut VD::Diagram<F>,
+        output: &mut O,
     ) -> Result<(), BvError> {
         // Get the first and the second site event.
         let first = *site_event_iterator_ - 1;
@@ -318,10 +862,10 @@ impl<I: InputType, F: OutputType> Builder<I, F> {
     }
 
     /// Init beach line with collinear sites.
-    fn init_beach_line_collinear_sites(
+    fn init_beach_line_collinear_sites<O: VD::DiagramOutput<F>>(
         &mut self,
         site_event_iterator_: &VSE::SiteEventIndexType,
-        output: &mut VD::Diagram<F>,
+        output: &mut O,
     ) -> Result<(), BvError> {
         let mut it_first: VSE::SiteEventIndexType = 0;
         let mut it_second: VSE::SiteEventIndexType = 1;
@@ -333,22 +877,27 @@ impl<I: InputType, F: OutputType> Builder<I, F> {
             let new_node_key = VB::BeachLineNodeKey::<I, F>::new_2(*first, *second);
 
             // Update the output.
-            let edge = output.insert_new_edge_2_(*first, *second).0;
+            let (edge, twin) = output.insert_new_edge_2_(*first, *second);
+            Self::trace_(&mut self.observer_, || VT::SweepEvent::EdgeCreated {
+                edge,
+                twin,
+            });
 
             // Insert a new bisector into the beach line.
             #[cfg(feature = "console_debug")]
-            let _ = self.beach_line_.insert(
+            let (key, index) = self.beach_line_.insert(
                 self.beach_line_.last_position()?,
                 new_node_key,
                 VB::BeachLineNodeData::new_1(edge),
                 &self.circle_events_,
-            );
+            )?;
             #[cfg(not(feature = "console_debug"))]
-            let _ = self.beach_line_.insert(
+            let (key, index) = self.beach_line_.insert(
                 self.beach_line_.last_position()?,
                 new_node_key,
                 Some(VB::BeachLineNodeData::new_1(edge)),
-            );
+            )?;
+            Self::trace_node_inserted_(&mut self.observer_, &key, index);
             // Update iterators.
             it_first += 1;
             it_second += 1;
@@ -362,8 +911,7 @@ impl<I: InputType, F: OutputType> Builder<I, F> {
         beachline_ptr: &PIterator<VB::BeachLineNodeKey<I, F>, VB::BeachLineNodeDataType>,
     ) -> Result<(), BvError> {
         if let Some(mut node_cell) = beachline_ptr.get_v()?.get() {
-            self.circle_events_
-                .deactivate(node_cell.get_circle_event_id());
+            self.deactivate_circle_event_id_(node_cell.get_circle_event_id());
 
             // make sure there are no dangling references to deactivated circle events..
             node_cell.set_circle_event_id(None);
@@ -372,10 +920,52 @@ impl<I: InputType, F: OutputType> Builder<I, F> {
         Ok(())
     }
 
-    pub(crate) fn process_site_event(
+    /// Deactivates a circle event, reporting it to the observer if it was active
+    #[inline(always)]
+    fn deactivate_circle_event_id_(&mut self, circle_event_id: Option<VC::CircleEventIndex>) {
+        if let Some(id) = circle_event_id {
+            if self.observer_.is_some() && self.circle_events_.is_active(id) {
+                Self::trace_(&mut self.observer_, || {
+                    VT::SweepEvent::CircleEventDeactivated { id: id.0 }
+                });
+            }
+        }
+        self.circle_events_.deactivate(circle_event_id);
+    }
+
+    #[inline(always)]
+    fn trace_node_inserted_(
+        observer: &mut Observer<I, F>,
+        key: &VB::BeachLineNodeKey<I, F>,
+        node: VB::BeachLineIndex,
+    ) {
+        Self::trace_(observer, || VT::SweepEvent::BeachLineNodeInserted {
+            node: node.0,
+            left_site: key.left_site().into(),
+            right_site: key.right_site().into(),
+        });
+    }
+
+    #[inline(always)]
+    fn trace_node_removed_(
+        observer: &mut Observer<I, F>,
+        ptr: &PIterator<VB::BeachLineNodeKey<I, F>, VB::BeachLineNodeDataType>,
+    ) -> Result<(), BvError> {
+        if observer.is_some() {
+            let key = ptr.get_k()?;
+            Self::trace_(observer, || VT::SweepEvent::BeachLineNodeRemoved {
+                node: ptr.current(),
+                left_site: key.left_site().into(),
+                right_site: key.right_site().into(),
+            });
+        }
+        Ok(())
+    }
+
+    pub(crate) fn process_site_event<O: VD::DiagramOutput<F>>(
         &mut self,
         site_event_iterator_: &mut VSE::SiteEventIndexType,
-        output: &mut VD::Diagram<F>,
+        output: &mut O,
     ) -> Result<(), BvError> {
         #[cfg(feature = "console_debug")]
         {
@@ -400,6 +990,9 @@ impl<I: InputType, F: OutputType> Builder<I, F> {
                     ))
                 })?;
             tln!("processing site:{}", site_event); //dbg!(&site_event);
+            Self::trace_(&mut self.observer_, || VT::SweepEvent::SiteEventProcessed {
+                site: site_event.into(),
+            });
 
             // Move site iterator.
             let mut last_index = *site_event_iterator_ + 1;
@@ -413,10 +1006,11 @@ impl<I: InputType, F: OutputType> Builder<I, F> {
                 {
                     // we checked with !is_empty(), unwrap is safe
                     let b_it = self.end_points_.pop().unwrap();
-                    let mut b_it = cpp_map::PIterator::new_2(
+                    let mut b_it = PIterator::new_2(
                         Rc::clone(&self.beach_line_.beach_line_),
                         b_it.beachline_index().0,
                     );
+                    Self::trace_node_removed_(&mut self.observer_, &b_it)?;
                     #[cfg(feature = "console_debug")]
                     {
                         self.beach_line_.dbgpa_compat_(&self.circle_events_)?;
@@ -472,9 +1066,16 @@ impl<I: InputType, F: OutputType> Builder<I, F> {
                 self.beach_line_.dbgpa_compat_(&self.circle_events_)?;
             }
         }
+        let first_index = *site_event_iterator_;
         while *site_event_iterator_ != last_index {
             // site_event is a copy of the the event site_event_iterator_ is indexing
             let mut site_event = self.site_events_[*site_event_iterator_];
+            if *site_event_iterator_ != first_index {
+                // the segments starting at the same point as the first site
+                Self::trace_(&mut self.observer_, || VT::SweepEvent::SiteEventProcessed {
+                    site: (&site_event).into(),
+                });
+            }
             let mut left_it = right_it.clone();
 
             // Do further processing depending on the above node position.
@@ -602,9 +1203,9 @@ impl<I: InputType, F: OutputType> Builder<I, F> {
     /// (B, C) bisector and change (A, B) bisector to the (A, C). That's
     /// why we use replace_key() there and take all the responsibility that
     /// map data structure keeps correct ordering.
-    pub(crate) fn process_circle_event(
+    pub(crate) fn process_circle_event<O: VD::DiagramOutput<F>>(
         &mut self,
-        output: &mut VD::Diagram<F>,
+        output: &mut O,
     ) -> Result<(), BvError> {
         #[cfg(feature = "console_debug")]
         {
@@ -622,6 +1223,14 @@ impl<I: InputType, F: OutputType> Builder<I, F> {
             ))
         })?;
         tln!("processing:{:?}", circle_event);
+        Self::trace_(&mut self.observer_, || {
+            VT::SweepEvent::CircleEventProcessed {
+                id: circle_event.get_index().map_or(usize::MAX, |i| i.0),
+                x: cast::<f64, F>(circle_event.x()),
+                y: cast::<f64, F>(circle_event.y()),
+                lower_x: cast::<f64, F>(circle_event.lower_x()),
+            }
+        });
 
         if !self
             .circle_events_
@@ -714,6 +1323,13 @@ impl<I: InputType, F: OutputType> Builder<I, F> {
             }
 
             it_first.replace_key(it_first_key_after)?;
+            Self::trace_(&mut self.observer_, || {
+                VT::SweepEvent::BeachLineNodeUpdated {
+                    node: it_first.current(),
+                    left_site: it_first_key_after.left_site().into(),
+                    right_site: it_first_key_after.right_site().into(),
+                }
+            });
 
             #[cfg(feature = "console_debug")]
             {
@@ -729,9 +1345,22 @@ impl<I: InputType, F: OutputType> Builder<I, F> {
 
         // Insert the new bisector into the beach line.
         {
-            let edge = output
-                .insert_new_edge_5_(site1, site3, circle_event, bisector1, bisector2)
-                .0;
+            let (edge, twin) =
+                output.insert_new_edge_5_(site1, site3, circle_event, bisector1, bisector2);
+            Self::trace_(&mut self.observer_, || {
+                let vertex = VD::VertexIndex(output.num_vertices_() - 1);
+                // the vertex was just created, unwrap is safe
+                let v = output.vertex_(vertex.0).unwrap();
+                VT::SweepEvent::VertexCreated {
+                    vertex,
+                    x: v.x(),
+                    y: v.y(),
+                }
+            });
+            Self::trace_(&mut self.observer_, || VT::SweepEvent::EdgeCreated {
+                edge,
+                twin,
+            });
             let data = if let Some(ref mut node) = it_first.get_v()?.get() {
                 node.set_edge_id(edge);
                 //tln!("Updated node data: {:?} new edge:{}", node, edge.0);
@@ -773,6 +1402,7 @@ impl<I: InputType, F: OutputType> Builder<I, F> {
         #[cfg(feature = "console_debug")]
         assert_ne!(it_first.current(), it_last.current());
 
+        Self::trace_node_removed_(&mut self.observer_, &it_last)?;
         let _ = it_last.remove_current()?;
 
         #[cfg(feature = "console_debug")]
@@ -796,7 +1426,7 @@ impl<I: InputType, F: OutputType> Builder<I, F> {
         // Check new triplets formed by the neighboring arcs
         // to the left for potential circle events.
         if !self.beach_line_.is_empty() && !it_first.is_at_head()? {
-            self.circle_events_.deactivate(
+            self.deactivate_circle_event_id_(
                 it_first
                     .get_v()?
                     .get()
@@ -821,8 +1451,9 @@ impl<I: InputType, F: OutputType> Builder<I, F> {
 
         if it_last.is_ok()? {
             let it_last_node = it_last.get_v()?;
-            self.circle_events_
-                .deactivate(it_last_node.get().and_then(|x| x.get_circle_event_id()));
+            self.deactivate_circle_event_id_(
+                it_last_node.get().and_then(|x| x.get_circle_event_id()),
+            );
 
             let site_r1 = *it_last.get_k()?.right_site();
             self.activate_circle_event(
@@ -836,13 +1467,13 @@ impl<I: InputType, F: OutputType> Builder<I, F> {
     }
 
     /// Insert new nodes into the beach line. Update the output.
-    fn insert_new_arc(
+    fn insert_new_arc<O: VD::DiagramOutput<F>>(
         &mut self,
         site_arc1: VSE::SiteEvent<I, F>,
         site_arc2: VSE::SiteEvent<I, F>,
         site_event: VSE::SiteEvent<I, F>,
         position: usize,
-        output: &mut VD::Diagram<F>,
+        output: &mut O,
     ) -> Result<VB::BeachLineIndex, BvError> {
         tln!(
             "->insert_new_arc(\n  site_arc1:{:?}\n  ,site_arc2:{:?}\n  ,site_event:{:?}",
@@ -863,20 +1494,25 @@ impl<I: InputType, F: OutputType> Builder<I, F> {
         tln!("new bl key:{:?}", new_right_node);
         // Update the output.
         let edges = output.insert_new_edge_2_(site_arc2, site_event);
+        Self::trace_(&mut self.observer_, || VT::SweepEvent::EdgeCreated {
+            edge: edges.0,
+            twin: edges.1,
+        });
 
         #[cfg(not(feature = "console_debug"))]
-        let _ = self.beach_line_.insert(
+        let (key, index) = self.beach_line_.insert(
             position,
             new_right_node,
             Some(VB::BeachLineNodeData::new_1(edges.1)),
-        );
+        )?;
         #[cfg(feature = "console_debug")]
-        let _ = self.beach_line_.insert(
+        let (key, index) = self.beach_line_.insert(
             position,
             new_right_node,
             VB::BeachLineNodeData::new_1(edges.1),
             &self.circle_events_,
         )?;
+        Self::trace_node_inserted_(&mut self.observer_, &key, index);
 
         #[cfg(feature = "console_debug")]
         {
@@ -895,6 +1531,7 @@ impl<I: InputType, F: OutputType> Builder<I, F> {
             let (_, index) = self.beach_line_.insert_2(new_node, &self.circle_events_)?;
             #[cfg(not(feature = "console_debug"))]
             let (_, index) = self.beach_line_.insert_2(new_node)?;
+            Self::trace_node_inserted_(&mut self.observer_, &new_node, index);
 
             #[cfg(feature = "console_debug")]
             {
@@ -910,17 +1547,21 @@ impl<I: InputType, F: OutputType> Builder<I, F> {
 
         #[cfg(not(feature = "console_debug"))]
         {
-            Ok(self
+            let index = self
                 .beach_line_
                 .insert(position, new_left_node, Some(new_node_data))?
-                .1)
+                .1;
+            Self::trace_node_inserted_(&mut self.observer_, &new_left_node, index);
+            Ok(index)
         }
         #[cfg(feature = "console_debug")]
         {
-            let rv = Ok(self
+            let index = self
                 .beach_line_
                 .insert(position, new_left_node, new_node_data, &self.circle_events_)?
-                .1);
+                .1;
+            Self::trace_node_inserted_(&mut self.observer_, &new_left_node, index);
+            let rv = Ok(index);
             self.beach_line_.dbgpa_compat_(&self.circle_events_)?;
             self.beach_line_.dbgp_all_cmp_();
             println!();
@@ -949,7 +1590,15 @@ impl<I: InputType, F: OutputType> Builder<I, F> {
             // new circle event in the circle event queue.
             tln!("added circle event:{:?}", c_event);
 
+            let (x, y, lower_x) = (c_event.x(), c_event.y(), c_event.lower_x());
             let circle_event_id = self.circle_events_.associate_and_push(c_event);
+            Self::trace_(&mut self.observer_, || VT::SweepEvent::CircleEventCreated {
+                id: circle_event_id.0,
+                x: cast::<f64, F>(x),
+                y: cast::<f64, F>(y),
+                lower_x: cast::<f64, F>(lower_x),
+                sites: [(&site1).into(), (&site2).into(), (&site3).into()],
+            });
             let bn = self.beach_line_.get_node(&bisector_node)?;
             if let Some(mut bd) = bn.1.get() {
                 bd.set_circle_event_id(Some(circle_event_id));
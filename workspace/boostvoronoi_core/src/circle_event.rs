@@ -412,11 +412,9 @@ impl CircleEventQueue {
     pub(crate) fn clear(&mut self) {
         self.ce_by_order_.clear();
         self.ce_by_id_.clear();
-        self.inactive_circle_ids_ = {
-            let mut cids = VobU32::fill(512);
-            cids.resize(512, false);
-            cids
-        }
+        self.c_list_next_free_index_ = CircleEventIndex(0);
+        // keep the allocated memory
+        self.inactive_circle_ids_.set_all(false);
     }
 
     /// Take ownership of the circle event,
@@ -483,8 +481,8 @@ impl CircleEventQueue {
     }
 
     /// Returns the number of circle events (both active and inactive)
-    /// Only used by test code.
-    #[cfg(any(feature = "test", feature = "console_debug"))]
+    /// Only used by debug code.
+    #[cfg(feature = "console_debug")]
     pub(crate) fn len(&self) -> usize {
         self.ce_by_order_.len()
     }
@@ -0,0 +1,668 @@
+//          Copyright Eadf (github.com/eadf) 2021.
+// Distributed under the Boost Software License, Version 1.0.
+//    (See accompanying file LICENSE_1_0.txt or copy at
+//          http://www.boost.org/LICENSE_1_0.txt)
+
+//! Clipping of the diagram to a bounded, convex, region.
+//!
+//! The [`DiagramClipper`] turns the (possibly infinite) cells of a `Diagram` into closed polygons
+//! inside a [`ConvexRegion`], and the edges into finite polylines. Curved edges are discretized
+//! with [`crate::visual_utils::VoronoiVisualUtils::discretize()`].
+//! The clipped cells can also be measured exactly, see [`DiagramClipper::cell_measure()`].
+
+use crate::diagram::{self as VD, SiteGeometry};
+use crate::geometry::{Line, Point};
+use crate::measure::{measure, BoundaryPiece, CellMeasure};
+use crate::parabola::ParabolicArc;
+use crate::visual_utils::{Aabb2, SimpleAffine, VoronoiVisualUtils};
+use crate::{cast, BvError, InputType, OutputType};
+
+/// The points of an edge, and the direction of the edge if it is infinite
+type EdgePoints<F> = (Vec<[F; 2]>, Option<[F; 2]>);
+
+#[inline(always)]
+fn cross<F: OutputType>(o: [F; 2], a: [F; 2], b: [F; 2]) -> F {
+    (a[0] - o[0]) * (b[1] - o[1]) - (a[1] - o[1]) * (b[0] - o[0])
+}
+
+#[inline(always)]
+fn distance<F: OutputType>(a: [F; 2], b: [F; 2]) -> F {
+    (a[0] - b[0]).hypot(a[1] - b[1])
+}
+
+/// An infinite edge, represented as a ray starting at `origin` and going out to infinity along
+/// `direction`.
+///
+/// If the edge has a finite vertex, that vertex is the `origin`. Edges without any finite vertex
+/// are infinite in both directions (lines), then `origin` is some point on the line and
+/// `direction` points from the vertex0 end of the edge to the vertex1 end.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ray<F: OutputType> {
+    pub origin: [F; 2],
+    /// The direction of the ray, it is not normalized
+    pub direction: [F; 2],
+}
+
+/// A convex polygon used as clipping region, the vertices are stored in counter clockwise order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConvexRegion<F: OutputType> {
+    vertices_: Vec<[F; 2]>,
+}
+
+impl<F: OutputType> ConvexRegion<F> {
+    /// Creates a region from the vertices of a convex polygon, in clockwise or counter clockwise
+    /// order.
+    /// # Errors
+    /// Will return `BvError::ValueError` if the polygon is not convex or has no area.
+    pub fn new(vertices: Vec<[F; 2]>) -> Result<Self, BvError> {
+        let n = vertices.len();
+        let mut positive = false;
+        let mut negative = false;
+        for i in 0..n {
+            let c = cross(vertices[i], vertices[(i + 1) % n], vertices[(i + 2) % n]);
+            positive |= c > F::zero();
+            negative |= c < F::zero();
+        }
+        if n < 3 || positive == negative {
+            return Err(BvError::ValueError(
+                "The clipping region must be a convex polygon with a non-zero area".to_string(),
+            ));
+        }
+        let mut vertices = vertices;
+        if negative {
+            vertices.reverse();
+        }
+        Ok(Self {
+            vertices_: vertices,
+        })
+    }
+
+    /// Creates a rectangular region from an AABB.
+    /// # Errors
+    /// Will return `BvError::ValueError` if the AABB is uninitialized or has no area.
+    pub fn from_aabb(aabb: &Aabb2<F>) -> Result<Self, BvError> {
+        match (aabb.get_low(), aabb.get_high()) {
+            (Some(low), Some(high)) => Self::new(vec![
+                [low[0], low[1]],
+                [high[0], low[1]],
+                [high[0], high[1]],
+                [low[0], high[1]],
+            ]),
+            _ => Err(BvError::ValueError(
+                "The clipping AABB is uninitialized".to_string(),
+            )),
+        }
+    }
+
+    /// Returns the vertices of the region, in counter clockwise order
+    pub fn vertices(&self) -> &[[F; 2]] {
+        &self.vertices_
+    }
+
+    /// Returns true if the point is inside the region, or on its boundary
+    pub fn contains(&self, point: [F; 2]) -> bool {
+        let n = self.vertices_.len();
+        (0..n).all(|i| cross(self.vertices_[i], self.vertices_[(i + 1) % n], point) >= F::zero())
+    }
+
+    /// Returns the average of the vertices and the distance to the vertex furthest from it
+    fn bounding_circle(&self) -> ([F; 2], F) {
+        let n = cast::<usize, F>(self.vertices_.len());
+        let sum = self
+            .vertices_
+            .iter()
+            .fold([F::zero(), F::zero()], |s, v| [s[0] + v[0], s[1] + v[1]]);
+        let center = [sum[0] / n, sum[1] / n];
+        let radius = self
+            .vertices_
+            .iter()
+            .fold(F::zero(), |r, v| r.max(distance(center, *v)));
+        (center, radius)
+    }
+
+    /// Clips the line segment `p0`-`p1` to the region (Cyrus-Beck). Returns the range of the
+    /// parameter `t` of `p0 + t * (p1 - p0)` inside the region, if any.
+    fn clip_segment(&self, p0: [F; 2], p1: [F; 2]) -> Option<(F, F)> {
+        let n = self.vertices_.len();
+        let mut t0 = F::zero();
+        let mut t1 = F::one();
+        for i in 0..n {
+            let a = self.vertices_[i];
+            let b = self.vertices_[(i + 1) % n];
+            let s0 = cross(a, b, p0);
+            let s1 = cross(a, b, p1);
+            if s0 < F::zero() && s1 < F::zero() {
+                return None;
+            }
+            if s0 < F::zero() {
+                t0 = t0.max(s0 / (s0 - s1));
+            } else if s1 < F::zero() {
+                t1 = t1.min(s0 / (s0 - s1));
+            }
+        }
+        if t0 <= t1 {
+            Some((t0, t1))
+        } else {
+            None
+        }
+    }
+
+    /// Clips a polyline to the region, a polyline can leave and re-enter the region so the result
+    /// may consist of several pieces.
+    fn clip_polyline(&self, polyline: &[[F; 2]]) -> Vec<Vec<[F; 2]>> {
+        let mut pieces = Vec::<Vec<[F; 2]>>::new();
+        let mut open = false;
+        for w in polyline.windows(2) {
+            let (p0, p1) = (w[0], w[1]);
+            if let Some((t0, t1)) = self.clip_segment(p0, p1) {
+                let lerp = |t: F| {
+                    if t == F::zero() {
+                        p0
+                    } else if t == F::one() {
+                        p1
+                    } else {
+                        [p0[0] + (p1[0] - p0[0]) * t, p0[1] + (p1[1] - p0[1]) * t]
+                    }
+                };
+                if !(open && t0 == F::zero()) {
+                    pieces.push(vec![lerp(t0)]);
+                }
+                if let Some(piece) = pieces.last_mut() {
+                    piece.push(lerp(t1));
+                }
+                open = t1 == F::one();
+            } else {
+                open = false;
+            }
+        }
+        for piece in pieces.iter_mut() {
+            piece.dedup();
+        }
+        pieces.retain(|piece| piece.len() > 1);
+        pieces
+    }
+
+    /// Clips a simple polygon to the region (Sutherland-Hodgman).
+    fn clip_polygon(&self, subject: Vec<[F; 2]>) -> Vec<[F; 2]> {
+        let mut output = subject;
+        let mut input = Vec::with_capacity(output.len());
+        let n = self.vertices_.len();
+        for i in 0..n {
+            if output.is_empty() {
+                break;
+            }
+            std::mem::swap(&mut input, &mut output);
+            output.clear();
+            let a = self.vertices_[i];
+            let b = self.vertices_[(i + 1) % n];
+            let mut previous = input[input.len() - 1];
+            let mut previous_side = cross(a, b, previous);
+            for &current in input.iter() {
+                let current_side = cross(a, b, current);
+                if current_side >= F::zero() {
+                    if previous_side < F::zero() {
+                        output.push(Self::intersection(
+                            previous,
+                            current,
+                            previous_side,
+                            current_side,
+                        ));
+                    }
+                    output.push(current);
+                } else if previous_side >= F::zero() {
+                    output.push(Self::intersection(
+                        previous,
+                        current,
+                        previous_side,
+                        current_side,
+                    ));
+                }
+                previous = current;
+                previous_side = current_side;
+            }
+        }
+        output.dedup();
+        while output.len() > 1 && output.first() == output.last() {
+            let _ = output.pop();
+        }
+        output
+    }
+
+    /// The point between `p0` and `p1` where the (signed) side changes from `s0` to `s1`
+    #[inline]
+    fn intersection(p0: [F; 2], p1: [F; 2], s0: F, s1: F) -> [F; 2] {
+        let t = s0 / (s0 - s1);
+        [p0[0] + (p1[0] - p0[0]) * t, p0[1] + (p1[1] - p0[1]) * t]
+    }
+}
+
+/// Returns a point on the line of an infinite edge, and the direction of the edge. The direction
+/// points from the vertex0 end of the edge towards the vertex1 end.
+/// Ported from the `clip_infinite_edge` function of the boost voronoi visualizer.
+pub(crate) fn infinite_edge_line<I: InputType, F: OutputType>(
+    diagram: &VD::Diagram<F>,
+    edge_id: VD::EdgeIndex,
+    points: &[Point<I>],
+    segments: &[Line<I>],
+) -> Result<([F; 2], [F; 2]), BvError> {
+    let cell1_id = diagram.edge_get_cell(edge_id)?;
+    let cell2_id = diagram.edge_get_cell(diagram.edge_get_twin(edge_id)?)?;
+    let to_f = |p: Point<I>| [cast::<I, F>(p.x), cast::<I, F>(p.y)];
+    // Infinite edges could not be created by two segment sites.
+    let site = |cell_id: VD::CellIndex| {
+        SiteGeometry::of_cell(&diagram.get_cell(cell_id)?.get(), points, segments)
+    };
+    let site1 = site(cell1_id)?;
+    let cell1_is_point = matches!(site1, SiteGeometry::Point(_));
+    Ok(match (site1, site(cell2_id)?) {
+        (SiteGeometry::Point(p1), SiteGeometry::Point(p2)) => {
+            let (p1, p2) = (to_f(p1), to_f(p2));
+            let half = cast::<f64, F>(0.5);
+            (
+                [(p1[0] + p2[0]) * half, (p1[1] + p2[1]) * half],
+                [p1[1] - p2[1], p2[0] - p1[0]],
+            )
+        }
+        (SiteGeometry::Point(p), SiteGeometry::Segment(segment))
+        | (SiteGeometry::Segment(segment), SiteGeometry::Point(p)) => {
+            let dx = cast::<I, F>(segment.end.x) - cast::<I, F>(segment.start.x);
+            let dy = cast::<I, F>(segment.end.y) - cast::<I, F>(segment.start.y);
+            let direction = if (p == segment.start) ^ cell1_is_point {
+                [dy, -dx]
+            } else {
+                [-dy, dx]
+            };
+            (to_f(p), direction)
+        }
+        (SiteGeometry::Segment(_), SiteGeometry::Segment(_)) => {
+            return Err(BvError::InternalError(format!(
+                "The infinite edge {} is formed by two segments",
+                edge_id.0
+            )))
+        }
+    })
+}
+
+/// Clips the cells and edges of a `Diagram` to a `ConvexRegion`.
+///
+/// The clipper needs the input geometry of the `Builder` to compute the infinite edges and to
+/// discretize the curved edges.
+/// ```
+/// # use boostvoronoi_core::builder::Builder;
+/// # use boostvoronoi_core::clipping::{ConvexRegion, DiagramClipper};
+/// # use boostvoronoi_core::geometry::{Line, Point};
+/// # use boostvoronoi_core::visual_utils::Aabb2;
+/// # use boostvoronoi_core::BvError;
+/// let points: Vec<Point<i32>> = vec![[0, 0].into(), [10, 0].into()];
+/// let segments: Vec<Line<i32>> = vec![];
+/// let diagram = Builder::<i32, f64>::default()
+///     .with_vertices(points.iter())?
+///     .build()?;
+/// let region = ConvexRegion::from_aabb(&Aabb2::new_from_i32::<i32>(-10, -10, 20, 10))?;
+/// let clipper = DiagramClipper::new(&diagram, &points, &segments, region);
+/// let polygons = clipper.cell_polygons(0.1)?;
+/// assert_eq!(polygons[0].len(), 4);
+/// let near = |p: &[f64; 2], q: [f64; 2]| (p[0] - q[0]).hypot(p[1] - q[1]) < 1e-9;
+/// assert!(polygons[0].iter().any(|p| near(p, [-10.0, -10.0])));
+/// assert!(polygons[0].iter().any(|p| near(p, [5.0, 10.0])));
+/// # Ok::<(), BvError>(())
+/// ```
+pub struct DiagramClipper<'a, I: InputType, F: OutputType> {
+    diagram_: &'a VD::Diagram<F>,
+    points_: &'a [Point<I>],
+    segments_: &'a [Line<I>],
+    region_: ConvexRegion<F>,
+    // Infinite edges are cut off at this circle, it is large enough to contain the region and
+    // all the finite geometry with a wide margin.
+    far_center_: [F; 2],
+    far_radius_: F,
+}
+
+impl<'a, I: InputType, F: OutputType> DiagramClipper<'a, I, F> {
+    /// Creates a clipper for `diagram`. `points` and `segments` must be the input geometry of the
+    /// `Builder`, in the same order. Polygons and polylines count as their segments, in order.
+    pub fn new(
+        diagram: &'a VD::Diagram<F>,
+        points: &'a [Point<I>],
+        segments: &'a [Line<I>],
+        region: ConvexRegion<F>,
+    ) -> Self {
+        let (center, region_radius) = region.bounding_circle();
+        let to_f = |p: &Point<I>| [cast::<I, F>(p.x), cast::<I, F>(p.y)];
+        let radius = diagram
+            .vertices()
+            .iter()
+            .map(|v| {
+                let v = v.get();
+                [v.x(), v.y()]
+            })
+            .chain(points.iter().map(to_f))
+            .chain(segments.iter().flat_map(|l| [to_f(&l.start), to_f(&l.end)]))
+            .fold(region_radius, |r, p| r.max(distance(center, p)));
+        Self {
+            diagram_: diagram,
+            points_: points,
+            segments_: segments,
+            region_: region,
+            far_center_: center,
+            far_radius_: (radius + F::one()) * cast::<f64, F>(4.0),
+        }
+    }
+
+    /// Returns the clipping region
+    pub fn region(&self) -> &ConvexRegion<F> {
+        &self.region_
+    }
+
+    /// Returns the input geometry of a cell
+    fn site_(&self, cell_id: VD::CellIndex) -> Result<SiteGeometry<I>, BvError> {
+        SiteGeometry::of_cell(
+            &self.diagram_.get_cell(cell_id)?.get(),
+            self.points_,
+            self.segments_,
+        )
+    }
+
+    /// Returns a point on the infinite edge, and the direction of the edge. The direction points
+    /// from the vertex0 end of the edge towards the vertex1 end.
+    fn infinite_edge_ray_(&self, edge_id: VD::EdgeIndex) -> Result<([F; 2], [F; 2]), BvError> {
+        infinite_edge_line(self.diagram_, edge_id, self.points_, self.segments_)
+    }
+
+    /// Returns the ray representation of an infinite edge, or `None` if the edge is finite.
+    /// See [`Ray`].
+    pub fn edge_ray(&self, edge_id: VD::EdgeIndex) -> Result<Option<Ray<F>>, BvError> {
+        let vertex0 = self.diagram_.get_edge(edge_id)?.get().vertex0();
+        let vertex1 = self.diagram_.edge_get_vertex1(edge_id)?;
+        if vertex0.is_some() && vertex1.is_some() {
+            return Ok(None);
+        }
+        let (origin, direction) = self.infinite_edge_ray_(edge_id)?;
+        let vertex = |v: VD::VertexIndex| -> Result<[F; 2], BvError> {
+            let v = self.diagram_.vertex_get(v)?.get();
+            Ok([v.x(), v.y()])
+        };
+        Ok(Some(match (vertex0, vertex1) {
+            (Some(v0), _) => Ray {
+                origin: vertex(v0)?,
+                direction,
+            },
+            (_, Some(v1)) => Ray {
+                origin: vertex(v1)?,
+                direction: [-direction[0], -direction[1]],
+            },
+            _ => Ray { origin, direction },
+        }))
+    }
+
+    /// Returns the point where the ray `origin + t * direction`, `t > 0` crosses the far circle.
+    fn far_point_(&self, origin: [F; 2], direction: [F; 2]) -> [F; 2] {
+        let length = direction[0].hypot(direction[1]);
+        let d = [direction[0] / length, direction[1] / length];
+        let o = [
+            origin[0] - self.far_center_[0],
+            origin[1] - self.far_center_[1],
+        ];
+        let b = o[0] * d[0] + o[1] * d[1];
+        let c = o[0] * o[0] + o[1] * o[1] - self.far_radius_ * self.far_radius_;
+        let t = -b + (b * b - c).max(F::zero()).sqrt();
+        [origin[0] + d[0] * t, origin[1] + d[1] * t]
+    }
+
+    /// Returns the points of an edge, from vertex0 to vertex1. Infinite ends are replaced by
+    /// points on the far circle, curved edges are discretized.
+    /// The direction of the edge is returned as well, if the edge is infinite.
+    fn edge_points_(
+        &self,
+        edge_id: VD::EdgeIndex,
+        max_deviation: F,
+    ) -> Result<EdgePoints<F>, BvError> {
+        let edge = self.diagram_.get_edge(edge_id)?.get();
+        let vertex = |v: VD::VertexIndex| -> Result<[F; 2], BvError> {
+            let v = self.diagram_.vertex_get(v)?.get();
+            Ok([v.x(), v.y()])
+        };
+        match (edge.vertex0(), self.diagram_.edge_get_vertex1(edge_id)?) {
+            (Some(v0), Some(v1)) => {
+                let mut points = vec![vertex(v0)?, vertex(v1)?];
+                if edge.is_curved() {
+                    let cell2_id = self
+                        .diagram_
+                        .edge_get_cell(self.diagram_.edge_get_twin(edge_id)?)?;
+                    match (self.site_(edge.cell()?)?, self.site_(cell2_id)?) {
+                        (SiteGeometry::Point(point), SiteGeometry::Segment(segment))
+                        | (SiteGeometry::Segment(segment), SiteGeometry::Point(point)) => {
+                            VoronoiVisualUtils::discretize(
+                                &point,
+                                &segment,
+                                max_deviation,
+                                &SimpleAffine::default(),
+                                &mut points,
+                            );
+                        }
+                        _ => (),
+                    }
+                }
+                Ok((points, None))
+            }
+            (v0, v1) => {
+                let (origin, direction) = self.infinite_edge_ray_(edge_id)?;
+                let start = match v0 {
+                    Some(v0) => vertex(v0)?,
+                    None => self.far_point_(origin, [-direction[0], -direction[1]]),
+                };
+                let end = match v1 {
+                    Some(v1) => vertex(v1)?,
+                    None => self.far_point_(origin, direction),
+                };
+                Ok((vec![start, end], Some(direction)))
+            }
+        }
+    }
+
+    /// Appends the points of the far circle strictly between `from` and `to`, going counter
+    /// clockwise. `from_direction` is the direction of the edge going out to infinity at `from`,
+    /// and `to_direction` is the direction of the edge coming in from infinity at `to`.
+    fn far_arc_(
+        &self,
+        from: [F; 2],
+        from_direction: [F; 2],
+        to: [F; 2],
+        to_direction: [F; 2],
+        polygon: &mut Vec<[F; 2]>,
+    ) {
+        let pi = F::from(std::f64::consts::PI).unwrap();
+        let two_pi = pi + pi;
+        // The angle at infinity, seen from the cell, is in the range [0, PI]
+        let (a, b) = (from_direction, [-to_direction[0], -to_direction[1]]);
+        let mut sweep = (a[0] * b[1] - a[1] * b[0]).atan2(a[0] * b[0] + a[1] * b[1]);
+        if sweep < F::zero() {
+            sweep = if sweep < -pi / cast::<f64, F>(2.0) {
+                sweep + two_pi
+            } else {
+                F::zero()
+            };
+        }
+        // Adjust the sweep to the actual far points, they are close to the directions.
+        let c = self.far_center_;
+        let angle0 = (from[1] - c[1]).atan2(from[0] - c[0]);
+        let angle1 = (to[1] - c[1]).atan2(to[0] - c[0]);
+        let mut position_sweep = angle1 - angle0;
+        while position_sweep < sweep - pi {
+            position_sweep = position_sweep + two_pi;
+        }
+        while position_sweep >= sweep + pi {
+            position_sweep = position_sweep - two_pi;
+        }
+        if position_sweep <= F::zero() {
+            return;
+        }
+        // the chords are well outside of the region as long as each step is less than PI/4
+        let steps = (position_sweep / (pi / cast::<f64, F>(4.0)))
+            .ceil()
+            .to_usize()
+            .unwrap_or(1)
+            .max(1);
+        for step in 1..steps {
+            let angle = angle0 + position_sweep * cast::<usize, F>(step) / cast::<usize, F>(steps);
+            polygon.push([
+                c[0] + self.far_radius_ * angle.cos(),
+                c[1] + self.far_radius_ * angle.sin(),
+            ]);
+        }
+    }
+
+    /// Returns the boundary of the cell, clipped to the region, as a closed polygon in counter
+    /// clockwise order. The last point connects back to the first, it is not repeated.
+    /// Curved edges are discretized so that the polygon deviates at most `max_deviation` from
+    /// the parabolic arcs.
+    /// The polygon is empty if the cell is completely outside the region.
+    pub fn cell_polygon(
+        &self,
+        cell_id: VD::CellIndex,
+        max_deviation: F,
+    ) -> Result<Vec<[F; 2]>, BvError> {
+        let cell = self.diagram_.get_cell(cell_id)?.get();
+        if cell.is_degenerate() {
+            // A lone site owns the whole plane
+            return Ok(if self.diagram_.cells().len() == 1 {
+                self.region_.vertices().to_vec()
+            } else {
+                Vec::new()
+            });
+        }
+        let edges = self
+            .diagram_
+            .cell_edge_iterator(cell_id)
+            .map(|edge_id| {
+                let vertex1 = self.diagram_.edge_get_vertex1(edge_id)?;
+                let (points, direction) = self.edge_points_(edge_id, max_deviation)?;
+                Ok((points, direction, vertex1.is_none()))
+            })
+            .collect::<Result<Vec<_>, BvError>>()?;
+        let mut polygon = Vec::<[F; 2]>::new();
+        for (i, (points, direction, ends_at_infinity)) in edges.iter().enumerate() {
+            polygon.extend_from_slice(&points[..points.len() - 1]);
+            if *ends_at_infinity {
+                // The next edge comes back from infinity, connect the two along the far circle
+                let end = points[points.len() - 1];
+                polygon.push(end);
+                let (next_points, next_direction, _) = &edges[(i + 1) % edges.len()];
+                if let (Some(direction), Some(next_direction)) = (direction, next_direction) {
+                    self.far_arc_(
+                        end,
+                        *direction,
+                        next_points[0],
+                        *next_direction,
+                        &mut polygon,
+                    );
+                }
+            }
+        }
+        Ok(self.region_.clip_polygon(polygon))
+    }
+
+    /// Returns the boundary of the cell as lines and exact parabolic arcs, in counter clockwise
+    /// order. Infinite cells are closed along the far circle, outside of the region.
+    fn cell_boundary_(&self, cell_id: VD::CellIndex) -> Result<Vec<BoundaryPiece<F>>, BvError> {
+        let mut boundary = Vec::<BoundaryPiece<F>>::new();
+        let mut far_points = Vec::<[F; 2]>::new();
+        let edges: Vec<VD::EdgeIndex> = self.diagram_.cell_edge_iterator(cell_id).collect();
+        for (i, edge_id) in edges.iter().enumerate() {
+            if let Some(arc) =
+                ParabolicArc::from_edge(self.diagram_, *edge_id, self.points_, self.segments_)?
+            {
+                boundary.push(BoundaryPiece::curve(arc.to_bezier().control_points));
+                continue;
+            }
+            // straight edges are not discretized
+            let (points, direction) = self.edge_points_(*edge_id, F::infinity())?;
+            let (start, end) = (points[0], points[points.len() - 1]);
+            boundary.push(BoundaryPiece::line(start, end));
+            if self.diagram_.edge_get_vertex1(*edge_id)?.is_none() {
+                // The next edge comes back from infinity, connect the two along the far circle
+                let next_id = edges[(i + 1) % edges.len()];
+                let (next_points, next_direction) = self.edge_points_(next_id, F::infinity())?;
+                if let (Some(direction), Some(next_direction)) = (direction, next_direction) {
+                    far_points.clear();
+                    far_points.push(end);
+                    self.far_arc_(
+                        end,
+                        direction,
+                        next_points[0],
+                        next_direction,
+                        &mut far_points,
+                    );
+                    far_points.push(next_points[0]);
+                    boundary.extend(
+                        far_points
+                            .windows(2)
+                            .map(|w| BoundaryPiece::line(w[0], w[1])),
+                    );
+                }
+            }
+        }
+        Ok(boundary)
+    }
+
+    /// Returns the area, perimeter and centroid of the cell, clipped to the region.
+    /// The measurements are exact over the linear and the parabolic edges, there is no
+    /// discretization involved.
+    /// Returns `None` if the cell is completely outside the region.
+    pub fn cell_measure(&self, cell_id: VD::CellIndex) -> Result<Option<CellMeasure<F>>, BvError> {
+        let cell = self.diagram_.get_cell(cell_id)?.get();
+        let boundary = if cell.is_degenerate() {
+            // A lone site owns the whole plane
+            if self.diagram_.cells().len() != 1 {
+                return Ok(None);
+            }
+            let region = self.region_.vertices();
+            (0..region.len())
+                .map(|i| BoundaryPiece::line(region[i], region[(i + 1) % region.len()]))
+                .collect()
+        } else {
+            self.cell_boundary_(cell_id)?
+        };
+        Ok(measure(boundary, self.region_.vertices()))
+    }
+
+    /// Returns the measurements of every cell, indexed by `CellIndex`, see
+    /// `DiagramClipper::cell_measure()`.
+    pub fn cell_measures(&self) -> Result<Vec<Option<CellMeasure<F>>>, BvError> {
+        (0..self.diagram_.cells().len())
+            .map(|c| self.cell_measure(VD::CellIndex(c)))
+            .collect()
+    }
+
+    /// Returns the clipped polygon of every cell, indexed by `CellIndex`, see
+    /// `DiagramClipper::cell_polygon()`.
+    pub fn cell_polygons(&self, max_deviation: F) -> Result<Vec<Vec<[F; 2]>>, BvError> {
+        (0..self.diagram_.cells().len())
+            .map(|c| self.cell_polygon(VD::CellIndex(c), max_deviation))
+            .collect()
+    }
+
+    /// Returns the part of the edge inside the region as polylines, going from the vertex0 end of
+    /// the edge to the vertex1 end. Infinite edges are cut off outside the region, curved edges
+    /// are discretized so that the polylines deviate at most `max_deviation` from the parabolic
+    /// arcs.
+    /// A curved edge may leave and re-enter the region, so there can be more than one polyline.
+    /// The result is empty if the edge is completely outside the region.
+    pub fn clip_edge(
+        &self,
+        edge_id: VD::EdgeIndex,
+        max_deviation: F,
+    ) -> Result<Vec<Vec<[F; 2]>>, BvError> {
+        let (points, _) = self.edge_points_(edge_id, max_deviation)?;
+        Ok(self.region_.clip_polyline(&points))
+    }
+
+    /// Returns the clipped polylines of every edge, indexed by `EdgeIndex`, see
+    /// `DiagramClipper::clip_edge()`. Note that every edge has a twin, with the same geometry in
+    /// the opposite direction.
+    pub fn clip_edges(&self, max_deviation: F) -> Result<Vec<Vec<Vec<[F; 2]>>>, BvError> {
+        (0..self.diagram_.edges().len())
+            .map(|e| self.clip_edge(VD::EdgeIndex(e), max_deviation))
+            .collect()
+    }
+}
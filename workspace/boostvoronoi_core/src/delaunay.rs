@@ -0,0 +1,203 @@
+//          Copyright Eadf (github.com/eadf) 2021.
+// Distributed under the Boost Software License, Version 1.0.
+//    (See accompanying file LICENSE_1_0.txt or copy at
+//          http://www.boost.org/LICENSE_1_0.txt)
+
+//! Delaunay triangulation, the dual graph of a diagram built from point sites only.
+//!
+//! Every finite voronoi vertex is the circumcenter of a Delaunay triangle. A vertex of degree
+//! greater than three has more than three cocircular sites, the convex polygon of those sites is
+//! split into a triangle fan from the site with the lowest source index.
+//! The cells with infinite edges are the sites on the convex hull.
+
+use crate::diagram::{self as VD, SourceCategory};
+use crate::sync_diagram as SD;
+use crate::{BvError, OutputType};
+
+/// The Delaunay triangulation of the input points of a diagram.
+///
+/// All the indices are source indices, i.e. indices into the point input of the `Builder`.
+/// ```
+/// # use boostvoronoi_core::builder::Builder;
+/// # use boostvoronoi_core::delaunay::DelaunayTriangulation;
+/// # use boostvoronoi_core::BvError;
+/// let diagram = Builder::<i32, f64>::default()
+///     .with_vertices([[0, 0], [10, 0], [10, 10], [0, 10], [5, 2]])?
+///     .build()?;
+/// let delaunay = DelaunayTriangulation::new(&diagram)?;
+/// assert_eq!(delaunay.triangles().len(), 4);
+/// assert_eq!(delaunay.convex_hull(), &[0, 1, 2, 3]);
+/// # Ok::<(), BvError>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DelaunayTriangulation {
+    triangles_: Vec<[usize; 3]>,
+    convex_hull_: Vec<usize>,
+}
+
+impl DelaunayTriangulation {
+    /// Extracts the Delaunay triangulation from a diagram built from points only.
+    /// # Errors
+    /// Will return `BvError::ValueError` if the diagram contains segment sites.
+    pub fn new<F: OutputType>(diagram: &VD::Diagram<F>) -> Result<Self, BvError> {
+        let cells: Vec<VD::Cell> = diagram.cells().iter().map(|c| c.get()).collect();
+        let vertices: Vec<VD::Vertex<F>> = diagram.vertices().iter().map(|v| v.get()).collect();
+        let edges: Vec<VD::Edge> = diagram.edges().iter().map(|e| e.get()).collect();
+        Self::new_(&cells, &vertices, &edges)
+    }
+
+    /// Extracts the Delaunay triangulation from a `SyncDiagram`, see
+    /// `DelaunayTriangulation::new()`.
+    pub fn new_sync<F: OutputType>(diagram: &SD::SyncDiagram<F>) -> Result<Self, BvError> {
+        Self::new_(diagram.cells(), diagram.vertices(), diagram.edges())
+    }
+
+    fn new_<F: OutputType>(
+        cells: &[VD::Cell],
+        vertices: &[VD::Vertex<F>],
+        edges: &[VD::Edge],
+    ) -> Result<Self, BvError> {
+        if let Some(cell) = cells
+            .iter()
+            .find(|c| c.source_category() != SourceCategory::SinglePoint)
+        {
+            return Err(BvError::ValueError(format!(
+                "The Delaunay triangulation requires point sites only, cell {} is not a point",
+                cell.id().0
+            )));
+        }
+        let edge = |edge_id: VD::EdgeIndex| -> Result<&VD::Edge, BvError> {
+            edges
+                .get(edge_id.0)
+                .ok_or_else(|| BvError::IdError(format!("Edge {} does not exist", edge_id.0)))
+        };
+        let site = |edge_id: VD::EdgeIndex| -> Result<usize, BvError> {
+            let cell_id = edge(edge_id)?.cell()?;
+            cells
+                .get(cell_id.0)
+                .map(|c| c.source_index())
+                .ok_or_else(|| BvError::IdError(format!("Cell {} does not exist", cell_id.0)))
+        };
+
+        let mut triangles = Vec::<[usize; 3]>::with_capacity(vertices.len());
+        let mut ring = Vec::<usize>::new();
+        for vertex in vertices.iter() {
+            // The cells around the vertex, in counter clockwise order
+            ring.clear();
+            let start = vertex.get_incident_edge()?;
+            let mut edge_id = start;
+            loop {
+                ring.push(site(edge_id)?);
+                edge_id = edge(edge(edge_id)?.prev()?)?.twin()?;
+                if edge_id == start {
+                    break;
+                }
+                if ring.len() > edges.len() {
+                    return Err(BvError::InternalError(format!(
+                        "The edges around vertex {} do not form a loop",
+                        vertex.get_id().0
+                    )));
+                }
+            }
+            // A consistent fan, starting from the lowest source index
+            if let Some(first) = (0..ring.len()).min_by_key(|i| ring[*i]) {
+                ring.rotate_left(first);
+            }
+            for i in 1..ring.len().saturating_sub(1) {
+                triangles.push([ring[0], ring[i], ring[i + 1]]);
+            }
+        }
+
+        let convex_hull = if vertices.is_empty() {
+            Self::collinear_hull_(cells, edges)?
+        } else {
+            // Follow the edges going out to infinity, the cell to the right of such an edge is
+            // followed by the cell to the left of it on the convex hull.
+            let mut next = vec![None; cells.len()];
+            for e in edges.iter() {
+                if e.vertex0().is_some() && edge(e.twin()?)?.vertex0().is_none() {
+                    next[edge(e.twin()?)?.cell()?.0] = Some(e.cell()?.0);
+                }
+            }
+            let mut hull = Vec::<usize>::new();
+            if let Some(first) = (0..cells.len())
+                .filter(|c| next[*c].is_some())
+                .min_by_key(|c| cells[*c].source_index())
+            {
+                let mut cell = first;
+                loop {
+                    hull.push(cells[cell].source_index());
+                    cell = next[cell].ok_or_else(|| {
+                        BvError::InternalError(format!(
+                            "The convex hull is broken at cell {}",
+                            cell
+                        ))
+                    })?;
+                    if cell == first {
+                        break;
+                    }
+                    if hull.len() > cells.len() {
+                        return Err(BvError::InternalError(
+                            "The convex hull does not form a loop".to_string(),
+                        ));
+                    }
+                }
+            }
+            hull
+        };
+
+        Ok(Self {
+            triangles_: triangles,
+            convex_hull_: convex_hull,
+        })
+    }
+
+    /// Without vertices, all the sites are on a line and every cell is a strip between (at most)
+    /// two infinite edges. The hull is then the sites in order along that line.
+    fn collinear_hull_(cells: &[VD::Cell], edges: &[VD::Edge]) -> Result<Vec<usize>, BvError> {
+        // the neighbours of each cell, there are at most two of them
+        let mut neighbours = vec![[None, None]; cells.len()];
+        for e in edges.iter() {
+            let twin_cell = edges
+                .get(e.twin()?.0)
+                .ok_or_else(|| BvError::IdError(format!("Edge {} has no twin", e.id().0)))?
+                .cell()?
+                .0;
+            let n = &mut neighbours[e.cell()?.0];
+            if n[0].is_none() {
+                n[0] = Some(twin_cell);
+            } else {
+                n[1] = Some(twin_cell);
+            }
+        }
+        let mut hull = Vec::with_capacity(cells.len());
+        // start at an end of the line, a cell with a single neighbour
+        let mut previous = None;
+        let mut cell = (0..cells.len()).find(|c| neighbours[*c][1].is_none());
+        while let Some(c) = cell {
+            hull.push(cells[c].source_index());
+            let [n0, n1] = neighbours[c];
+            cell = if n0 != previous { n0 } else { n1 };
+            previous = Some(c);
+            if hull.len() > cells.len() {
+                return Err(BvError::InternalError(
+                    "The collinear sites do not form a line".to_string(),
+                ));
+            }
+        }
+        Ok(hull)
+    }
+
+    /// Returns the triangles as triples of source indices, in counter clockwise order
+    pub fn triangles(&self) -> &[[usize; 3]] {
+        &self.triangles_
+    }
+
+    /// Returns the sites on the convex hull in counter clockwise order, starting with the lowest
+    /// source index. Sites on the straight parts of the hull are included.
+    /// If all the sites are collinear the hull is the sites in order along the line, from one
+    /// end to the other.
+    pub fn convex_hull(&self) -> &[usize] {
+        &self.convex_hull_
+    }
+}
@@ -14,7 +14,9 @@
 
 use crate::circle_event as VC;
 use crate::ctypes as CT;
+use crate::geometry::{Line, Point};
 use crate::site_event as VSE;
+use crate::validation as VV;
 use crate::visual_utils as VU;
 use crate::{sync_diagram as SD, BvError};
 
@@ -119,23 +121,157 @@ pub enum SourceCategory {
     Segment,
 }
 
+impl From<ColorBits> for SourceCategory {
+    #[inline(always)]
+    fn from(bits: ColorBits) -> Self {
+        match bits {
+            ColorBits::SINGLE_POINT__BIT => SourceCategory::SinglePoint,
+            ColorBits::SEGMENT_START_POINT__BIT => SourceCategory::SegmentStart,
+            ColorBits::SEGMENT_END_POINT__BIT => SourceCategory::SegmentEnd,
+            _ => SourceCategory::Segment,
+        }
+    }
+}
+
+/// The part of an input polygon ring, or polyline, a voronoi `Cell` was created from.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PathElement {
+    /// The source was the n:th vertex of the ring or polyline
+    Vertex(usize),
+    /// The source was the n:th edge of the ring or polyline, i.e. the edge from vertex n
+    /// to vertex n+1 (wrapping around for rings).
+    Edge(usize),
+}
+
+/// Identifies the input polygon or polyline a voronoi `Cell` was created from.
+/// Polygons and polylines are numbered separately, in the order they were added to the `Builder`.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PathSource {
+    /// The source was a polygon ring. Ring 0 is the exterior ring, the holes are numbered from 1.
+    Polygon {
+        polygon: usize,
+        ring: usize,
+        element: PathElement,
+    },
+    /// The source was a polyline
+    Polyline {
+        polyline: usize,
+        element: PathElement,
+    },
+}
+
+/// Records the range of source indices occupied by a polygon ring or a polyline.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) struct PathRecord {
+    /// The source index of the first segment of the path
+    pub(crate) first_: SourceIndex,
+    /// The number of segments in the path
+    pub(crate) len_: usize,
+    /// The polygon or polyline number
+    pub(crate) path_: usize,
+    /// The ring number, or `None` for polylines
+    pub(crate) ring_: Option<usize>,
+}
+
+impl PathRecord {
+    /// Looks up the path source of a cell. `records` must be sorted by `first_`.
+    pub(crate) fn lookup(records: &[Self], cell: &Cell) -> Option<PathSource> {
+        let (source_index, category) = cell.source_index_2();
+        let record = records
+            .get(
+                records
+                    .partition_point(|r| r.first_ <= source_index)
+                    .checked_sub(1)?,
+            )
+            .filter(|r| source_index < r.first_ + r.len_)?;
+        let edge = source_index - record.first_;
+        let element = match category {
+            SourceCategory::Segment => PathElement::Edge(edge),
+            SourceCategory::SegmentStart | SourceCategory::SinglePoint => PathElement::Vertex(edge),
+            SourceCategory::SegmentEnd => match record.ring_ {
+                Some(_) => PathElement::Vertex((edge + 1) % record.len_),
+                None => PathElement::Vertex(edge + 1),
+            },
+        };
+        Some(match record.ring_ {
+            Some(ring) => PathSource::Polygon {
+                polygon: record.path_,
+                ring,
+                element,
+            },
+            None => PathSource::Polyline {
+                polyline: record.path_,
+                element,
+            },
+        })
+    }
+}
+
+/// The input geometry a voronoi `Cell` was created from.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum SiteGeometry<I: InputType> {
+    Point(Point<I>),
+    Segment(Line<I>),
+}
+
+impl<I: InputType> SiteGeometry<I> {
+    /// Looks up the input geometry of a cell. `points` and `segments` must be the input of the
+    /// `Builder`; the vertices are numbered first and the segments after them.
+    pub(crate) fn of_cell(
+        cell: &Cell,
+        points: &[Point<I>],
+        segments: &[Line<I>],
+    ) -> Result<Self, BvError> {
+        let (index, category) = cell.source_index_2();
+        let missing = || {
+            BvError::IdError(format!(
+                "The source index {} of cell {} is not part of the input",
+                index,
+                cell.id().0
+            ))
+        };
+        let segment = |index: SourceIndex| {
+            index
+                .checked_sub(points.len())
+                .and_then(|i| segments.get(i))
+                .ok_or_else(missing)
+        };
+        Ok(match category {
+            // a zero length segment is a point site, but it is numbered as a segment
+            SourceCategory::SinglePoint if index >= points.len() => {
+                SiteGeometry::Point(segment(index)?.start)
+            }
+            SourceCategory::SinglePoint => {
+                SiteGeometry::Point(*points.get(index).ok_or_else(missing)?)
+            }
+            SourceCategory::SegmentStart => SiteGeometry::Point(segment(index)?.start),
+            SourceCategory::SegmentEnd => SiteGeometry::Point(segment(index)?.end),
+            SourceCategory::Segment => SiteGeometry::Segment(*segment(index)?),
+        })
+    }
+}
+
 /// Represents a Voronoi cell.
 ///
 /// Data members:
 ///   1) index of the source within the initial input set
 ///   2) id of the incident edge
 ///   3) mutable color member
+///
 /// Cell may contain point or segment site inside.
 // TODO: fix the name confusing "initial index" & "source index" referring to the same thing.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone)]
 pub struct Cell {
     // sorted_index of the site event
-    id_: CellIndex,
+    pub(crate) id_: CellIndex,
     // source_index/initial_index of the site event
-    source_index_: SourceIndex,
-    incident_edge_: Option<EdgeIndex>,
-    color_: ColorType,
+    pub(crate) source_index_: SourceIndex,
+    pub(crate) incident_edge_: Option<EdgeIndex>,
+    pub(crate) color_: ColorType,
 }
 
 impl fmt::Debug for Cell {
@@ -168,12 +304,7 @@ impl Cell {
 
     #[inline(always)]
     pub fn source_category(&self) -> SourceCategory {
-        match self.internal_color() {
-            ColorBits::SINGLE_POINT__BIT => SourceCategory::SinglePoint,
-            ColorBits::SEGMENT_START_POINT__BIT => SourceCategory::SegmentStart,
-            ColorBits::SEGMENT_END_POINT__BIT => SourceCategory::SegmentEnd,
-            _ => SourceCategory::Segment,
-        }
+        self.internal_color().into()
     }
 
     /// Returns true if the cell contains point site, false else.
@@ -230,6 +361,26 @@ impl Cell {
     pub fn get_incident_edge(&self) -> Option<EdgeIndex> {
         self.incident_edge_
     }
+
+    /// get_color returns the custom cell info. (does not contain the reserved bits)
+    #[inline(always)]
+    pub fn get_color(&self) -> ColorType {
+        self.color_ >> ColorBits::RESERVED_BITS__SHIFT.0
+    }
+
+    /// set_color sets the custom cell info. (does not affect the reserved bits)
+    #[inline(always)]
+    pub fn set_color(&mut self, color: ColorType) -> ColorType {
+        self.color_ &= ColorBits::RESERVED__MASK.0;
+        self.color_ |= color << ColorBits::RESERVED_BITS__SHIFT.0;
+        self.color_
+    }
+
+    /// or_color sets the custom cell info together with the previous value. (does not affect the reserved bits)
+    #[inline(always)]
+    pub fn or_color(&mut self, color: ColorType) -> ColorType {
+        self.set_color(self.get_color() | color)
+    }
 }
 
 /// Iterator over edges of a Cell
@@ -378,14 +529,18 @@ impl<'s, F: OutputType> Iterator for EdgeRotPrevIterator<'s, F> {
 ///
 /// Data members:
 ///   1) vertex coordinates
-///   2) id of the incident edge
-///   3) mutable color member
+///   2) radius of the empty circle centered at the vertex
+///   3) id of the incident edge
+///   4) mutable color member
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone)]
 pub struct Vertex<F: OutputType> {
     pub(crate) id_: VertexIndex,
     pub(crate) x_: F,
     pub(crate) y_: F,
+    // missing in data serialized by older versions
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) radius_: F,
     pub(crate) incident_edge_: Option<EdgeIndex>,
     pub(crate) color_: ColorType,
 }
@@ -394,10 +549,11 @@ impl<F: OutputType> fmt::Debug for Vertex<F> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "(id:{} x:{} y:{} ie:{} co:{})",
+            "(id:{} x:{} y:{} r:{} ie:{} co:{})",
             self.id_.0,
             self.x_,
             self.y_,
+            self.radius_,
             CT::format_id(self.incident_edge_.map(|x| x.0)),
             self.color_
         )
@@ -406,21 +562,26 @@ impl<F: OutputType> fmt::Debug for Vertex<F> {
 
 impl<F: OutputType> Vertex<F> {
     pub fn new_3(id: VertexIndex, x: F, y: F, is_site_vertex: bool) -> Rc<cell::Cell<Vertex<F>>> {
+        Rc::new(cell::Cell::new(Self::new_(id, x, y, is_site_vertex)))
+    }
+
+    pub(crate) fn new_(id: VertexIndex, x: F, y: F, is_site_vertex: bool) -> Self {
         let color = if is_site_vertex {
             ColorBits::SITE_VERTEX__BIT.0
         } else {
             ColorBits::ZERO.0
         };
-        Rc::new(cell::Cell::new(Self {
+        Self {
             id_: id,
             x_: x,
             y_: y,
+            radius_: F::zero(),
             incident_edge_: None,
             color_: color,
-        }))
+        }
     }
 
-    fn vertex_equality_predicate_eq(&self, other: &Self) -> bool {
+    pub(crate) fn vertex_equality_predicate_eq(&self, other: &Self) -> bool {
         let ulp = 128;
         let x1: f64 = NumCast::from(self.x()).unwrap();
         let y1: f64 = NumCast::from(self.y()).unwrap();
@@ -460,7 +621,17 @@ impl<F: OutputType> Vertex<F> {
         self.y_
     }
 
+    /// Returns the radius of the circle event: the radius of the empty circle centered at the
+    /// vertex, touching the sites of the cells around the vertex.
+    /// The radius is zero for vertices coinciding with an input site, and for vertices
+    /// deserialized from data written by versions that did not store the radius.
+    #[inline]
+    pub fn radius(&self) -> F {
+        self.radius_
+    }
+
     /// get_color returns the custom edge info. (does not contain the reserved bits)
+    /// See `attributes::AttributeMap` for storing typed data without the reserved bits.
     pub fn get_color(&self) -> ColorType {
         self.color_ >> ColorBits::RESERVED_BITS__SHIFT.0
     }
@@ -499,13 +670,13 @@ impl<F: OutputType> Vertex<F> {
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone)]
 pub struct Edge {
-    id_: EdgeIndex,
-    cell_: Option<CellIndex>,
-    vertex_: Option<VertexIndex>,
-    twin_: Option<EdgeIndex>,
-    next_ccw_: Option<EdgeIndex>,
-    prev_ccw_: Option<EdgeIndex>,
-    color_: ColorType,
+    pub(crate) id_: EdgeIndex,
+    pub(crate) cell_: Option<CellIndex>,
+    pub(crate) vertex_: Option<VertexIndex>,
+    pub(crate) twin_: Option<EdgeIndex>,
+    pub(crate) next_ccw_: Option<EdgeIndex>,
+    pub(crate) prev_ccw_: Option<EdgeIndex>,
+    pub(crate) color_: ColorType,
 }
 
 impl fmt::Debug for Edge {
@@ -529,7 +700,7 @@ impl Edge {
     const BIT_IS_LINEAR: ColorType = 0x1; // linear is opposite to curved
     const BIT_IS_PRIMARY: ColorType = 0x2; // primary is opposite to secondary
 
-    fn new_(id: EdgeIndex, cell: CellIndex, is_linear: bool, is_primary: bool) -> EdgeType {
+    pub(crate) fn new_(id: EdgeIndex, cell: CellIndex, is_linear: bool, is_primary: bool) -> Self {
         let mut rv = Self {
             id_: id,
             cell_: Some(cell),
@@ -545,7 +716,7 @@ impl Edge {
         if is_primary {
             rv.color_ |= Self::BIT_IS_PRIMARY;
         }
-        Rc::from(cell::Cell::from(rv))
+        rv
     }
 
     /// Returns the edge index
@@ -642,6 +813,7 @@ impl Edge {
     }
 
     /// get_color returns the custom edge info. (does not contain the reserved bits)
+    /// See `attributes::AttributeMap` for storing typed data without the reserved bits.
     #[inline(always)]
     pub fn get_color(&self) -> ColorType {
         self.color_ >> ColorBits::RESERVED_BITS__SHIFT.0
@@ -677,6 +849,8 @@ pub struct Diagram<F: OutputType> {
     cells_: Vec<CellType>,         // indexed by CellIndex
     vertices_: Vec<VertexType<F>>, // indexed by VertexIndex
     edges_: Vec<EdgeType>,         // indexed by EdgeIndex
+    // the source index ranges of the polygon rings and polylines
+    pub(crate) path_records_: Vec<PathRecord>,
 }
 
 impl<F: OutputType> Diagram<F> {
@@ -685,6 +859,7 @@ impl<F: OutputType> Diagram<F> {
             cells_: Vec::<CellType>::with_capacity(input_size),
             vertices_: Vec::<VertexType<F>>::with_capacity(input_size),
             edges_: Vec::<EdgeType>::with_capacity(input_size * 2),
+            path_records_: Vec::new(),
         }
     }
 
@@ -693,6 +868,37 @@ impl<F: OutputType> Diagram<F> {
         self.cells_.clear();
         self.vertices_.clear();
         self.edges_.clear();
+        self.path_records_.clear();
+    }
+
+    /// Returns the polygon ring, or polyline, element a cell was created from.
+    /// Returns `Ok(None)` if the cell was not created by `Builder::with_polygons()` or
+    /// `Builder::with_polylines()`.
+    pub fn cell_path_source(&self, cell_id: CellIndex) -> Result<Option<PathSource>, BvError> {
+        Ok(PathRecord::lookup(
+            &self.path_records_,
+            &self.get_cell(cell_id)?.get(),
+        ))
+    }
+
+    /// Checks the half-edge invariants of the diagram: twin symmetry, the next/prev links around
+    /// every cell, the edges around every vertex, the incident edges, the Euler characteristic
+    /// and that no placeholder cells are left by the builder. A valid diagram returns an empty
+    /// report.
+    /// ```
+    /// # use boostvoronoi_core::builder::Builder;
+    /// # use boostvoronoi_core::geometry::Line;
+    /// # use boostvoronoi_core::BvError;
+    /// let s = vec![Line::from([0_i32, 0, 10, 10]), Line::from([10, 10, 20, 0])];
+    /// let diagram = Builder::<i32, f64>::default().with_segments(s.iter())?.build()?;
+    /// assert!(diagram.validate_topology().is_valid());
+    /// # Ok::<(), BvError>(())
+    /// ```
+    pub fn validate_topology(&self) -> VV::TopologyReport {
+        let cells: Vec<Cell> = self.cells_.iter().map(|c| c.get()).collect();
+        let edges: Vec<Edge> = self.edges_.iter().map(|e| e.get()).collect();
+        let vertices: Vec<Vertex<F>> = self.vertices_.iter().map(|v| v.get()).collect();
+        VV::validate_topology(&cells, &edges, &vertices)
     }
 
     #[inline(always)]
@@ -718,6 +924,19 @@ impl<F: OutputType> Diagram<F> {
         rv
     }
 
+    /// Replaces the coordinates of every vertex with the result of `f(x, y)`, and multiplies the
+    /// radius by `radius_scale`
+    pub(crate) fn transform_vertices_(&self, f: impl Fn(F, F) -> [F; 2], radius_scale: F) {
+        for v in self.vertices_.iter() {
+            let mut vertex = v.get();
+            let [x, y] = f(vertex.x_, vertex.y_);
+            vertex.x_ = x;
+            vertex.y_ = y;
+            vertex.radius_ *= radius_scale;
+            v.set(vertex);
+        }
+    }
+
     #[inline(always)]
     /// Returns a reference to the list of edges
     pub fn edges(&self) -> &Vec<EdgeType> {
@@ -732,6 +951,29 @@ impl<F: OutputType> Diagram<F> {
         })?))
     }
 
+    /// Returns the color field of the cell.
+    pub fn cell_get_color(&self, cell_id: CellIndex) -> Result<ColorType, BvError> {
+        Ok(self.get_cell(cell_id)?.get().get_color())
+    }
+
+    /// Set the color of the cell. This affects only the public bits, not the internal
+    pub fn cell_set_color(&self, cell_id: CellIndex, color: ColorType) -> Result<(), BvError> {
+        let cell_cell = self.get_cell(cell_id)?;
+        let mut cell = cell_cell.get();
+        let _ = cell.set_color(color);
+        cell_cell.set(cell);
+        Ok(())
+    }
+
+    /// OR the previous color field value with this new color value
+    pub fn cell_or_color(&self, cell_id: CellIndex, color: ColorType) -> Result<(), BvError> {
+        let cell_cell = self.get_cell(cell_id)?;
+        let mut cell = cell_cell.get();
+        let _ = cell.or_color(color);
+        cell_cell.set(cell);
+        Ok(())
+    }
+
     #[inline(always)]
     /// Returns the edge associated with the edge id
     pub(crate) fn get_edge_(&self, edge_id: Option<EdgeIndex>) -> Option<EdgeType> {
@@ -781,6 +1023,8 @@ impl<F: OutputType> Diagram<F> {
 
     /// Iterates over all edges, colors each edge as exterior if it has an unbroken primary edge
     /// link connection to an infinite edge.
+    /// Holes and nested polygons are not detected, see `inside_outside::InsideOutside` for a
+    /// classification of polygon input.
     pub fn color_exterior_edges(&self, external_color: ColorType) {
         for it in self.edges().iter() {
             let edge_id = Some(it.get().id());
@@ -811,7 +1055,7 @@ impl<F: OutputType> Diagram<F> {
             || self.vertex_is_site_point_(v1).unwrap_or(true)
             || !self
                 .get_edge_(edge_id)
-                .map_or(false, |x| x.get().is_primary())
+                .is_some_and(|x| x.get().is_primary())
         {
             // stop recursion if this edge does not have a vertex1 (e.g is infinite)
             // or if this edge isn't a primary edge.
@@ -843,45 +1087,6 @@ impl<F: OutputType> Diagram<F> {
         self.edges_.iter()
     }
 
-    /// push a new cell on the output. Nothing but id and source category is initialized
-    fn make_new_cell_with_category_(
-        &mut self,
-        cell_id: CellIndex, // same as sorted_index
-        initial_index: SourceIndex,
-        sc: ColorBits,
-    ) -> CellIndex {
-        // fill cell with temporary blocks- they will be over-written later
-        // Todo: fix this dirty hack with Option<>
-        while self.cells_.len() < cell_id.0 {
-            self.cells_.push(Rc::new(cell::Cell::new(Cell::new(
-                CellIndex(usize::MAX),
-                usize::MAX,
-                ColorBits::TEMPORARY_CELL.0,
-            ))));
-        }
-        self.cells_.push(Rc::new(cell::Cell::new(Cell::new(
-            cell_id,
-            initial_index,
-            sc.0,
-        ))));
-        #[cfg(feature = "console_debug")]
-        assert_eq!(self.cells_[cell_id.0].get().id().0, cell_id.0);
-
-        let ccell = &self.cells_[cell_id.0];
-        {
-            let cell = ccell.get();
-            #[cfg(feature = "console_debug")]
-            {
-                assert_eq!(cell.id_.0, cell_id.0);
-                assert_eq!(cell.source_index_, initial_index);
-                assert_eq!(cell.color_, sc.0);
-            }
-            //cell.color_ = sc.get_value();
-            ccell.set(cell);
-        }
-        cell_id
-    }
-
     /// returns the number of cells in the diagram
     pub fn num_cells(&self) -> usize {
         self.cells_.len()
@@ -905,31 +1110,12 @@ impl<F: OutputType> Diagram<F> {
             .reserve((additional_sites << 2) + (additional_sites << 1));
     }
 
-    pub(crate) fn process_single_site_<I: InputType>(&mut self, site: &VSE::SiteEvent<I, F>) {
-        let _ = self.make_new_cell_with_category_(
-            CellIndex(site.sorted_index()),
-            site.initial_index(),
-            site.source_category(),
-        );
-    }
-
     #[inline]
     fn cell_get_(&self, cell_id: Option<CellIndex>) -> Option<&CellType> {
         let _ = cell_id?;
         self.cells_.get(cell_id.unwrap().0)
     }
 
-    fn cell_set_incident_edge_(&self, cell_id: Option<CellIndex>, edge: Option<EdgeIndex>) {
-        if cell_id.is_none() {
-            return;
-        }
-        if let Some(cell) = self.cell_get_(cell_id) {
-            let mut c = cell.get();
-            c.incident_edge_ = edge;
-            cell.set(c)
-        }
-    }
-
     fn cell_get_incident_edge_(&self, cell_id: Option<CellIndex>) -> Option<EdgeIndex> {
         let _ = cell_id?;
         if let Some(cell) = self.cell_get_(cell_id) {
@@ -938,16 +1124,6 @@ impl<F: OutputType> Diagram<F> {
         None
     }
 
-    fn cell_is_degenerate_(&self, cell_id: Option<CellIndex>) -> bool {
-        if cell_id.is_none() {
-            return false;
-        }
-        if let Some(cell) = self.cell_get_(cell_id) {
-            return cell.get().is_degenerate();
-        }
-        false
-    }
-
     /// Returns an edge iterator. This iterates over the edges belonging to this cell starting with
     /// the incident edge.
     pub fn cell_edge_iterator(&self, cell_id: CellIndex) -> EdgeNextIterator<'_, F> {
@@ -994,6 +1170,22 @@ impl<F: OutputType> Diagram<F> {
         self.vertex_or_color_(Some(vertex_id), color)
     }
 
+    /// OR the previous color field value with this new color value, like `cell_or_color()` and
+    /// `edge_or_color()`.
+    /// # Errors
+    /// Will return `BvError::IdError` if the vertex does not exist.
+    pub fn vertex_try_or_color(
+        &self,
+        vertex_id: VertexIndex,
+        color: ColorType,
+    ) -> Result<(), BvError> {
+        let vertex_cell = self.vertex_get(vertex_id)?;
+        let mut vertex = vertex_cell.get();
+        let _ = vertex.or_color(color);
+        vertex_cell.set(vertex);
+        Ok(())
+    }
+
     /// Returns the color field of the vertex.
     pub fn vertex_get_color(&self, vertex_id: Option<VertexIndex>) -> Option<ColorType> {
         let _ = vertex_id?;
@@ -1004,25 +1196,6 @@ impl<F: OutputType> Diagram<F> {
         None
     }
 
-    /// Overwrites the content of dest with the content of source.
-    /// edge_id is compensated accordingly
-    fn vertex_copy_(&self, dest: usize, source: usize) {
-        let mut v = self.vertices_[source].get();
-        v.id_ = VertexIndex(dest);
-        self.vertices_[dest].set(v);
-    }
-
-    fn vertex_set_incident_edge_(&self, vertex_id: Option<VertexIndex>, edge: Option<EdgeIndex>) {
-        if vertex_id.is_none() {
-            return;
-        }
-        if let Some(vertex) = self.vertex_get_(vertex_id) {
-            let mut c = vertex.get();
-            c.incident_edge_ = edge;
-            vertex.set(c)
-        }
-    }
-
     /// return one of the edges originating at the vertex
     pub fn vertex_get_incident_edge(&self, vertex_id: Option<VertexIndex>) -> Option<EdgeIndex> {
         let _ = vertex_id?;
@@ -1057,6 +1230,17 @@ impl<F: OutputType> Diagram<F> {
             .map(|cell| cell.get().is_site_point())
     }
 
+    /// Returns the cells around the vertex in counter clockwise order, i.e. the cells of the
+    /// sites defining the vertex. Every one of those sites is at the distance
+    /// `Vertex::radius()` from the vertex.
+    /// Use `Cell::source_index_2()` to find the input geometry of the sites.
+    pub fn vertex_cells(&self, vertex_id: VertexIndex) -> Result<Vec<CellIndex>, BvError> {
+        let incident_edge = self.vertex_get(vertex_id)?.get().get_incident_edge()?;
+        self.edge_rot_next_iterator(Some(incident_edge))
+            .map(|edge_id| self.edge_get_cell(edge_id))
+            .collect()
+    }
+
     /// returns true if this vertex coincides with an site point
     #[inline]
     pub fn vertex_is_site_point(&self, vertex_id: VertexIndex) -> Result<bool, BvError> {
@@ -1068,20 +1252,6 @@ impl<F: OutputType> Diagram<F> {
         })
     }
 
-    /// Create and insert a new edge
-    fn create_and_insert_edge(
-        &mut self,
-        cell_id: CellIndex,
-        is_linear: bool,
-        is_primary: bool,
-    ) -> EdgeIndex {
-        let new_edge_id = EdgeIndex(self.edges_.len());
-        let new_edge = Edge::new_(new_edge_id, cell_id, is_linear, is_primary);
-        self.edges_.push(new_edge);
-        tln!("Created and inserted new edge : e={}", new_edge_id.0);
-        new_edge_id
-    }
-
     #[inline]
     pub(crate) fn edge_get_(&self, edge_id: Option<EdgeIndex>) -> Option<&EdgeType> {
         let rv = self.edges_.get(edge_id?.0);
@@ -1096,14 +1266,6 @@ impl<F: OutputType> Diagram<F> {
         rv
     }
 
-    /// Overwrites the content of dest with the content of source.
-    /// edge_id of the new dest is corrected
-    pub(crate) fn edge_copy_(&self, dest: usize, source: usize) {
-        let mut e = self.edges_[source].get();
-        e.id_ = EdgeIndex(dest);
-        self.edges_[dest].set(e);
-    }
-
     #[inline]
     /// Returns the color field of the edge.
     pub(crate) fn edge_get_color_(&self, edge_id: Option<EdgeIndex>) -> Option<ColorType> {
@@ -1165,18 +1327,6 @@ impl<F: OutputType> Diagram<F> {
         Ok(())
     }
 
-    #[inline]
-    pub(crate) fn edge_set_twin_(&self, edge_id: Option<EdgeIndex>, twin_id: Option<EdgeIndex>) {
-        if edge_id.is_none() {
-            return;
-        }
-        if let Some(edgecell) = self.edge_get_(edge_id) {
-            let mut edge = edgecell.get();
-            edge.twin_ = twin_id;
-            edgecell.set(edge);
-        }
-    }
-
     #[inline]
     /// Returns an edge iterator, the edges will all originate at the same vertex as 'edge_id'.
     ///  'edge_id' will be the first edge returned by the iterator.
@@ -1215,18 +1365,6 @@ impl<F: OutputType> Diagram<F> {
         })
     }
 
-    #[inline]
-    fn edge_set_cell_(&self, edge_id: Option<EdgeIndex>, cell_id: Option<CellIndex>) {
-        if edge_id.is_none() {
-            return;
-        }
-        if let Some(edgecell) = self.edge_get_(edge_id) {
-            let mut edge = edgecell.get();
-            edge.cell_ = cell_id;
-            edgecell.set(edge);
-        }
-    }
-
     #[inline]
     fn edge_get_cell_(&self, edge_id: Option<EdgeIndex>) -> Option<CellIndex> {
         let _ = edge_id?;
@@ -1274,71 +1412,6 @@ impl<F: OutputType> Diagram<F> {
             .ok_or_else(|| BvError::IdError(format!("Edge id {} doesn't exists", edge_id.0)))
     }
 
-    /// Remove degenerate edge.
-    fn remove_edge_(&mut self, edge: Option<EdgeIndex>) {
-        #[cfg(feature = "console_debug")]
-        if let Some(edge_id) = edge {
-            tln!("removing edge: {}", edge_id.0);
-        } else {
-            tln!("removing edge: but it was None!");
-            return;
-        }
-        // Update the endpoints of the incident edges to the second vertex.
-        let vertex = self.edge_get_vertex0_(edge);
-        let mut updated_edge = self.edge_rot_next_(self.edge_get_twin_(edge));
-
-        while updated_edge != self.edge_get_twin_(edge) {
-            self.edge_set_vertex0_(updated_edge, vertex);
-            updated_edge = self.edge_rot_next_(updated_edge);
-        }
-        let edge1 = edge;
-        let edge2 = self.edge_get_twin_(edge);
-
-        // Update prev/next pointers for the incident edges.
-        //edge1_rot_next->twin()->next(edge2_rot_prev);
-        self.edge_set_next_(
-            self.edge_get_twin_(self.edge_rot_next_(edge1)),
-            self.edge_rot_prev(edge2),
-        );
-        //edge2_rot_prev->prev(edge1_rot_next->twin());
-        self.edge_set_prev_(
-            self.edge_rot_prev(edge2),
-            self.edge_get_twin_(self.edge_rot_next_(edge1)),
-        );
-
-        //edge1_rot_prev->prev(edge2_rot_next->twin());
-        self.edge_set_prev_(
-            self.edge_rot_prev(edge1),
-            self.edge_get_twin_(self.edge_rot_next_(edge2)),
-        );
-
-        //edge2_rot_next->twin()->next(edge1_rot_prev);
-        self.edge_set_next_(
-            self.edge_get_twin_(self.edge_rot_next_(edge2)),
-            self.edge_rot_prev(edge1),
-        );
-    }
-
-    fn vertex_new_2_(&mut self, x: F, y: F, is_site_vertex: bool) -> VertexIndex {
-        let new_vertex_id = VertexIndex(self.vertices_.len());
-        let new_edge = Vertex::new_3(new_vertex_id, x, y, is_site_vertex);
-        self.vertices_.push(new_edge);
-        #[cfg(feature = "console_debug")]
-        assert_eq!(self.vertices_.len() - 1, new_vertex_id.0);
-        new_vertex_id
-    }
-
-    fn edge_set_vertex0_(&self, edge_id: Option<EdgeIndex>, vertex_id: Option<VertexIndex>) {
-        if edge_id.is_none() {
-            return;
-        }
-        if let Some(edgecell) = self.edge_get_(edge_id) {
-            let mut edge = edgecell.get();
-            edge.vertex_ = vertex_id;
-            edgecell.set(edge);
-        }
-    }
-
     #[inline]
     pub(crate) fn edge_get_vertex0_(&self, edge_id: Option<EdgeIndex>) -> Option<VertexIndex> {
         let _ = edge_id?;
@@ -1365,35 +1438,11 @@ impl<F: OutputType> Diagram<F> {
     }
 
     #[inline]
-    fn edge_set_prev_(&self, edge_id: Option<EdgeIndex>, prev_id: Option<EdgeIndex>) {
-        if edge_id.is_none() {
-            return;
-        }
-        if let Some(edgecell) = self.edge_get_(edge_id) {
-            let mut edge = edgecell.get();
-            edge.prev_ccw_ = prev_id;
-            edgecell.set(edge);
-        }
-    }
-
-    #[inline]
-    fn edge_set_next_(&self, edge_id: Option<EdgeIndex>, next_id: Option<EdgeIndex>) {
-        if edge_id.is_none() {
-            return;
-        }
-        if let Some(edgecell) = self.edge_get_(edge_id) {
-            let mut edge = edgecell.get();
-            edge.next_ccw_ = next_id;
-            edgecell.set(edge);
-        }
-    }
-
-    #[inline]
-    fn edge_get_next_(&self, edge_id: Option<EdgeIndex>) -> Option<EdgeIndex> {
-        let _ = edge_id?;
-        self.edges_
-            .get(edge_id.unwrap().0)
-            .and_then(|x| x.get().next_())
+    fn edge_get_next_(&self, edge_id: Option<EdgeIndex>) -> Option<EdgeIndex> {
+        let _ = edge_id?;
+        self.edges_
+            .get(edge_id.unwrap().0)
+            .and_then(|x| x.get().next_())
     }
 
     #[inline]
@@ -1431,16 +1480,344 @@ impl<F: OutputType> Diagram<F> {
         self.edge_get_next_(twin)
     }
 
+    /// prints cells and vertices to the console
+    /// edges will be printed if the 'edge_filter' returns true for that edge id.
+    #[cfg(feature = "console_debug")]
+    pub fn debug_print_all<FN: Fn(usize) -> bool>(&self, edge_filter: FN) {
+        tln!();
+        tln!("output:");
+        for (i, c) in self.cells_.iter().enumerate() {
+            let cc = c.get();
+            print!("cell#{} {:?} ", i, &cc);
+            if cc.contains_point() {
+                tln!("point");
+            } else if cc.contains_segment() {
+                tln!("segment");
+            } else if cc.contains_segment_startpoint() {
+                tln!("startpoint");
+            } else if cc.contains_segment_endpoint() {
+                tln!("endpoint");
+            } else {
+                tln!();
+            }
+        }
+        for (i, v) in self.vertices_.iter().enumerate() {
+            assert_eq!(i, v.get().id_.0);
+
+            let edges1: Vec<usize> = self
+                .edge_rot_next_iterator(v.get().get_incident_edge_())
+                .map(|x| x.0)
+                .filter(|x| edge_filter(*x))
+                .collect();
+            let edges2: Vec<usize> = self
+                .edge_rot_next_iterator(v.get().get_incident_edge_())
+                .filter_map(|x| self.edge_get_twin_(Some(x)))
+                .map(|x| x.0)
+                .filter(|x| edge_filter(*x))
+                .collect();
+            //if !(edges1.is_empty() && edges2.is_empty()) {
+            t!("vertex#{} {:?}", i, &v.get());
+            t!(" outgoing edges:{:?}", edges1);
+            tln!(" incoming edges:{:?}", edges2);
+            //}
+        }
+    }
+
+    #[cfg(feature = "console_debug")]
+    pub fn debug_print_edges(&self, text: &str) {
+        tln!("edges {} {}", text, self.edges_.len());
+        for (i, e) in self.edges_.iter().enumerate() {
+            let e = e.get();
+            tln!("edge{} ({:?})", e.id_.0, &e);
+            assert_eq!(i, e.id_.0);
+        }
+    }
+}
+
+/// The storage the sweep-line algorithm of the `Builder` writes its output into.
+/// Implemented by both `Diagram` and `SyncDiagram`, so the sweep fills either of them directly.
+///
+/// Only the element access is implemented per type, the construction of the diagram is done
+/// by the provided methods.
+pub(crate) trait DiagramOutput<F: OutputType> {
+    /// reserves space for an number of additional sites
+    fn reserve_(&mut self, additional_sites: usize);
+
+    /// clear the list of cells, vertices and edges
+    fn clear_(&mut self);
+
+    fn path_records_mut_(&mut self) -> &mut Vec<PathRecord>;
+
+    fn num_cells_(&self) -> usize;
+
+    fn num_vertices_(&self) -> usize;
+
+    fn num_edges_(&self) -> usize;
+
+    fn push_cell_(&mut self, cell: Cell);
+
+    fn push_vertex_(&mut self, vertex: Vertex<F>);
+
+    fn push_edge_(&mut self, edge: Edge);
+
+    /// Returns a copy of the cell
+    fn cell_(&self, cell_id: usize) -> Option<Cell>;
+
+    /// Returns a copy of the vertex
+    fn vertex_(&self, vertex_id: usize) -> Option<Vertex<F>>;
+
+    /// Returns a copy of the edge
+    fn edge_(&self, edge_id: usize) -> Option<Edge>;
+
+    fn update_cell_(&mut self, cell_id: usize, f: impl FnOnce(&mut Cell));
+
+    fn update_vertex_(&mut self, vertex_id: usize, f: impl FnOnce(&mut Vertex<F>));
+
+    fn update_edge_(&mut self, edge_id: usize, f: impl FnOnce(&mut Edge));
+
+    fn truncate_vertices_(&mut self, len: usize);
+
+    fn truncate_edges_(&mut self, len: usize);
+
+    /// push a new cell on the output. Nothing but id and source category is initialized
+    fn make_new_cell_with_category_(
+        &mut self,
+        cell_id: CellIndex, // same as sorted_index
+        initial_index: SourceIndex,
+        sc: ColorBits,
+    ) -> CellIndex {
+        // fill cell with temporary blocks- they will be over-written later
+        // Todo: fix this dirty hack with Option<>
+        while self.num_cells_() < cell_id.0 {
+            self.push_cell_(Cell::new(
+                CellIndex(usize::MAX),
+                usize::MAX,
+                ColorBits::TEMPORARY_CELL.0,
+            ));
+        }
+        self.push_cell_(Cell::new(cell_id, initial_index, sc.0));
+        #[cfg(feature = "console_debug")]
+        {
+            let cell = self.cell_(cell_id.0).unwrap();
+            assert_eq!(cell.id_.0, cell_id.0);
+            assert_eq!(cell.source_index_, initial_index);
+            assert_eq!(cell.color_, sc.0);
+        }
+        cell_id
+    }
+
+    fn process_single_site_<I: InputType>(&mut self, site: &VSE::SiteEvent<I, F>) {
+        let _ = self.make_new_cell_with_category_(
+            CellIndex(site.sorted_index()),
+            site.initial_index(),
+            site.source_category(),
+        );
+    }
+
+    #[inline]
+    fn cell_set_incident_edge_(&mut self, cell_id: Option<CellIndex>, edge: Option<EdgeIndex>) {
+        if let Some(cell_id) = cell_id {
+            self.update_cell_(cell_id.0, |c| c.incident_edge_ = edge);
+        }
+    }
+
+    #[inline]
+    fn cell_get_incident_edge_(&self, cell_id: Option<CellIndex>) -> Option<EdgeIndex> {
+        self.cell_(cell_id?.0)?.incident_edge_
+    }
+
+    #[inline]
+    fn cell_is_degenerate_(&self, cell_id: Option<CellIndex>) -> bool {
+        cell_id
+            .and_then(|c| self.cell_(c.0))
+            .is_some_and(|c| c.is_degenerate())
+    }
+
+    /// Overwrites the content of dest with the content of source.
+    /// vertex_id is compensated accordingly
+    fn vertex_copy_(&mut self, dest: usize, source: usize) {
+        if let Some(mut v) = self.vertex_(source) {
+            v.id_ = VertexIndex(dest);
+            self.update_vertex_(dest, |d| *d = v);
+        }
+    }
+
+    #[inline]
+    fn vertex_set_incident_edge_(
+        &mut self,
+        vertex_id: Option<VertexIndex>,
+        edge: Option<EdgeIndex>,
+    ) {
+        if let Some(vertex_id) = vertex_id {
+            self.update_vertex_(vertex_id.0, |v| v.incident_edge_ = edge);
+        }
+    }
+
+    #[inline]
+    fn vertex_get_incident_edge_(&self, vertex_id: Option<VertexIndex>) -> Option<EdgeIndex> {
+        self.vertex_(vertex_id?.0)?.incident_edge_
+    }
+
+    fn vertex_new_2_(&mut self, x: F, y: F, is_site_vertex: bool) -> VertexIndex {
+        let new_vertex_id = VertexIndex(self.num_vertices_());
+        self.push_vertex_(Vertex::new_(new_vertex_id, x, y, is_site_vertex));
+        new_vertex_id
+    }
+
+    /// Create and insert a new edge
+    fn create_and_insert_edge(
+        &mut self,
+        cell_id: CellIndex,
+        is_linear: bool,
+        is_primary: bool,
+    ) -> EdgeIndex {
+        let new_edge_id = EdgeIndex(self.num_edges_());
+        self.push_edge_(Edge::new_(new_edge_id, cell_id, is_linear, is_primary));
+        tln!("Created and inserted new edge : e={}", new_edge_id.0);
+        new_edge_id
+    }
+
+    /// Overwrites the content of dest with the content of source.
+    /// edge_id of the new dest is corrected
+    fn edge_copy_(&mut self, dest: usize, source: usize) {
+        if let Some(mut e) = self.edge_(source) {
+            e.id_ = EdgeIndex(dest);
+            self.update_edge_(dest, |d| *d = e);
+        }
+    }
+
+    #[inline]
+    fn edge_set_twin_(&mut self, edge_id: Option<EdgeIndex>, twin_id: Option<EdgeIndex>) {
+        if let Some(edge_id) = edge_id {
+            self.update_edge_(edge_id.0, |e| e.twin_ = twin_id);
+        }
+    }
+
+    #[inline]
+    fn edge_get_twin_(&self, edge_id: Option<EdgeIndex>) -> Option<EdgeIndex> {
+        self.edge_(edge_id?.0)?.twin_
+    }
+
+    #[inline]
+    fn edge_set_cell_(&mut self, edge_id: Option<EdgeIndex>, cell_id: Option<CellIndex>) {
+        if let Some(edge_id) = edge_id {
+            self.update_edge_(edge_id.0, |e| e.cell_ = cell_id);
+        }
+    }
+
+    #[inline]
+    fn edge_get_cell_(&self, edge_id: Option<EdgeIndex>) -> Option<CellIndex> {
+        self.edge_(edge_id?.0)?.cell_
+    }
+
+    #[inline]
+    fn edge_set_vertex0_(&mut self, edge_id: Option<EdgeIndex>, vertex_id: Option<VertexIndex>) {
+        if let Some(edge_id) = edge_id {
+            self.update_edge_(edge_id.0, |e| e.vertex_ = vertex_id);
+        }
+    }
+
+    #[inline]
+    fn edge_get_vertex0_(&self, edge_id: Option<EdgeIndex>) -> Option<VertexIndex> {
+        self.edge_(edge_id?.0)?.vertex_
+    }
+
+    #[inline]
+    fn edge_get_vertex1_(&self, edge_id: Option<EdgeIndex>) -> Option<VertexIndex> {
+        self.edge_get_vertex0_(self.edge_get_twin_(edge_id))
+    }
+
+    #[inline]
+    fn edge_set_prev_(&mut self, edge_id: Option<EdgeIndex>, prev_id: Option<EdgeIndex>) {
+        if let Some(edge_id) = edge_id {
+            self.update_edge_(edge_id.0, |e| e.prev_ccw_ = prev_id);
+        }
+    }
+
+    #[inline]
+    fn edge_set_next_(&mut self, edge_id: Option<EdgeIndex>, next_id: Option<EdgeIndex>) {
+        if let Some(edge_id) = edge_id {
+            self.update_edge_(edge_id.0, |e| e.next_ccw_ = next_id);
+        }
+    }
+
+    #[inline]
+    fn edge_get_next_(&self, edge_id: Option<EdgeIndex>) -> Option<EdgeIndex> {
+        self.edge_(edge_id?.0)?.next_ccw_
+    }
+
+    #[inline]
+    fn edge_get_prev_(&self, edge_id: Option<EdgeIndex>) -> Option<EdgeIndex> {
+        self.edge_(edge_id?.0)?.prev_ccw_
+    }
+
+    #[inline]
+    /// Returns a pointer to the rotation next edge
+    /// over the starting point of the half-edge.
+    fn edge_rot_next_(&self, edge_id: Option<EdgeIndex>) -> Option<EdgeIndex> {
+        self.edge_get_twin_(self.edge_get_prev_(edge_id))
+    }
+
+    #[inline]
+    /// Returns a pointer to the rotation previous edge
+    /// over the starting point of the half-edge.
+    fn edge_rot_prev_(&self, edge_id: Option<EdgeIndex>) -> Option<EdgeIndex> {
+        self.edge_get_next_(self.edge_get_twin_(edge_id))
+    }
+
+    /// Remove degenerate edge.
+    fn remove_edge_(&mut self, edge: Option<EdgeIndex>) {
+        #[cfg(feature = "console_debug")]
+        if let Some(edge_id) = edge {
+            tln!("removing edge: {}", edge_id.0);
+        } else {
+            tln!("removing edge: but it was None!");
+            return;
+        }
+        // Update the endpoints of the incident edges to the second vertex.
+        let vertex = self.edge_get_vertex0_(edge);
+        let mut updated_edge = self.edge_rot_next_(self.edge_get_twin_(edge));
+
+        while updated_edge != self.edge_get_twin_(edge) {
+            self.edge_set_vertex0_(updated_edge, vertex);
+            updated_edge = self.edge_rot_next_(updated_edge);
+        }
+        let edge1 = edge;
+        let edge2 = self.edge_get_twin_(edge);
+
+        // Update prev/next pointers for the incident edges.
+        //edge1_rot_next->twin()->next(edge2_rot_prev);
+        self.edge_set_next_(
+            self.edge_get_twin_(self.edge_rot_next_(edge1)),
+            self.edge_rot_prev_(edge2),
+        );
+        //edge2_rot_prev->prev(edge1_rot_next->twin());
+        self.edge_set_prev_(
+            self.edge_rot_prev_(edge2),
+            self.edge_get_twin_(self.edge_rot_next_(edge1)),
+        );
+
+        //edge1_rot_prev->prev(edge2_rot_next->twin());
+        self.edge_set_prev_(
+            self.edge_rot_prev_(edge1),
+            self.edge_get_twin_(self.edge_rot_next_(edge2)),
+        );
+
+        //edge2_rot_next->twin()->next(edge1_rot_prev);
+        self.edge_set_next_(
+            self.edge_get_twin_(self.edge_rot_next_(edge2)),
+            self.edge_rot_prev_(edge1),
+        );
+    }
+
     /// Insert a new half-edge into the output data structure.
     /// Takes as input left and right sites that form a new bisector.
     /// Returns a pair of pointers to new half-edges.
-    pub(crate) fn insert_new_edge_2_<I: InputType>(
+    fn insert_new_edge_2_<I: InputType>(
         &mut self,
         site1: VSE::SiteEvent<I, F>,
         site2: VSE::SiteEvent<I, F>,
     ) -> (EdgeIndex, EdgeIndex) {
-        //tln!("-> insert_new_edge_2()");
-        //tln!("site1:{:?}\nsite2:{:?}", &site1, &site2);
         // Get sites' indexes.
         let site1_index = site1.sorted_index();
         let site2_index = site2.sorted_index();
@@ -1455,7 +1832,7 @@ impl<F: OutputType> Diagram<F> {
         let edge2_id = self.create_and_insert_edge(CellIndex(site2_index), is_linear, is_primary);
 
         // Add the initial cell during the first edge insertion.
-        if self.cells_.is_empty() {
+        if self.num_cells_() == 0 {
             let _ = self.make_new_cell_with_category_(
                 CellIndex(site1_index),
                 site1.initial_index(),
@@ -1471,7 +1848,7 @@ impl<F: OutputType> Diagram<F> {
             site2.source_category(),
         );
 
-        // Set up pointers to cells. Todo! is this needed? Didn't we do this already?
+        // Set up pointers to cells.
         self.edge_set_cell_(Some(edge1_id), Some(CellIndex(site1_index)));
         self.edge_set_cell_(Some(edge2_id), Some(CellIndex(site2_index)));
 
@@ -1479,9 +1856,6 @@ impl<F: OutputType> Diagram<F> {
         self.edge_set_twin_(Some(edge1_id), Some(edge2_id));
         self.edge_set_twin_(Some(edge2_id), Some(edge1_id));
 
-        //tln!("edge1: {:?}", self.get_edge_(edge1_id).get());
-        //tln!("edge2: {:?}", self.get_edge_(edge2_id).get());
-        //tln!("edges.len():{}", self.edges_.len());
         (edge1_id, edge2_id)
     }
 
@@ -1491,7 +1865,7 @@ impl<F: OutputType> Diagram<F> {
     /// that corresponds to the intersection point of the two old half-edges,
     /// pointers to those half-edges. Half-edges' direction goes out of the
     /// new Voronoi vertex point. Returns a pair of pointers to a new half-edges.
-    pub(crate) fn insert_new_edge_5_<I: InputType>(
+    fn insert_new_edge_5_<I: InputType>(
         &mut self,
         site1: VSE::SiteEvent<I, F>,
         site3: VSE::SiteEvent<I, F>,
@@ -1499,15 +1873,6 @@ impl<F: OutputType> Diagram<F> {
         edge12_id: EdgeIndex,
         edge23_id: EdgeIndex,
     ) -> (EdgeIndex, EdgeIndex) {
-        /*tln!("-> insert_new_edge_5()");
-        tln!(
-            "site1:{:?}\nsite3:{:?}\ncircle:{:?}\nedge12_id:{:?}\nedge23_id{:?}\n",
-            &site1,
-            &site3,
-            &circle,
-            edge12_id,
-            edge23_id
-        );*/
         tln!("new vertex@{:?}", circle);
 
         let is_linear = VSE::SiteEvent::<I, F>::is_linear_edge(&site1, &site3);
@@ -1527,6 +1892,9 @@ impl<F: OutputType> Diagram<F> {
             cast::<f64, F>(circle.y()),
             circle.is_site_point(),
         );
+        // The circle event is at the rightmost point of the circle
+        let radius = cast::<f64, F>(circle.lower_x() - circle.x());
+        self.update_vertex_(new_vertex_id.0, |v| v.radius_ = radius);
 
         // Update vertex pointers of the old edges.
         self.edge_set_vertex0_(Some(edge12_id), Some(new_vertex_id));
@@ -1561,39 +1929,34 @@ impl<F: OutputType> Diagram<F> {
         //new_edge2.prev(edge23->twin());
         self.edge_set_prev_(Some(new_edge2_id), edge23_twin_id);
 
-        //tln!("edge12: {:?}", self.get_edge_(edge12_id).get());
-        //tln!("edge23: {:?}", self.get_edge_(edge23_id).get());
-        //tln!("edges.len():{}", self.edges_.len());
         // Return a pointer to the new half-edge.
         (new_edge1_id, new_edge2_id)
     }
 
     /// Make sure the diagram is consistent. Removes degenerate edges, connects incident
     /// edges etc. etc
-    pub(crate) fn finish(&mut self) {
+    fn finish(&mut self) {
         // Remove degenerate edges.
         #[cfg(feature = "console_debug")]
-        self.debug_print_edges("b4 degenerate");
+        self.debug_print_edges_("b4 degenerate");
 
-        if !self.edges_.is_empty() {
+        if self.num_edges_() > 0 {
             let mut last_edge: usize = 0;
             let mut it: usize = last_edge;
-            let edges_end: usize = self.edges_.len();
+            let edges_end: usize = self.num_edges_();
 
-            //let mut edges_to_erase: Vec<usize> = Vec::new();
             while it < edges_end {
                 let is_equal = {
-                    let v1 = self.edge_get_vertex0_(Some(EdgeIndex(it)));
-                    let v1 = self.vertex_get_(v1);
-                    let v2 = self.edge_get_vertex1_(Some(EdgeIndex(it)));
-                    let v2 = self.vertex_get_(v2);
-                    //tln!("looking at edge:{}, v1={:?}, v2={:?}", it, v1, v2);
-                    v1.is_some()
-                        && v2.is_some()
-                        && v1
-                            .unwrap()
-                            .get()
-                            .vertex_equality_predicate_eq(&v2.unwrap().get())
+                    let v1 = self
+                        .edge_get_vertex0_(Some(EdgeIndex(it)))
+                        .and_then(|v| self.vertex_(v.0));
+                    let v2 = self
+                        .edge_get_vertex1_(Some(EdgeIndex(it)))
+                        .and_then(|v| self.vertex_(v.0));
+                    match (v1, v2) {
+                        (Some(v1), Some(v2)) => v1.vertex_equality_predicate_eq(&v2),
+                        _ => false,
+                    }
                 };
 
                 if is_equal {
@@ -1632,16 +1995,14 @@ impl<F: OutputType> Diagram<F> {
                 }
                 it += 2;
             }
-            for e in (last_edge..edges_end).rev() {
-                let _ = self.edges_.remove(e);
-            }
+            self.truncate_edges_(last_edge);
         }
         #[cfg(feature = "console_debug")]
-        self.debug_print_edges("after degenerate");
+        self.debug_print_edges_("after degenerate");
         tln!();
 
         // Set up incident edge pointers for cells and vertices.
-        for edge_it in self.edges().iter().enumerate().map(|x| EdgeIndex(x.0)) {
+        for edge_it in (0..self.num_edges_()).map(EdgeIndex) {
             let cell = self.edge_get_cell_(Some(edge_it));
             if self.cell_get_incident_edge_(cell).is_none() {
                 self.cell_set_incident_edge_(cell, Some(edge_it));
@@ -1651,36 +2012,35 @@ impl<F: OutputType> Diagram<F> {
         }
 
         #[cfg(feature = "console_debug")]
-        for (i, v) in self.vertices_.iter().enumerate() {
+        for (i, v) in (0..self.num_vertices_()).filter_map(|i| Some((i, self.vertex_(i)?))) {
             tln!(
                 "vertex #{} contains a point: ({:.12}, {:.12}) ie:{}",
                 i,
-                v.get().x(),
-                v.get().y(),
-                v.get()
-                    .get_incident_edge_()
-                    .map_or("-".to_string(), |x| x.0.clone().to_string())
+                v.x(),
+                v.y(),
+                v.get_incident_edge_()
+                    .map_or("-".to_string(), |x| x.0.to_string())
             );
         }
 
-        tln!("vertices b4 degenerate {}", self.vertices_.len());
+        tln!("vertices b4 degenerate {}", self.num_vertices_());
         // Remove degenerate vertices.
-        if !self.vertices_.is_empty() {
-            let mut last_vertex_iterator = (0..self.vertices_.len()).map(VertexIndex);
+        if self.num_vertices_() > 0 {
+            let mut last_vertex_iterator = (0..self.num_vertices_()).map(VertexIndex);
             let mut last_vertex = last_vertex_iterator.next();
-            for it in (0..self.vertices_.len()).map(VertexIndex) {
+            for it in (0..self.num_vertices_()).map(VertexIndex) {
                 let sit = Some(it);
-                if self.vertex_get_incident_edge(sit).is_some() {
+                if self.vertex_get_incident_edge_(sit).is_some() {
                     if sit != last_vertex {
                         self.vertex_copy_(last_vertex.unwrap().0, it.0);
                         let v = last_vertex;
-                        let mut e = self.vertex_get_incident_edge(last_vertex);
+                        let mut e = self.vertex_get_incident_edge_(last_vertex);
                         loop {
                             //e->vertex0(v);
                             self.edge_set_vertex0_(e, v);
                             // e = e->rot_next();
                             e = self.edge_rot_next_(e);
-                            if self.vertex_get_incident_edge(v) == e {
+                            if self.vertex_get_incident_edge_(v) == e {
                                 break;
                             }
                         }
@@ -1689,18 +2049,16 @@ impl<F: OutputType> Diagram<F> {
                 }
             }
             if let Some(last_vertex) = last_vertex {
-                for v in (last_vertex.0..self.vertices_.len()).rev() {
-                    let _ = self.vertices_.remove(v);
-                }
+                self.truncate_vertices_(last_vertex.0);
             }
         }
-        tln!("vertices after degenerate {}", self.vertices_.len());
+        tln!("vertices after degenerate {}", self.num_vertices_());
 
         // Set up next/prev pointers for infinite edges.
-        if self.vertices_.is_empty() {
-            if !self.edges_.is_empty() {
+        if self.num_vertices_() == 0 {
+            if self.num_edges_() > 0 {
                 // Update prev/next pointers for the line edges.
-                let mut edge_it = self.edges_.iter().enumerate().map(|x| x.0);
+                let mut edge_it = 0..self.num_edges_();
 
                 let mut edge1 = edge_it.next().map(EdgeIndex);
                 self.edge_set_next_(edge1, edge1);
@@ -1711,7 +2069,6 @@ impl<F: OutputType> Diagram<F> {
                 while edge_it_value.is_some() {
                     let edge2 = edge_it_value.map(EdgeIndex);
                     edge_it_value = edge_it.next();
-                    //dbg!(edge1.unwrap(),edge2.unwrap());
 
                     self.edge_set_next_(edge1, edge2);
                     self.edge_set_prev_(edge1, edge2);
@@ -1726,14 +2083,13 @@ impl<F: OutputType> Diagram<F> {
             }
         } else {
             // Update prev/next pointers for the ray edges.
-            // let mut cell_it_keys = self.cells_.keys();
-            for cell_it in 0..self.cells_.len() {
-                if self.cell_is_degenerate_(Some(CellIndex(cell_it))) {
+            for cell_it in (0..self.num_cells_()).map(|c| Some(CellIndex(c))) {
+                if self.cell_is_degenerate_(cell_it) {
                     continue;
                 }
                 // Move to the previous edge while
                 // it is possible in the CW direction.
-                let mut left_edge = self.cell_get_incident_edge_(Some(CellIndex(cell_it)));
+                let mut left_edge = self.cell_get_incident_edge_(cell_it);
                 let terminal_edge = left_edge;
                 while let Some(new_left_edge) = self.edge_get_prev_(left_edge) {
                     left_edge = Some(new_left_edge);
@@ -1747,7 +2103,7 @@ impl<F: OutputType> Diagram<F> {
                     continue;
                 }
 
-                let mut right_edge = self.cell_get_incident_edge_(Some(CellIndex(cell_it)));
+                let mut right_edge = self.cell_get_incident_edge_(cell_it);
                 while let Some(new_right_edge) = self.edge_get_next_(right_edge) {
                     right_edge = Some(new_right_edge);
                 }
@@ -1758,67 +2114,148 @@ impl<F: OutputType> Diagram<F> {
         }
     }
 
-    /// prints cells and vertices to the console
-    /// edges will be printed if the 'edge_filter' returns true for that edge id.
     #[cfg(feature = "console_debug")]
-    pub fn debug_print_all<FN: Fn(usize) -> bool>(&self, edge_filter: FN) {
-        tln!();
-        tln!("output:");
-        for (i, c) in self.cells_.iter().enumerate() {
-            let cc = c.get();
-            print!("cell#{} {:?} ", i, &cc);
-            if cc.contains_point() {
-                tln!("point");
-            } else if cc.contains_segment() {
-                tln!("segment");
-            } else if cc.contains_segment_startpoint() {
-                tln!("startpoint");
-            } else if cc.contains_segment_endpoint() {
-                tln!("endpoint");
-            } else {
-                tln!();
-            }
+    fn debug_print_edges_(&self, text: &str) {
+        tln!("edges {} {}", text, self.num_edges_());
+        for (i, e) in (0..self.num_edges_()).filter_map(|i| Some((i, self.edge_(i)?))) {
+            tln!("edge{} ({:?})", e.id_.0, &e);
+            assert_eq!(i, e.id_.0);
         }
-        for (i, v) in self.vertices_.iter().enumerate() {
-            assert_eq!(i, v.get().id_.0);
+    }
+}
 
-            let edges1: Vec<usize> = self
-                .edge_rot_next_iterator(v.get().get_incident_edge_())
-                .map(|x| x.0)
-                .filter(|x| edge_filter(*x))
-                .collect();
-            let edges2: Vec<usize> = self
-                .edge_rot_next_iterator(v.get().get_incident_edge_())
-                .filter_map(|x| self.edge_get_twin_(Some(x)))
-                .map(|x| x.0)
-                .filter(|x| edge_filter(*x))
-                .collect();
-            //if !(edges1.is_empty() && edges2.is_empty()) {
-            t!("vertex#{} {:?}", i, &v.get());
-            t!(" outgoing edges:{:?}", edges1);
-            tln!(" incoming edges:{:?}", edges2);
-            //}
+impl<F: OutputType> DiagramOutput<F> for Diagram<F> {
+    #[inline]
+    fn reserve_(&mut self, additional_sites: usize) {
+        Diagram::reserve_(self, additional_sites);
+    }
+
+    #[inline]
+    fn clear_(&mut self) {
+        self.clear();
+    }
+
+    #[inline]
+    fn path_records_mut_(&mut self) -> &mut Vec<PathRecord> {
+        &mut self.path_records_
+    }
+
+    #[inline]
+    fn num_cells_(&self) -> usize {
+        self.cells_.len()
+    }
+
+    #[inline]
+    fn num_vertices_(&self) -> usize {
+        self.vertices_.len()
+    }
+
+    #[inline]
+    fn num_edges_(&self) -> usize {
+        self.edges_.len()
+    }
+
+    #[inline]
+    fn push_cell_(&mut self, cell: Cell) {
+        self.cells_.push(Rc::new(cell::Cell::new(cell)));
+    }
+
+    #[inline]
+    fn push_vertex_(&mut self, vertex: Vertex<F>) {
+        self.vertices_.push(Rc::new(cell::Cell::new(vertex)));
+    }
+
+    #[inline]
+    fn push_edge_(&mut self, edge: Edge) {
+        self.edges_.push(Rc::new(cell::Cell::new(edge)));
+    }
+
+    #[inline]
+    fn cell_(&self, cell_id: usize) -> Option<Cell> {
+        self.cells_.get(cell_id).map(|c| c.get())
+    }
+
+    #[inline]
+    fn vertex_(&self, vertex_id: usize) -> Option<Vertex<F>> {
+        self.vertices_.get(vertex_id).map(|v| v.get())
+    }
+
+    #[inline]
+    fn edge_(&self, edge_id: usize) -> Option<Edge> {
+        self.edges_.get(edge_id).map(|e| e.get())
+    }
+
+    #[inline]
+    fn update_cell_(&mut self, cell_id: usize, f: impl FnOnce(&mut Cell)) {
+        if let Some(cell) = self.cells_.get(cell_id) {
+            let mut c = cell.get();
+            f(&mut c);
+            cell.set(c);
         }
     }
 
-    #[cfg(feature = "console_debug")]
-    pub fn debug_print_edges(&self, text: &str) {
-        tln!("edges {} {}", text, self.edges_.len());
-        for (i, e) in self.edges_.iter().enumerate() {
-            let e = e.get();
-            tln!("edge{} ({:?})", e.id_.0, &e);
-            assert_eq!(i, e.id_.0);
+    #[inline]
+    fn update_vertex_(&mut self, vertex_id: usize, f: impl FnOnce(&mut Vertex<F>)) {
+        if let Some(vertex) = self.vertices_.get(vertex_id) {
+            let mut v = vertex.get();
+            f(&mut v);
+            vertex.set(v);
         }
     }
+
+    #[inline]
+    fn update_edge_(&mut self, edge_id: usize, f: impl FnOnce(&mut Edge)) {
+        if let Some(edge) = self.edges_.get(edge_id) {
+            let mut e = edge.get();
+            f(&mut e);
+            edge.set(e);
+        }
+    }
+
+    #[inline]
+    fn truncate_vertices_(&mut self, len: usize) {
+        self.vertices_.truncate(len);
+    }
+
+    #[inline]
+    fn truncate_edges_(&mut self, len: usize) {
+        self.edges_.truncate(len);
+    }
 }
 
 impl<F: OutputType> From<Diagram<F>> for SD::SyncDiagram<F> {
     /// Converts a `Diagram` into a `SyncDiagram` by dropping the `std::cell::Cell` and `Rc`
     fn from(other: Diagram<F>) -> SD::SyncDiagram<F> {
-        SD::SyncDiagram::new(
+        let mut rv = SD::SyncDiagram::new(
             other.cells_.into_iter().map(|x| x.get()).collect(),
             other.vertices_.into_iter().map(|x| x.get()).collect(),
             other.edges_.into_iter().map(|x| x.get()).collect(),
-        )
+        );
+        rv.path_records_ = other.path_records_;
+        rv
+    }
+}
+
+impl<F: OutputType> From<SD::SyncDiagram<F>> for Diagram<F> {
+    /// Converts a `SyncDiagram` into a `Diagram` by wrapping every item in a `Rc<std::cell::Cell>`
+    fn from(other: SD::SyncDiagram<F>) -> Diagram<F> {
+        Diagram {
+            cells_: other
+                .cells_
+                .into_iter()
+                .map(|c| Rc::new(cell::Cell::new(c)))
+                .collect(),
+            vertices_: other
+                .vertices_
+                .into_iter()
+                .map(|v| Rc::new(cell::Cell::new(v)))
+                .collect(),
+            edges_: other
+                .edges_
+                .into_iter()
+                .map(|e| Rc::new(cell::Cell::new(e)))
+                .collect(),
+            path_records_: other.path_records_,
+        }
     }
 }
@@ -0,0 +1,123 @@
+//          Copyright Eadf (github.com/eadf) 2021.
+// Distributed under the Boost Software License, Version 1.0.
+//    (See accompanying file LICENSE_1_0.txt or copy at
+//          http://www.boost.org/LICENSE_1_0.txt)
+
+//! Largest empty circle and maximum inscribed circle queries.
+//!
+//! The distance to the closest site is convex inside each voronoi cell, so its maximum over a
+//! region is found at a voronoi vertex, where an edge leaves the region, or at a corner of the
+//! region.
+
+use crate::clipping::{ConvexRegion, DiagramClipper};
+use crate::diagram::{self as VD, SourceCategory};
+use crate::geometry::{Point, Polygon};
+use crate::medial_axis::PolygonSegments;
+use crate::{cast, BvError, InputType, OutputType};
+
+/// A circle, with the center and the radius in diagram coordinates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EmptyCircle<F: OutputType> {
+    pub center: [F; 2],
+    pub radius: F,
+}
+
+/// Returns the largest circle with its center inside `bounds` that contains none of the
+/// `points`, or `None` if there are no points.
+///
+/// `points` must be the input of the `Builder` the diagram was built from.
+/// ```
+/// # use boostvoronoi_core::builder::Builder;
+/// # use boostvoronoi_core::clipping::ConvexRegion;
+/// # use boostvoronoi_core::empty_circle::largest_empty_circle;
+/// # use boostvoronoi_core::geometry::Point;
+/// # use boostvoronoi_core::BvError;
+/// let points: Vec<Point<i32>> = vec![[0, 0].into(), [10, 0].into(), [0, 10].into()];
+/// let diagram = Builder::<i32, f64>::default()
+///     .with_vertices(points.iter())?
+///     .build()?;
+/// let bounds = ConvexRegion::new(vec![[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0]])?;
+/// let circle = largest_empty_circle(&diagram, &points, &bounds)?.unwrap();
+/// assert_eq!(circle.center, [10.0, 10.0]);
+/// assert_eq!(circle.radius, 10.0);
+/// # Ok::<(), BvError>(())
+/// ```
+/// # Errors
+/// Will return `BvError::ValueError` if the diagram contains segment sites.
+pub fn largest_empty_circle<I: InputType, F: OutputType>(
+    diagram: &VD::Diagram<F>,
+    points: &[Point<I>],
+    bounds: &ConvexRegion<F>,
+) -> Result<Option<EmptyCircle<F>>, BvError> {
+    let mut best: Option<EmptyCircle<F>> = None;
+    let clipper = DiagramClipper::new(diagram, points, &[], bounds.clone());
+    for cell in diagram.cells().iter() {
+        let cell = cell.get();
+        let (source_index, category) = cell.source_index_2();
+        if category != SourceCategory::SinglePoint {
+            return Err(BvError::ValueError(format!(
+                "The largest empty circle requires point sites only, cell {} is not a point",
+                cell.id().0
+            )));
+        }
+        let site = points.get(source_index).ok_or_else(|| {
+            BvError::IdError(format!(
+                "The source index {} of cell {} is not part of the input",
+                source_index,
+                cell.id().0
+            ))
+        })?;
+        let site = [cast::<I, F>(site.x), cast::<I, F>(site.y)];
+        // the cell has no curved edges, the deviation is not used
+        for p in clipper.cell_polygon(cell.id(), F::one())? {
+            let radius = (p[0] - site[0]).hypot(p[1] - site[1]);
+            if !best.is_some_and(|b| radius <= b.radius) {
+                best = Some(EmptyCircle { center: p, radius });
+            }
+        }
+    }
+    Ok(best)
+}
+
+/// Returns the largest circle inside the polygons, or `None` if the diagram has no vertex
+/// inside the polygons.
+///
+/// The diagram must be built from the `polygons` only, using `Builder::with_polygons()`.
+/// The center of the circle is a voronoi vertex; every vertex is classified as inside or
+/// outside by the side of the polygon segments it is closest to, so holes are excluded.
+/// ```
+/// # use boostvoronoi_core::builder::Builder;
+/// # use boostvoronoi_core::empty_circle::maximum_inscribed_circle;
+/// # use boostvoronoi_core::geometry::Polygon;
+/// # use boostvoronoi_core::BvError;
+/// let polygons = [Polygon::<i32>::from(vec![[0, 0], [100, 0], [100, 40], [0, 40]])];
+/// let diagram = Builder::<i32, f64>::default()
+///     .with_polygons(polygons.iter().cloned())?
+///     .build()?;
+/// let circle = maximum_inscribed_circle(&diagram, &polygons)?.unwrap();
+/// assert_eq!(circle.radius, 20.0);
+/// assert_eq!(circle.center[1], 20.0);
+/// # Ok::<(), BvError>(())
+/// ```
+/// # Errors
+/// Will return `BvError::IdError` if the diagram has sites that are not part of the polygons.
+pub fn maximum_inscribed_circle<I: InputType, F: OutputType>(
+    diagram: &VD::Diagram<F>,
+    polygons: &[Polygon<I>],
+) -> Result<Option<EmptyCircle<F>>, BvError> {
+    let input = PolygonSegments::<I, F>::new(polygons);
+    let mut best: Option<EmptyCircle<F>> = None;
+    for vertex in diagram.vertices().iter() {
+        let vertex = vertex.get();
+        if best.is_some_and(|b| vertex.radius() <= b.radius) {
+            continue;
+        }
+        if input.vertex_is_inside(diagram, &vertex)? == Some(true) {
+            best = Some(EmptyCircle {
+                center: [vertex.x(), vertex.y()],
+                radius: vertex.radius(),
+            });
+        }
+    }
+    Ok(best)
+}
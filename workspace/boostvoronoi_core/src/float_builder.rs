@@ -0,0 +1,150 @@
+//          Copyright Eadf (github.com/eadf) 2021.
+// Distributed under the Boost Software License, Version 1.0.
+//    (See accompanying file LICENSE_1_0.txt or copy at
+//          http://www.boost.org/LICENSE_1_0.txt)
+
+//! A floating point front-end to the integer [`Builder`].
+//!
+//! The input geometry is quantized onto the integer grid using a uniform scale (so that the
+//! Voronoi diagram is preserved) that maps the input AABB onto the safe integer range.
+//! The vertices of the resulting diagram are mapped back to world coordinates.
+
+use crate::builder::Builder;
+use crate::diagram as VD;
+use crate::geometry::{Line, Point};
+use crate::visual_utils::{Aabb2, SimpleAffine};
+use crate::{cast, BvError, InputType, OutputType};
+use std::marker::PhantomData;
+
+/// The quantized input will be in the range `-SAFE_EXTENT..=SAFE_EXTENT`.
+/// Within this range all the integer predicates of the algorithm are exact, for both `i32` and
+/// `i64` input types.
+pub const SAFE_EXTENT: i32 = 1 << 30;
+
+/// A builder that accepts floating point input.
+/// ```
+/// # use boostvoronoi_core::float_builder::FloatBuilder;
+/// # use boostvoronoi_core::BvError;
+/// let points = [[0.5_f64, 0.5], [-0.5, 0.25]];
+/// let segments = [[-1.0_f64, -1.0, 1.0, -1.0]];
+/// let builder = FloatBuilder::<i32, f64>::default()
+///     .with_vertices(points.iter().copied())?
+///     .with_segments(segments.iter().copied())?;
+/// // The transform maps the integer coordinates used by the algorithm to world coordinates
+/// let transform = builder.transform();
+/// // The vertices of the diagram are already in world coordinates
+/// let diagram = builder.build()?;
+/// for v in diagram.vertices() {
+///     let v = v.get();
+///     assert!(v.x().abs() < 3.0 && v.y().abs() < 3.0);
+/// }
+/// let [x, y] = transform.reverse_transform::<i32>(0.5, 0.5)?;
+/// assert_eq!(transform.transform_i([x, y]), [0.5, 0.5]);
+/// # Ok::<(), BvError>(())
+/// ```
+pub struct FloatBuilder<I: InputType, F: OutputType> {
+    vertices_: Vec<[F; 2]>,
+    segments_: Vec<[F; 4]>,
+    #[doc(hidden)]
+    pd_: PhantomData<I>,
+}
+
+impl<I: InputType, F: OutputType> Default for FloatBuilder<I, F> {
+    fn default() -> Self {
+        Self {
+            vertices_: Vec::new(),
+            segments_: Vec::new(),
+            pd_: PhantomData,
+        }
+    }
+}
+
+impl<I: InputType, F: OutputType> FloatBuilder<I, F> {
+    /// Inserts vertices.
+    /// This method accepts iterators of anything that implements `Into<[F;2]>`
+    /// # Errors
+    /// Will return `BvError::ValueError` if a coordinate is not finite.
+    pub fn with_vertices<T, IT>(mut self, vertices: T) -> Result<Self, BvError>
+    where
+        T: IntoIterator<Item = IT>,
+        IT: Copy + Into<[F; 2]>,
+    {
+        for v in vertices.into_iter().map(|v| -> [F; 2] { v.into() }) {
+            if !(v[0].is_finite() && v[1].is_finite()) {
+                return Err(BvError::ValueError(format!("Non finite vertex: {:?}", v)));
+            }
+            self.vertices_.push(v);
+        }
+        Ok(self)
+    }
+
+    /// Inserts segments, given as `[x1, y1, x2, y2]`.
+    /// This method accepts iterators of anything that implements `Into<[F;4]>`
+    /// # Errors
+    /// Will return `BvError::ValueError` if a coordinate is not finite.
+    pub fn with_segments<T, IT>(mut self, segments: T) -> Result<Self, BvError>
+    where
+        T: IntoIterator<Item = IT>,
+        IT: Copy + Into<[F; 4]>,
+    {
+        for s in segments.into_iter().map(|s| -> [F; 4] { s.into() }) {
+            if !s.iter().all(|c| c.is_finite()) {
+                return Err(BvError::ValueError(format!("Non finite segment: {:?}", s)));
+            }
+            self.segments_.push(s);
+        }
+        Ok(self)
+    }
+
+    /// Returns the transform used for the input added so far.
+    /// `transform()` maps integer (diagram input) coordinates into world coordinates and
+    /// `reverse_transform()` maps world coordinates into integer coordinates.
+    pub fn transform(&self) -> SimpleAffine<F> {
+        let mut aabb = Aabb2::<F>::default();
+        for v in self.vertices_.iter() {
+            aabb.update_vertex(v[0], v[1]);
+        }
+        for s in self.segments_.iter() {
+            aabb.update_vertex(s[0], s[1]);
+            aabb.update_vertex(s[2], s[3]);
+        }
+        let two = cast::<i32, F>(2);
+        let mut rv = SimpleAffine::<F>::default();
+        if let (Some(low), Some(high)) = (aabb.get_low(), aabb.get_high()) {
+            let half_size = ((high[0] - low[0]) / two).max((high[1] - low[1]) / two);
+            if half_size > F::zero() {
+                let scale = half_size / cast::<i32, F>(SAFE_EXTENT);
+                rv.scale = [scale, scale];
+            }
+            rv.to_offset = [(low[0] + high[0]) / two, (low[1] + high[1]) / two];
+        }
+        rv
+    }
+
+    /// Quantizes the input, runs the sweep-line algorithm and maps the vertices of the output
+    /// back to world coordinates.
+    pub fn build(self) -> Result<VD::Diagram<F>, BvError> {
+        let transform = self.transform();
+        let to_int = |x: F, y: F| -> Result<Point<I>, BvError> {
+            let [x, y] = transform.reverse_transform::<I>(x, y)?;
+            Ok(Point { x, y })
+        };
+        let vertices = self
+            .vertices_
+            .iter()
+            .map(|v| to_int(v[0], v[1]))
+            .collect::<Result<Vec<_>, _>>()?;
+        let segments = self
+            .segments_
+            .iter()
+            .map(|s| Ok(Line::new(to_int(s[0], s[1])?, to_int(s[2], s[3])?)))
+            .collect::<Result<Vec<_>, BvError>>()?;
+        let diagram = Builder::<I, F>::default()
+            .with_vertices(vertices.iter())?
+            .with_segments(segments.iter())?
+            .build()?;
+        // the scale is uniform, the radius of the vertices scales with it
+        diagram.transform_vertices_(|x, y| transform.transform(x, y), transform.scale[0]);
+        Ok(diagram)
+    }
+}
@@ -987,3 +987,77 @@ impl<F: OutputType + nalgebra::Scalar> From<Vertex<F>> for nalgebra::Point2<F> {
         Self::new(v.x(), v.y())
     }
 }
+
+/// A simple polygon type - integer only.
+/// The `exterior` ring and the `interiors` (holes) are implicitly closed, if the last point of a
+/// ring is equal to the first point it will be ignored.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, cmp::PartialEq, cmp::Eq, Hash, Debug, Default)]
+pub struct Polygon<T: InputType> {
+    pub exterior: Vec<Point<T>>,
+    pub interiors: Vec<Vec<Point<T>>>,
+}
+
+impl<T: InputType> Polygon<T> {
+    /// Create a new Polygon
+    pub fn new(exterior: Vec<Point<T>>, interiors: Vec<Vec<Point<T>>>) -> Self {
+        Self {
+            exterior,
+            interiors,
+        }
+    }
+
+    /// Iterates over all the rings, the exterior ring comes first.
+    pub fn rings(&self) -> impl Iterator<Item = &Vec<Point<T>>> {
+        std::iter::once(&self.exterior).chain(self.interiors.iter())
+    }
+}
+
+impl<T: InputType, IT: Copy + Into<Point<T>>> From<Vec<IT>> for Polygon<T> {
+    #[inline]
+    /// Converts to `boostvoronoi::geometry::Polygon` (without holes) from `Vec<Into<Point<T>>>`
+    /// ```
+    /// # use boostvoronoi_core::geometry::*;
+    /// let p = Polygon::<i32>::from(vec![[0, 0], [10, 0], [10, 10]]);
+    /// assert_eq!(p.exterior.len(), 3);
+    /// assert!(p.interiors.is_empty());
+    /// ```
+    fn from(exterior: Vec<IT>) -> Self {
+        Self {
+            exterior: exterior.into_iter().map(|p| p.into()).collect(),
+            interiors: Vec::new(),
+        }
+    }
+}
+
+#[cfg(feature = "geo")]
+impl<T: InputType + geo::CoordNum> From<&geo::Polygon<T>> for Polygon<T> {
+    #[inline]
+    /// Converts to `boostvoronoi::geometry::Polygon` from `&geo::Polygon`
+    /// ```
+    /// # use boostvoronoi_core::geometry::*;
+    /// let gp = geo::Polygon::new(
+    ///     geo::LineString::from(vec![(0, 0), (10, 0), (10, 10), (0, 0)]),
+    ///     vec![],
+    /// );
+    /// let bp = Polygon::<i32>::from(&gp);
+    /// assert_eq!(bp.exterior.len(), 4);
+    /// assert_eq!(bp.exterior[1], Point::new(10, 0));
+    /// ```
+    fn from(polygon: &geo::Polygon<T>) -> Self {
+        let ring = |r: &geo::LineString<T>| r.coords().map(Point::from).collect();
+        Self {
+            exterior: ring(polygon.exterior()),
+            interiors: polygon.interiors().iter().map(ring).collect(),
+        }
+    }
+}
+
+#[cfg(feature = "geo")]
+impl<T: InputType + geo::CoordNum> From<geo::Polygon<T>> for Polygon<T> {
+    #[inline]
+    /// Converts to `boostvoronoi::geometry::Polygon` from `geo::Polygon`
+    fn from(polygon: geo::Polygon<T>) -> Self {
+        Self::from(&polygon)
+    }
+}
@@ -0,0 +1,380 @@
+//          Copyright Eadf (github.com/eadf) 2021.
+// Distributed under the Boost Software License, Version 1.0.
+//    (See accompanying file LICENSE_1_0.txt or copy at
+//          http://www.boost.org/LICENSE_1_0.txt)
+
+//! Classifies the cells, edges and vertices of a diagram built from closed rings as interior or
+//! exterior of the material.
+//!
+//! Voronoi edges never cross an input segment, they only touch the segments at vertices that
+//! coincide with a segment end point. So all the vertices connected by edges, not counting the
+//! vertices on the input sites, are on the same side of the boundary. The winding number is
+//! computed once for each such group of vertices, which handles holes and nested rings no matter
+//! if they are connected to the infinite edges or not.
+
+use crate::attributes::{CellAttributes, EdgeAttributes, VertexAttributes};
+use crate::clipping::infinite_edge_line;
+use crate::diagram::{self as VD, ColorType, SiteGeometry, SourceCategory};
+use crate::geometry::{Line, Point, Polygon};
+use crate::medial_axis::PolygonSegments;
+use crate::parabola::ParabolicArc;
+use crate::{cast, BvError, InputType, OutputType};
+
+/// Decides what is inside the rings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FillRule {
+    /// A point is inside if a ray from it crosses the rings an odd number of times.
+    /// The orientation of the rings does not matter.
+    EvenOdd,
+    /// A point is inside if the rings wind around it a non-zero number of times.
+    /// Counter clockwise rings add material, clockwise rings remove it.
+    NonZero,
+}
+
+/// Where a diagram element is, relative to the material
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Location {
+    Interior,
+    Exterior,
+    /// Vertices on an input site, cells of segments (they reach both sides of the segment) and
+    /// cells of ring corners where the sides meet at a straight angle.
+    Boundary,
+}
+
+/// The winding number test against the directed input segments
+struct Winding<F: OutputType> {
+    lines_: Vec<[[F; 2]; 2]>,
+    rule_: FillRule,
+}
+
+impl<F: OutputType> Winding<F> {
+    fn new<I: InputType>(segments: &[Line<I>], rule: FillRule) -> Self {
+        let to_f = |p: &Point<I>| [cast::<I, F>(p.x), cast::<I, F>(p.y)];
+        Self {
+            lines_: segments
+                .iter()
+                .map(|l| [to_f(&l.start), to_f(&l.end)])
+                .collect(),
+            rule_: rule,
+        }
+    }
+
+    fn location(&self, p: [F; 2]) -> Location {
+        let mut winding = 0_i64;
+        let mut crossings = 0_usize;
+        for [a, b] in self.lines_.iter() {
+            let side = (b[0] - a[0]) * (p[1] - a[1]) - (b[1] - a[1]) * (p[0] - a[0]);
+            if a[1] <= p[1] {
+                if b[1] > p[1] && side > F::zero() {
+                    winding += 1;
+                    crossings += 1;
+                }
+            } else if b[1] <= p[1] && side < F::zero() {
+                winding -= 1;
+                crossings += 1;
+            }
+        }
+        let inside = match self.rule_ {
+            FillRule::EvenOdd => crossings % 2 == 1,
+            FillRule::NonZero => winding != 0,
+        };
+        if inside {
+            Location::Interior
+        } else {
+            Location::Exterior
+        }
+    }
+}
+
+/// The interior/exterior classification of every cell, edge and vertex of a diagram.
+/// ```
+/// # use boostvoronoi_core::builder::Builder;
+/// # use boostvoronoi_core::geometry::Polygon;
+/// # use boostvoronoi_core::inside_outside::{FillRule, InsideOutside, Location};
+/// # use boostvoronoi_core::BvError;
+/// // a square with a square hole
+/// let polygons = [Polygon::<i32>::new(
+///     vec![[0, 0].into(), [30, 0].into(), [30, 30].into(), [0, 30].into()],
+///     vec![vec![[10, 10].into(), [20, 10].into(), [20, 20].into(), [10, 20].into()]],
+/// )];
+/// let diagram = Builder::<i32, f64>::default()
+///     .with_polygons(polygons.iter().cloned())?
+///     .build()?;
+/// let classification = InsideOutside::from_polygons(&diagram, &polygons, FillRule::EvenOdd)?;
+/// for vertex in diagram.vertices().iter() {
+///     let vertex = vertex.get();
+///     let location = classification.vertex_location(vertex.get_id())?;
+///     if vertex.x() > 10.0 && vertex.x() < 20.0 && vertex.y() > 10.0 && vertex.y() < 20.0 {
+///         // the hole is not connected to the infinite edges, but still exterior
+///         assert_eq!(location, Location::Exterior);
+///     }
+/// }
+/// # Ok::<(), BvError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct InsideOutside {
+    cells_: CellAttributes<Location>,
+    edges_: EdgeAttributes<Location>,
+    vertices_: VertexAttributes<Location>,
+}
+
+impl InsideOutside {
+    /// Classifies the diagram built from `points` and `segments`. The segments must form closed
+    /// rings, the points may be anywhere but not on a segment.
+    /// `points` and `segments` must be the input geometry of the `Builder`, in the same order.
+    /// # Errors
+    /// Will return `BvError::IdError` if a cell refers to geometry missing from the input.
+    pub fn new<I: InputType, F: OutputType>(
+        diagram: &VD::Diagram<F>,
+        points: &[Point<I>],
+        segments: &[Line<I>],
+        rule: FillRule,
+    ) -> Result<Self, BvError> {
+        Self::classify_(diagram, points, segments, Winding::new(segments, rule))
+    }
+
+    /// Classifies the diagram built by `Builder::with_polygons()` from `polygons` only.
+    /// With `FillRule::NonZero` the exterior rings count as counter clockwise and the holes as
+    /// clockwise, regardless of how they are stored.
+    /// # Errors
+    /// Will return `BvError::IdError` if the diagram has sites that are not part of the polygons.
+    pub fn from_polygons<I: InputType, F: OutputType>(
+        diagram: &VD::Diagram<F>,
+        polygons: &[Polygon<I>],
+        rule: FillRule,
+    ) -> Result<Self, BvError> {
+        let input = PolygonSegments::<I, F>::new(polygons);
+        let oriented: Vec<Line<I>> = input
+            .segments()
+            .iter()
+            .enumerate()
+            .map(|(i, line)| {
+                // the inside is to the left of a counter clockwise ring
+                let to_f = |p: &Point<I>| [cast::<I, F>(p.x), cast::<I, F>(p.y)];
+                let (a, b) = (to_f(&line.start), to_f(&line.end));
+                let left = [b[0] + a[1] - b[1], b[1] + b[0] - a[0]];
+                if input.is_inner_side(line, i, left) {
+                    *line
+                } else {
+                    Line::new(line.end, line.start)
+                }
+            })
+            .collect();
+        Self::classify_(
+            diagram,
+            &[],
+            input.segments(),
+            Winding::new(&oriented, rule),
+        )
+    }
+
+    fn classify_<I: InputType, F: OutputType>(
+        diagram: &VD::Diagram<F>,
+        points: &[Point<I>],
+        segments: &[Line<I>],
+        winding: Winding<F>,
+    ) -> Result<Self, BvError> {
+        let position = |v: VD::VertexIndex| -> Result<[F; 2], BvError> {
+            let v = diagram.vertex_get(v)?.get();
+            Ok([v.x(), v.y()])
+        };
+        let is_site = |v: Option<VD::VertexIndex>| -> Result<bool, BvError> {
+            Ok(match v {
+                Some(v) => diagram.vertex_get(v)?.get().is_site_point(),
+                None => true,
+            })
+        };
+
+        // group the vertices that are connected without passing through a site
+        let mut parent: Vec<usize> = (0..diagram.vertices().len()).collect();
+        fn find(parent: &mut [usize], mut v: usize) -> usize {
+            while parent[v] != v {
+                parent[v] = parent[parent[v]];
+                v = parent[v];
+            }
+            v
+        }
+        for edge in diagram.edges().iter() {
+            let edge = edge.get();
+            let (v0, v1) = (edge.vertex0(), diagram.edge_get_vertex1(edge.id())?);
+            if let (Some(a), Some(b)) = (v0, v1) {
+                if !is_site(v0)? && !is_site(v1)? {
+                    let (a, b) = (find(&mut parent, a.0), find(&mut parent, b.0));
+                    parent[a] = b;
+                }
+            }
+        }
+        let mut group_location: Vec<Option<Location>> = vec![None; parent.len()];
+        let mut vertices = VertexAttributes::new();
+        for vertex in diagram.vertices().iter() {
+            let vertex_id = vertex.get().get_id();
+            let location = if is_site(Some(vertex_id))? {
+                Location::Boundary
+            } else {
+                let group = find(&mut parent, vertex_id.0);
+                match group_location[group] {
+                    Some(location) => location,
+                    None => {
+                        let location = winding.location(position(vertex_id)?);
+                        group_location[group] = Some(location);
+                        location
+                    }
+                }
+            };
+            let _ = vertices.insert(vertex_id, location);
+        }
+
+        // the extent of the input, to step away from the sites along infinite edges
+        let extent = points
+            .iter()
+            .copied()
+            .chain(segments.iter().flat_map(|l| [l.start, l.end]))
+            .map(|p| cast::<I, F>(p.x).abs().max(cast::<I, F>(p.y).abs()))
+            .fold(F::one(), F::max);
+        let mut edges = EdgeAttributes::new();
+        for edge in diagram.edges().iter() {
+            let edge = edge.get();
+            let edge_id = edge.id();
+            let (v0, v1) = (edge.vertex0(), diagram.edge_get_vertex1(edge_id)?);
+            let known = [v0, v1]
+                .into_iter()
+                .flatten()
+                .filter_map(|v| vertices.get(v).copied())
+                .find(|l| *l != Location::Boundary);
+            let location = match known {
+                Some(location) => location,
+                // the edge only touches sites, test a point in the middle of it
+                None => winding.location(match (v0, v1) {
+                    (Some(a), Some(b)) => {
+                        match ParabolicArc::from_edge(diagram, edge_id, points, segments)? {
+                            Some(arc) => arc.to_bezier().point_at(cast::<f64, F>(0.5)),
+                            None => {
+                                let (a, b) = (position(a)?, position(b)?);
+                                let half = cast::<f64, F>(0.5);
+                                [(a[0] + b[0]) * half, (a[1] + b[1]) * half]
+                            }
+                        }
+                    }
+                    (v0, v1) => {
+                        let (origin, direction) =
+                            infinite_edge_line(diagram, edge_id, points, segments)?;
+                        let step = extent / direction[0].hypot(direction[1]);
+                        match (v0, v1) {
+                            (Some(a), _) => {
+                                let a = position(a)?;
+                                [a[0] + direction[0] * step, a[1] + direction[1] * step]
+                            }
+                            (_, Some(b)) => {
+                                let b = position(b)?;
+                                [b[0] - direction[0] * step, b[1] - direction[1] * step]
+                            }
+                            _ => origin,
+                        }
+                    }
+                }),
+            };
+            let _ = edges.insert(edge_id, location);
+        }
+
+        let mut cells = CellAttributes::new();
+        for cell in diagram.cells().iter() {
+            let cell = cell.get();
+            let location = match SiteGeometry::of_cell(&cell, points, segments)? {
+                SiteGeometry::Segment(_) => Location::Boundary,
+                SiteGeometry::Point(p) if cell.source_category() == SourceCategory::SinglePoint => {
+                    winding.location([cast::<I, F>(p.x), cast::<I, F>(p.y)])
+                }
+                // the cell of a segment end point is on one side, unless the segments meet at
+                // a straight angle
+                SiteGeometry::Point(_) => {
+                    let mut location = None;
+                    for edge_id in diagram.cell_edge_iterator(cell.id()) {
+                        let edge_location = edges.get(edge_id).copied();
+                        if location.is_none() {
+                            location = edge_location;
+                        } else if location != edge_location {
+                            location = Some(Location::Boundary);
+                        }
+                    }
+                    location.unwrap_or(Location::Boundary)
+                }
+            };
+            let _ = cells.insert(cell.id(), location);
+        }
+        Ok(Self {
+            cells_: cells,
+            edges_: edges,
+            vertices_: vertices,
+        })
+    }
+
+    /// Returns the location of every cell
+    #[inline]
+    pub fn cells(&self) -> &CellAttributes<Location> {
+        &self.cells_
+    }
+
+    /// Returns the location of every edge
+    #[inline]
+    pub fn edges(&self) -> &EdgeAttributes<Location> {
+        &self.edges_
+    }
+
+    /// Returns the location of every vertex
+    #[inline]
+    pub fn vertices(&self) -> &VertexAttributes<Location> {
+        &self.vertices_
+    }
+
+    /// Returns the location of a cell
+    pub fn cell_location(&self, cell_id: VD::CellIndex) -> Result<Location, BvError> {
+        self.cells_.get(cell_id).copied().ok_or_else(|| {
+            BvError::IdError(format!("The cell with id:{} does not exist", cell_id.0))
+        })
+    }
+
+    /// Returns the location of an edge
+    pub fn edge_location(&self, edge_id: VD::EdgeIndex) -> Result<Location, BvError> {
+        self.edges_.get(edge_id).copied().ok_or_else(|| {
+            BvError::IdError(format!("The edge with id:{} does not exist", edge_id.0))
+        })
+    }
+
+    /// Returns the location of a vertex
+    pub fn vertex_location(&self, vertex_id: VD::VertexIndex) -> Result<Location, BvError> {
+        self.vertices_.get(vertex_id).copied().ok_or_else(|| {
+            BvError::IdError(format!("The vertex with id:{} does not exist", vertex_id.0))
+        })
+    }
+
+    /// ORs `interior_color` into the color of every interior cell, edge and vertex of the
+    /// diagram, and `exterior_color` into every exterior one. Boundary elements are not colored.
+    pub fn color_diagram<F: OutputType>(
+        &self,
+        diagram: &VD::Diagram<F>,
+        interior_color: ColorType,
+        exterior_color: ColorType,
+    ) -> Result<(), BvError> {
+        let color = |location: &Location| match location {
+            Location::Interior => Some(interior_color),
+            Location::Exterior => Some(exterior_color),
+            Location::Boundary => None,
+        };
+        for (cell_id, location) in self.cells_.iter() {
+            if let Some(color) = color(location) {
+                diagram.cell_or_color(cell_id, color)?;
+            }
+        }
+        for (edge_id, location) in self.edges_.iter() {
+            if let Some(color) = color(location) {
+                diagram.edge_or_color(edge_id, color)?;
+            }
+        }
+        for (vertex_id, location) in self.vertices_.iter() {
+            if let Some(color) = color(location) {
+                diagram.vertex_try_or_color(vertex_id, color)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -31,19 +31,35 @@
 use num_traits::{Float, NumCast, PrimInt, Signed, Zero};
 use std::fmt;
 use std::hash::Hash;
+pub mod attributes;
 mod beach_line;
 pub mod builder;
 mod circle_event;
+pub mod clipping;
 mod ctypes;
+pub mod delaunay;
 pub mod diagram;
+pub mod empty_circle;
 mod end_point;
 
 pub mod file_reader;
+pub mod float_builder;
 pub mod geometry;
+pub mod inside_outside;
+pub mod lloyd;
+pub mod measure;
+pub mod medial_axis;
+pub mod offset;
+pub mod parabola;
+pub mod path_planning;
+pub mod point_location;
 pub(crate) mod predicate;
 pub(crate) mod robust_sqrt_expr;
 mod site_event;
+pub mod snap_rounding;
 pub mod sync_diagram;
+pub mod trace;
+pub mod validation;
 pub mod visual_utils;
 
 /// A feature gated print(), will only be active when the feature "console_debug" is selected.
@@ -80,12 +96,16 @@ pub enum BvError {
     BeachLineError(String),
     #[error("error: given value for the radius is less than 0.0.")]
     RadiusLessThanZero,
+    /// Not returned any more, vertices and segments may be inserted in any order.
+    #[deprecated(note = "vertices and segments may be inserted in any order")]
     #[error("error: vertices should be added before segments")]
     VerticesGoesFirst(String),
     #[error("error: Some error")]
     InternalError(String),
     #[error("Suspected self-intersecting input data")]
     SelfIntersecting(String),
+    #[error("The build was cancelled")]
+    Cancelled,
     #[error("Could not cast number")]
     NumberConversion(String),
     #[error(transparent)]
@@ -154,7 +174,7 @@ impl<T: PrimInt + fmt::Debug> GrowingVob for vob::Vob<T> {
     #[inline]
     fn set_grow(&mut self, bit: usize, state: bool) {
         if bit >= self.len() {
-            self.resize(bit + std::mem::size_of::<T>(), false);
+            self.resize(bit + size_of::<T>(), false);
         }
         let _ = self.set(bit, state);
     }
@@ -0,0 +1,208 @@
+//          Copyright Eadf (github.com/eadf) 2021.
+// Distributed under the Boost Software License, Version 1.0.
+//    (See accompanying file LICENSE_1_0.txt or copy at
+//          http://www.boost.org/LICENSE_1_0.txt)
+
+//! Lloyd relaxation, moves a set of points towards a centroidal voronoi tessellation.
+//!
+//! Every iteration builds the voronoi diagram of the points, clips the cells to a convex region
+//! and moves each point to the centroid of its clipped cell. The centroids are rounded back to
+//! the integer input type, a point that would land on top of another point stays where it was.
+
+use crate::builder::Builder;
+use crate::clipping::{ConvexRegion, DiagramClipper};
+use crate::geometry::Point;
+use crate::{cast, try_cast, BvError, InputType, OutputType};
+use std::marker::PhantomData;
+
+/// The centroid of every clipped cell, indexed by the source index
+type Centroids<F> = Vec<Option<[F; 2]>>;
+
+/// Statistics of one relaxation iteration, measured on the diagram the points were moved by.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LloydStatistics<F: OutputType> {
+    /// The quantization energy `Σ ∫∫|p - site|² dA` of the clipped cells, with the sites as
+    /// they were before the move
+    pub energy: F,
+    /// The largest distance between a site and the centroid of its cell
+    pub max_displacement: F,
+    /// The number of points that moved after the integer rounding
+    pub moved: usize,
+}
+
+/// The outcome of `LloydRelaxation::relax()`
+#[derive(Debug, Clone)]
+pub struct LloydResult<I: InputType, F: OutputType> {
+    /// The relaxed points, in the same order as the input
+    pub points: Vec<Point<I>>,
+    /// The statistics of every iteration that was run
+    pub statistics: Vec<LloydStatistics<F>>,
+    /// True if the iterations stopped because the displacement was within the tolerance, or
+    /// because the rounding left every point in place
+    pub converged: bool,
+}
+
+/// Runs Lloyd relaxation of points inside a convex region.
+/// ```
+/// # use boostvoronoi_core::clipping::ConvexRegion;
+/// # use boostvoronoi_core::geometry::Point;
+/// # use boostvoronoi_core::lloyd::LloydRelaxation;
+/// # use boostvoronoi_core::BvError;
+/// let region = ConvexRegion::new(vec![[0.0, 0.0], [100.0, 0.0], [100.0, 100.0], [0.0, 100.0]])?;
+/// let points: Vec<Point<i32>> = vec![[10, 10].into(), [12, 10].into(), [11, 14].into()];
+/// let result = LloydRelaxation::<i32, f64>::new(region)
+///     .with_max_iterations(50)
+///     .relax(points.iter())?;
+/// assert_eq!(result.points.len(), 3);
+/// let energy: Vec<f64> = result.statistics.iter().map(|s| s.energy).collect();
+/// assert!(energy.last() < energy.first());
+/// # Ok::<(), BvError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct LloydRelaxation<I: InputType, F: OutputType> {
+    region_: ConvexRegion<F>,
+    max_iterations_: usize,
+    tolerance_: F,
+    pd_: PhantomData<I>,
+}
+
+impl<I: InputType, F: OutputType> LloydRelaxation<I, F> {
+    /// Creates a relaxation inside `region`, running at most 100 iterations with a tolerance
+    /// of half an integer unit.
+    pub fn new(region: ConvexRegion<F>) -> Self {
+        Self {
+            region_: region,
+            max_iterations_: 100,
+            tolerance_: cast::<f64, F>(0.5),
+            pd_: PhantomData,
+        }
+    }
+
+    /// Sets the maximum number of iterations
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations_ = max_iterations;
+        self
+    }
+
+    /// The iterations stop when no site is further than `tolerance` from its centroid
+    pub fn with_tolerance(mut self, tolerance: F) -> Self {
+        self.tolerance_ = tolerance;
+        self
+    }
+
+    /// Relaxes the points and returns them together with the per-iteration statistics.
+    /// Points with a cell outside of the region are not moved.
+    /// # Errors
+    /// Will return `BvError::ValueError` if the input contains duplicate points.
+    /// Will return `BvError::NumberConversion` if a centroid can't be represented by `I`.
+    pub fn relax<T, IT>(&self, points: T) -> Result<LloydResult<I, F>, BvError>
+    where
+        T: IntoIterator<Item = IT>,
+        IT: Copy + Into<Point<I>>,
+    {
+        let mut points: Vec<Point<I>> = points.into_iter().map(|p| p.into()).collect();
+        {
+            let mut seen = ahash::AHashSet::with_capacity(points.len());
+            if let Some(p) = points.iter().find(|p| !seen.insert(**p)) {
+                return Err(BvError::ValueError(format!(
+                    "Lloyd relaxation requires unique points, {:?} is duplicated",
+                    p
+                )));
+            }
+        }
+        let mut statistics = Vec::new();
+        let mut converged = false;
+        while statistics.len() < self.max_iterations_ && !points.is_empty() {
+            let (centroids, iteration) = self.measure_(&points)?;
+            let mut moved = points.clone();
+            for (p, c) in moved.iter_mut().zip(centroids.iter()) {
+                if let Some(c) = c {
+                    *p = Point {
+                        x: try_cast::<F, I>(c[0].round())?,
+                        y: try_cast::<F, I>(c[1].round())?,
+                    };
+                }
+            }
+            Self::resolve_collisions_(&points, &mut moved);
+            let moved_count = points
+                .iter()
+                .zip(moved.iter())
+                .filter(|(a, b)| a != b)
+                .count();
+            points = moved;
+            statistics.push(LloydStatistics {
+                moved: moved_count,
+                ..iteration
+            });
+            if iteration.max_displacement <= self.tolerance_ || moved_count == 0 {
+                converged = true;
+                break;
+            }
+        }
+        Ok(LloydResult {
+            points,
+            statistics,
+            converged,
+        })
+    }
+
+    /// Returns the centroid of every point's clipped cell, indexed like `points`, and the
+    /// energy statistics of the configuration.
+    fn measure_(&self, points: &[Point<I>]) -> Result<(Centroids<F>, LloydStatistics<F>), BvError> {
+        let diagram = Builder::<I, F>::default()
+            .with_vertices(points.iter())?
+            .build()?;
+        let clipper = DiagramClipper::new(&diagram, points, &[], self.region_.clone());
+        let mut centroids = vec![None; points.len()];
+        let mut statistics = LloydStatistics {
+            energy: F::zero(),
+            max_displacement: F::zero(),
+            moved: 0,
+        };
+        for (cell, measure) in diagram.cells().iter().zip(clipper.cell_measures()?) {
+            let source_index = cell.get().source_index();
+            let (site, measure) = match (points.get(source_index), measure) {
+                (Some(site), Some(measure)) => (site, measure),
+                _ => continue,
+            };
+            let dx = measure.centroid[0] - cast::<I, F>(site.x);
+            let dy = measure.centroid[1] - cast::<I, F>(site.y);
+            // the parallel axis theorem moves the moment of inertia to the site
+            statistics.energy =
+                statistics.energy + measure.inertia + measure.area * (dx * dx + dy * dy);
+            statistics.max_displacement = statistics.max_displacement.max(dx.hypot(dy));
+            centroids[source_index] = Some(measure.centroid);
+        }
+        Ok((centroids, statistics))
+    }
+
+    /// Moves points that were rounded onto the same position back to where they were, until all
+    /// positions are unique. The old positions are unique so this always terminates.
+    fn resolve_collisions_(old: &[Point<I>], new: &mut [Point<I>]) {
+        loop {
+            let mut owner = ahash::AHashMap::<Point<I>, usize>::with_capacity(new.len());
+            let mut reverted = false;
+            for i in 0..new.len() {
+                match owner.get(&new[i]).copied() {
+                    None => {
+                        let _ = owner.insert(new[i], i);
+                    }
+                    Some(j) => {
+                        // a point that has not moved keeps its position
+                        let i = if new[j] == old[j] {
+                            i
+                        } else {
+                            let _ = owner.insert(new[i], i);
+                            j
+                        };
+                        new[i] = old[i];
+                        reverted = true;
+                    }
+                }
+            }
+            if !reverted {
+                return;
+            }
+        }
+    }
+}
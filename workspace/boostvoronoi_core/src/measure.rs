@@ -0,0 +1,341 @@
+//          Copyright Eadf (github.com/eadf) 2021.
+// Distributed under the Boost Software License, Version 1.0.
+//    (See accompanying file LICENSE_1_0.txt or copy at
+//          http://www.boost.org/LICENSE_1_0.txt)
+
+//! Area, perimeter and centroid of the cells.
+//!
+//! The boundary of a cell is made of lines and parabolic arcs, both are quadratic Bézier curves
+//! (a line has its middle control point half way). The boundary is clipped exactly against the
+//! half planes of a convex region, by splitting the curves where they cross the region edges.
+//! Area, centroid and moment of inertia are integrated with Green's theorem, the integrands are
+//! polynomials of at most seventh degree in the curve parameter so a four point Gauss-Legendre
+//! quadrature is exact.
+//! The length of a parabolic arc has a closed form.
+
+use crate::{cast, OutputType};
+
+/// The area, perimeter and centroid of a cell, clipped to the region of a
+/// [`crate::clipping::DiagramClipper`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CellMeasure<F: OutputType> {
+    pub area: F,
+    pub perimeter: F,
+    pub centroid: [F; 2],
+    /// The polar moment of inertia around the centroid, `∫∫|p - centroid|² dA`
+    pub inertia: F,
+}
+
+/// A piece of a cell boundary, a quadratic Bézier curve
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct BoundaryPiece<F: OutputType> {
+    control_points_: [[F; 2]; 3],
+    is_curved_: bool,
+}
+
+impl<F: OutputType> BoundaryPiece<F> {
+    /// A straight line from `start` to `end`
+    pub(crate) fn line(start: [F; 2], end: [F; 2]) -> Self {
+        let half = cast::<f64, F>(0.5);
+        Self {
+            control_points_: [
+                start,
+                [(start[0] + end[0]) * half, (start[1] + end[1]) * half],
+                end,
+            ],
+            is_curved_: false,
+        }
+    }
+
+    /// A parabolic arc, as a (non rational) quadratic Bézier curve
+    pub(crate) fn curve(control_points: [[F; 2]; 3]) -> Self {
+        Self {
+            control_points_: control_points,
+            is_curved_: true,
+        }
+    }
+
+    #[inline]
+    fn start(&self) -> [F; 2] {
+        self.control_points_[0]
+    }
+
+    #[inline]
+    fn end(&self) -> [F; 2] {
+        self.control_points_[2]
+    }
+
+    /// The blossom of the curve, `blossom(t, t)` is the point at `t`
+    fn blossom(&self, u: F, v: F) -> [F; 2] {
+        let [p0, p1, p2] = self.control_points_;
+        let w = [
+            (F::one() - u) * (F::one() - v),
+            (F::one() - u) * v + u * (F::one() - v),
+            u * v,
+        ];
+        [
+            w[0] * p0[0] + w[1] * p1[0] + w[2] * p2[0],
+            w[0] * p0[1] + w[1] * p1[1] + w[2] * p2[1],
+        ]
+    }
+
+    /// The part of the curve between the parameters `u` and `v`, starting at `start` and ending
+    /// at `end` (the points at `u` and `v`, computed once so that neighbours share them).
+    fn sub_piece(&self, u: F, v: F, start: [F; 2], end: [F; 2]) -> Self {
+        if self.is_curved_ {
+            Self::curve([start, self.blossom(u, v), end])
+        } else {
+            Self::line(start, end)
+        }
+    }
+
+    /// The signed side of the curve relative to the line `a`-`b` at the three control points,
+    /// the side is quadratic in the curve parameter with these Bernstein coefficients.
+    fn sides(&self, a: [F; 2], b: [F; 2]) -> [F; 3] {
+        let side = |p: [F; 2]| (b[0] - a[0]) * (p[1] - a[1]) - (b[1] - a[1]) * (p[0] - a[0]);
+        [
+            side(self.control_points_[0]),
+            side(self.control_points_[1]),
+            side(self.control_points_[2]),
+        ]
+    }
+
+    /// Returns the parameters in `(0, 1)` where the curve crosses the line `a`-`b`, in
+    /// increasing order.
+    fn crossings(&self, a: [F; 2], b: [F; 2]) -> Vec<F> {
+        let [s0, s1, s2] = self.sides(a, b);
+        // s(t) = s0 + 2(s1 - s0)t + (s0 - 2s1 + s2)t²
+        let qa = s0 - s1 - s1 + s2;
+        let qb = (s1 - s0) * cast::<f64, F>(2.0);
+        let mut roots = Vec::with_capacity(2);
+        let scale = s0.abs().max(s1.abs()).max(s2.abs());
+        if qa.abs() <= scale * cast::<f64, F>(1e-12) {
+            if qb != F::zero() {
+                roots.push(-s0 / qb);
+            }
+        } else {
+            let discriminant = qb * qb - cast::<f64, F>(4.0) * qa * s0;
+            if discriminant > F::zero() {
+                let root = discriminant.sqrt();
+                // the numerically stable form of the quadratic formula
+                let q = if qb < F::zero() {
+                    -(qb - root)
+                } else {
+                    -(qb + root)
+                } * cast::<f64, F>(0.5);
+                roots.push(q / qa);
+                if q != F::zero() {
+                    roots.push(s0 / q);
+                }
+            }
+        }
+        roots.retain(|t| *t > F::zero() && *t < F::one());
+        roots.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        roots.dedup();
+        roots
+    }
+
+    /// `[∮(x dy - y dx), ∮x² dy, ∮y² dx, ∮x³ dy, ∮y³ dx]` along the curve, relative to `origin`
+    fn integrals(&self, origin: [F; 2]) -> [F; 5] {
+        let p = self
+            .control_points_
+            .map(|p| [p[0] - origin[0], p[1] - origin[1]]);
+        let two = cast::<f64, F>(2.0);
+        // the four point Gauss-Legendre nodes and weights, moved to [0, 1]
+        let inner = (3.0 / 7.0 - 2.0 / 7.0 * (1.2_f64).sqrt()).sqrt() * 0.5;
+        let outer = (3.0 / 7.0 + 2.0 / 7.0 * (1.2_f64).sqrt()).sqrt() * 0.5;
+        let inner_weight = (18.0 + 30.0_f64.sqrt()) / 72.0;
+        let outer_weight = (18.0 - 30.0_f64.sqrt()) / 72.0;
+        let nodes = [0.5 - outer, 0.5 - inner, 0.5 + inner, 0.5 + outer].map(cast::<f64, F>);
+        let weights = [outer_weight, inner_weight, inner_weight, outer_weight].map(cast::<f64, F>);
+        let mut rv = [F::zero(); 5];
+        for (t, w) in nodes.into_iter().zip(weights) {
+            let s = F::one() - t;
+            let [x, y] = [
+                s * s * p[0][0] + (s + s) * t * p[1][0] + t * t * p[2][0],
+                s * s * p[0][1] + (s + s) * t * p[1][1] + t * t * p[2][1],
+            ];
+            let [dx, dy] = [
+                (s * (p[1][0] - p[0][0]) + t * (p[2][0] - p[1][0])) * two,
+                (s * (p[1][1] - p[0][1]) + t * (p[2][1] - p[1][1])) * two,
+            ];
+            rv[0] = rv[0] + w * (x * dy - y * dx);
+            rv[1] = rv[1] + w * x * x * dy;
+            rv[2] = rv[2] + w * y * y * dx;
+            rv[3] = rv[3] + w * x * x * x * dy;
+            rv[4] = rv[4] + w * y * y * y * dx;
+        }
+        rv
+    }
+
+    /// The length of the curve
+    pub(crate) fn length(&self) -> F {
+        let [p0, p1, p2] = self.control_points_;
+        let straight = (p2[0] - p0[0]).hypot(p2[1] - p0[1]);
+        if !self.is_curved_ {
+            return straight;
+        }
+        let two = cast::<f64, F>(2.0);
+        let four = two + two;
+        // B'(t) = 2at + b
+        let a = [p0[0] - p1[0] - p1[0] + p2[0], p0[1] - p1[1] - p1[1] + p2[1]];
+        let b = [(p1[0] - p0[0]) * two, (p1[1] - p0[1]) * two];
+        // |B'(t)| = sqrt(qa·t² + qb·t + qc)
+        let qa = four * (a[0] * a[0] + a[1] * a[1]);
+        let qb = four * (a[0] * b[0] + a[1] * b[1]);
+        let qc = b[0] * b[0] + b[1] * b[1];
+        let cross = a[0] * b[1] - a[1] * b[0];
+        if qa <= qc * cast::<f64, F>(1e-12) || cross.abs() <= qc * cast::<f64, F>(1e-12) {
+            // the control points are (almost) on a line
+            return straight;
+        }
+        let sqrt_a = qa.sqrt();
+        let antiderivative = |t: F| {
+            let s = (qa * t * t + qb * t + qc).sqrt();
+            let u = two * qa * t + qb;
+            u * s / (four * qa)
+                + (four * qa * qc - qb * qb) / (four * two * qa * sqrt_a)
+                    * (two * sqrt_a * s + u).ln()
+        };
+        antiderivative(F::one()) - antiderivative(F::zero())
+    }
+}
+
+/// Clips closed loops of boundary pieces, with the area to the left, against the half plane to
+/// the left of the line `a`-`b`.
+fn clip_loops<F: OutputType>(
+    loops: Vec<Vec<BoundaryPiece<F>>>,
+    a: [F; 2],
+    b: [F; 2],
+) -> Vec<Vec<BoundaryPiece<F>>> {
+    let mut output = Vec::<Vec<BoundaryPiece<F>>>::new();
+    // the runs of pieces inside the half plane, of the loops that cross the line
+    let mut chains = Vec::<Vec<BoundaryPiece<F>>>::new();
+    let half = cast::<f64, F>(0.5);
+    for pieces in loops {
+        // split the pieces where they cross the line and classify the parts by their middle
+        let mut parts = Vec::<(BoundaryPiece<F>, bool)>::with_capacity(pieces.len());
+        for piece in pieces.iter() {
+            let mut t = F::zero();
+            let mut start = piece.start();
+            let crossings = piece.crossings(a, b);
+            for v in crossings.into_iter().chain(std::iter::once(F::one())) {
+                let end = if v == F::one() {
+                    piece.end()
+                } else {
+                    piece.blossom(v, v)
+                };
+                let part = piece.sub_piece(t, v, start, end);
+                let middle = piece.blossom((t + v) * half, (t + v) * half);
+                let side = (b[0] - a[0]) * (middle[1] - a[1]) - (b[1] - a[1]) * (middle[0] - a[0]);
+                parts.push((part, side >= F::zero()));
+                t = v;
+                start = end;
+            }
+        }
+        if parts.iter().all(|(_, inside)| *inside) {
+            output.push(pieces);
+            continue;
+        }
+        // start at the beginning of a run of inside parts
+        let Some(first) =
+            (0..parts.len()).find(|i| parts[*i].1 && !parts[(i + parts.len() - 1) % parts.len()].1)
+        else {
+            continue;
+        };
+        parts.rotate_left(first);
+        let mut chain = Vec::<BoundaryPiece<F>>::new();
+        for (part, inside) in parts {
+            if inside {
+                chain.push(part);
+            } else if !chain.is_empty() {
+                chains.push(std::mem::take(&mut chain));
+            }
+        }
+        if !chain.is_empty() {
+            chains.push(chain);
+        }
+    }
+    if chains.is_empty() {
+        return output;
+    }
+    // The chains leave the half plane at their end (exit) and come back at their start (entry).
+    // Along the direction of the line, the boundary continues from each exit to the next entry.
+    let d = [b[0] - a[0], b[1] - a[1]];
+    let along = |p: [F; 2]| (p[0] - a[0]) * d[0] + (p[1] - a[1]) * d[1];
+    let sorted = |point: &dyn Fn(&Vec<BoundaryPiece<F>>) -> [F; 2]| {
+        let mut order: Vec<usize> = (0..chains.len()).collect();
+        order.sort_by(|i, j| {
+            along(point(&chains[*i]))
+                .partial_cmp(&along(point(&chains[*j])))
+                .unwrap()
+        });
+        order
+    };
+    let exits = sorted(&|c| c[c.len() - 1].end());
+    let entries = sorted(&|c| c[0].start());
+    let mut next = vec![0_usize; chains.len()];
+    for (exit, entry) in exits.into_iter().zip(entries) {
+        next[exit] = entry;
+    }
+    let mut visited = vec![false; chains.len()];
+    for first in 0..chains.len() {
+        let mut pieces = Vec::<BoundaryPiece<F>>::new();
+        let mut c = first;
+        while !visited[c] {
+            visited[c] = true;
+            pieces.extend_from_slice(&chains[c]);
+            let (exit, entry) = (
+                chains[c][chains[c].len() - 1].end(),
+                chains[next[c]][0].start(),
+            );
+            if exit != entry {
+                pieces.push(BoundaryPiece::line(exit, entry));
+            }
+            c = next[c];
+        }
+        if !pieces.is_empty() {
+            output.push(pieces);
+        }
+    }
+    output
+}
+
+/// Clips the closed boundary against the counter clockwise convex `region` and measures the
+/// result. Returns `None` if nothing of the boundary is inside the region.
+pub(crate) fn measure<F: OutputType>(
+    boundary: Vec<BoundaryPiece<F>>,
+    region: &[[F; 2]],
+) -> Option<CellMeasure<F>> {
+    let mut loops = vec![boundary];
+    for i in 0..region.len() {
+        loops = clip_loops(loops, region[i], region[(i + 1) % region.len()]);
+    }
+    let origin = loops.first()?.first()?.start();
+    let mut sums = [F::zero(); 5];
+    let mut perimeter = F::zero();
+    for piece in loops.iter().flatten() {
+        let integrals = piece.integrals(origin);
+        for (sum, integral) in sums.iter_mut().zip(integrals) {
+            *sum = *sum + integral;
+        }
+        perimeter = perimeter + piece.length();
+    }
+    let half = cast::<f64, F>(0.5);
+    let area = sums[0] * half;
+    if area <= F::zero() {
+        return None;
+    }
+    // ∫∫x dA = ∮x²/2 dy, ∫∫y dA = -∮y²/2 dx
+    let local = [sums[1] * half / area, -sums[2] * half / area];
+    let centroid = [origin[0] + local[0], origin[1] + local[1]];
+    // ∫∫(x² + y²) dA = ∮x³/3 dy - ∮y³/3 dx, moved to the centroid
+    let inertia = (sums[3] - sums[4]) / cast::<f64, F>(3.0)
+        - area * (local[0] * local[0] + local[1] * local[1]);
+    Some(CellMeasure {
+        area,
+        perimeter,
+        centroid,
+        inertia: inertia.max(F::zero()),
+    })
+}
@@ -0,0 +1,313 @@
+//          Copyright Eadf (github.com/eadf) 2021.
+// Distributed under the Boost Software License, Version 1.0.
+//    (See accompanying file LICENSE_1_0.txt or copy at
+//          http://www.boost.org/LICENSE_1_0.txt)
+
+//! Medial axis extraction for polygon input.
+//!
+//! The inner medial axis of a polygon is the part of the voronoi diagram of its segments that is
+//! inside the polygon. [`MedialAxis`] builds the diagram and applies these pruning rules:
+//! * Edges outside the exterior ring, or inside a hole, are dropped. Infinite edges are always
+//!   outside.
+//! * Secondary edges, i.e. edges between a segment and one of its own end points, are dropped
+//!   unless they are explicitly kept. Inside a polygon such edges only occur at reflex
+//!   (concave) corners, where they separate the cell of the corner from the cells of the two
+//!   adjacent segments. So by default no edge touches a reflex corner.
+//! * Vertices are only kept if an edge that was kept uses them.
+//!
+//! Convex corners are part of the medial axis, as vertices with a zero clearance radius.
+
+use crate::builder::Builder;
+use crate::diagram::{self as VD, SiteGeometry};
+use crate::geometry::{Line, Polygon};
+use crate::parabola::ParabolicArc;
+use crate::{cast, BvError, InputType, OutputType};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+/// A vertex of the medial axis
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MedialAxisVertex<F: OutputType> {
+    pub position: [F; 2],
+    /// The clearance radius, the distance to the closest point of the polygon boundary
+    pub radius: F,
+}
+
+/// An edge of the medial axis
+#[derive(Debug, Clone, PartialEq)]
+pub struct MedialAxisEdge<F: OutputType> {
+    /// The indices of the end points into `MedialAxis::vertices()`
+    pub vertices: [usize; 2],
+    /// The parabolic arc of a curved edge, from `vertices[0]` to `vertices[1]`. `None` if the
+    /// edge is a straight line.
+    pub curve: Option<ParabolicArc<F>>,
+    /// True if the edge is secondary, see the module documentation
+    pub is_secondary: bool,
+}
+
+/// The inner medial axis of polygons, as a graph of vertices and edges.
+/// ```
+/// # use boostvoronoi_core::geometry::Polygon;
+/// # use boostvoronoi_core::medial_axis::MedialAxis;
+/// # use boostvoronoi_core::BvError;
+/// let rectangle = Polygon::<i32>::from(vec![[0, 0], [100, 0], [100, 50], [0, 50]]);
+/// let medial_axis = MedialAxis::<i32, f64>::from_polygons([rectangle], false)?;
+/// // one edge to each corner, and one edge along the middle
+/// assert_eq!(medial_axis.edges().len(), 5);
+/// let max_radius = medial_axis.vertices().iter().map(|v| v.radius).fold(0.0, f64::max);
+/// assert_eq!(max_radius, 25.0);
+/// # Ok::<(), BvError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct MedialAxis<I: InputType, F: OutputType> {
+    vertices_: Vec<MedialAxisVertex<F>>,
+    edges_: Vec<MedialAxisEdge<F>>,
+    pd_: PhantomData<I>,
+}
+
+impl<I: InputType, F: OutputType> MedialAxis<I, F> {
+    /// Computes the inner medial axis of the polygons. The polygons should not overlap each
+    /// other, the rings may have any orientation.
+    /// If `keep_secondary` is set the secondary edges at the reflex corners are kept.
+    /// # Errors
+    /// Will return `BvError::ValueError` if a ring has less than three vertices, and any error
+    /// from `Builder::build()`.
+    pub fn from_polygons<T, IT>(polygons: T, keep_secondary: bool) -> Result<Self, BvError>
+    where
+        T: IntoIterator<Item = IT>,
+        IT: Into<Polygon<I>>,
+    {
+        let polygons: Vec<Polygon<I>> = polygons.into_iter().map(|p| p.into()).collect();
+        let diagram = Builder::<I, F>::default()
+            .with_polygons(polygons.iter().cloned())?
+            .build()?;
+
+        let input = PolygonSegments::<I, F>::new(&polygons);
+        let segments = input.segments();
+
+        let site = |cell_id: VD::CellIndex| -> Result<(SiteGeometry<I>, usize), BvError> {
+            let cell = diagram.get_cell(cell_id)?.get();
+            Ok((
+                SiteGeometry::of_cell(&cell, &[], segments)?,
+                cell.source_index(),
+            ))
+        };
+        let position = |vertex_id: VD::VertexIndex| -> Result<[F; 2], BvError> {
+            let v = diagram.vertex_get(vertex_id)?.get();
+            Ok([v.x(), v.y()])
+        };
+
+        // Classify the vertices that are not on the boundary as inside or outside
+        let vertex_inside = diagram
+            .vertices()
+            .iter()
+            .map(|v| input.vertex_is_inside(&diagram, &v.get()))
+            .collect::<Result<Vec<_>, BvError>>()?;
+
+        let mut vertices = Vec::<MedialAxisVertex<F>>::new();
+        let mut vertex_map = HashMap::<usize, usize>::new();
+        let mut edges = Vec::<MedialAxisEdge<F>>::new();
+        for edge in diagram.edges().iter() {
+            let edge = edge.get();
+            let edge_id = edge.id();
+            let twin_id = edge.twin()?;
+            // each edge is added once, the twin is skipped
+            if twin_id.0 < edge_id.0 {
+                continue;
+            }
+            let (v0, v1) = match (edge.vertex0(), diagram.edge_get_vertex1(edge_id)?) {
+                (Some(v0), Some(v1)) => (v0, v1),
+                _ => continue,
+            };
+            if edge.is_secondary() && !keep_secondary {
+                continue;
+            }
+            let (p0, p1) = (position(v0)?, position(v1)?);
+            let inside = match (vertex_inside[v0.0], vertex_inside[v1.0]) {
+                (Some(inside), _) | (None, Some(inside)) => inside,
+                (None, None) => {
+                    let half = cast::<f64, F>(0.5);
+                    input.contains([(p0[0] + p1[0]) * half, (p0[1] + p1[1]) * half])
+                }
+            };
+            if !inside {
+                continue;
+            }
+            let curve = if edge.is_curved() {
+                match (
+                    site(edge.cell()?)?.0,
+                    site(diagram.edge_get_cell(twin_id)?)?.0,
+                ) {
+                    (SiteGeometry::Point(point), SiteGeometry::Segment(line))
+                    | (SiteGeometry::Segment(line), SiteGeometry::Point(point)) => {
+                        Some(ParabolicArc::new(&point, &line, p0, p1)?)
+                    }
+                    _ => None,
+                }
+            } else {
+                None
+            };
+            let mut vertex_index = |vertex_id: VD::VertexIndex,
+                                    p: [F; 2]|
+             -> Result<usize, BvError> {
+                if let Some(index) = vertex_map.get(&vertex_id.0) {
+                    return Ok(*index);
+                }
+                let radius = if vertex_inside[vertex_id.0].is_none() {
+                    F::zero()
+                } else {
+                    let cell_id = diagram
+                        .edge_get_cell(diagram.vertex_get(vertex_id)?.get().get_incident_edge()?)?;
+                    distance_to_site(&site(cell_id)?.0, p)
+                };
+                vertices.push(MedialAxisVertex {
+                    position: p,
+                    radius,
+                });
+                let _ = vertex_map.insert(vertex_id.0, vertices.len() - 1);
+                Ok(vertices.len() - 1)
+            };
+            let ends = [vertex_index(v0, p0)?, vertex_index(v1, p1)?];
+            edges.push(MedialAxisEdge {
+                vertices: ends,
+                curve,
+                is_secondary: edge.is_secondary(),
+            });
+        }
+        Ok(Self {
+            vertices_: vertices,
+            edges_: edges,
+            pd_: PhantomData,
+        })
+    }
+
+    /// Returns the vertices of the medial axis
+    pub fn vertices(&self) -> &[MedialAxisVertex<F>] {
+        &self.vertices_
+    }
+
+    /// Returns the edges of the medial axis, every edge is only reported once
+    pub fn edges(&self) -> &[MedialAxisEdge<F>] {
+        &self.edges_
+    }
+}
+
+/// The distance from `p` to the closest point of the site
+pub(crate) fn distance_to_site<I: InputType, F: OutputType>(
+    site: &SiteGeometry<I>,
+    p: [F; 2],
+) -> F {
+    let (a, b) = match site {
+        SiteGeometry::Point(point) => (*point, *point),
+        SiteGeometry::Segment(line) => (line.start, line.end),
+    };
+    let (x0, y0) = (cast::<I, F>(a.x), cast::<I, F>(a.y));
+    let (dx, dy) = (cast::<I, F>(b.x) - x0, cast::<I, F>(b.y) - y0);
+    let length = dx * dx + dy * dy;
+    let t = if length > F::zero() {
+        (((p[0] - x0) * dx + (p[1] - y0) * dy) / length)
+            .max(F::zero())
+            .min(F::one())
+    } else {
+        F::zero()
+    };
+    (p[0] - x0 - t * dx).hypot(p[1] - y0 - t * dy)
+}
+
+/// The segments of polygon input, in the same order as the `Builder` adds them, together with
+/// the side of each segment that is inside the polygon.
+#[derive(Debug, Clone)]
+pub(crate) struct PolygonSegments<I: InputType, F: OutputType> {
+    segments_: Vec<Line<I>>,
+    // 1 if the inside is to the left of the segment, -1 if it is to the right
+    inner_side_: Vec<F>,
+}
+
+impl<I: InputType, F: OutputType> PolygonSegments<I, F> {
+    pub(crate) fn new(polygons: &[Polygon<I>]) -> Self {
+        let mut segments = Vec::<Line<I>>::new();
+        let mut inner_side = Vec::<F>::new();
+        for polygon in polygons.iter() {
+            for (ring_index, ring) in polygon.rings().enumerate() {
+                let ring = match ring.split_last() {
+                    Some((last, rest)) if ring.len() > 1 && *last == ring[0] => rest,
+                    _ => ring.as_slice(),
+                };
+                let mut area = F::zero();
+                for (i, start) in ring.iter().enumerate() {
+                    let line = Line::new(*start, ring[(i + 1) % ring.len()]);
+                    area = area + cast::<I, F>(line.start.x) * cast::<I, F>(line.end.y)
+                        - cast::<I, F>(line.end.x) * cast::<I, F>(line.start.y);
+                    segments.push(line);
+                }
+                // the inside is to the left of a counter clockwise exterior ring, and to the
+                // left of a clockwise hole
+                let ccw = area > F::zero();
+                let sign = if ccw == (ring_index == 0) {
+                    F::one()
+                } else {
+                    -F::one()
+                };
+                inner_side.resize(segments.len(), sign);
+            }
+        }
+        Self {
+            segments_: segments,
+            inner_side_: inner_side,
+        }
+    }
+
+    /// Returns the segments, indexed by source index
+    #[inline]
+    pub(crate) fn segments(&self) -> &[Line<I>] {
+        &self.segments_
+    }
+
+    /// Returns true if `p` is on the inner side of the segment `line` with the source index
+    /// `source_index`.
+    #[inline]
+    pub(crate) fn is_inner_side(&self, line: &Line<I>, source_index: usize, p: [F; 2]) -> bool {
+        let (x0, y0) = (cast::<I, F>(line.start.x), cast::<I, F>(line.start.y));
+        let (x1, y1) = (cast::<I, F>(line.end.x), cast::<I, F>(line.end.y));
+        let side = (x1 - x0) * (p[1] - y0) - (y1 - y0) * (p[0] - x0);
+        side * self.inner_side_[source_index] > F::zero()
+    }
+
+    /// Classifies a vertex of the diagram of the segments as inside or outside the polygons.
+    /// Returns `None` for vertices on the boundary.
+    pub(crate) fn vertex_is_inside(
+        &self,
+        diagram: &VD::Diagram<F>,
+        vertex: &VD::Vertex<F>,
+    ) -> Result<Option<bool>, BvError> {
+        if vertex.is_site_point() {
+            return Ok(None);
+        }
+        let p = [vertex.x(), vertex.y()];
+        // the side of any segment the vertex is equidistant to decides
+        for edge_id in diagram.edge_rot_next_iterator(Some(vertex.get_incident_edge()?)) {
+            let cell = diagram.get_cell(diagram.edge_get_cell(edge_id)?)?.get();
+            if let SiteGeometry::Segment(line) = SiteGeometry::of_cell(&cell, &[], &self.segments_)?
+            {
+                return Ok(Some(self.is_inner_side(&line, cell.source_index(), p)));
+            }
+        }
+        Ok(Some(self.contains(p)))
+    }
+
+    /// Even-odd point in polygon test against all the segments
+    pub(crate) fn contains(&self, p: [F; 2]) -> bool {
+        let mut inside = false;
+        for line in self.segments_.iter() {
+            let (a, b) = (line.start, line.end);
+            let (ay, by) = (cast::<I, F>(a.y), cast::<I, F>(b.y));
+            if (ay > p[1]) != (by > p[1]) {
+                let (ax, bx) = (cast::<I, F>(a.x), cast::<I, F>(b.x));
+                if p[0] < ax + (p[1] - ay) / (by - ay) * (bx - ax) {
+                    inside = !inside;
+                }
+            }
+        }
+        inside
+    }
+}
@@ -0,0 +1,548 @@
+//          Copyright Eadf (github.com/eadf) 2021.
+// Distributed under the Boost Software License, Version 1.0.
+//    (See accompanying file LICENSE_1_0.txt or copy at
+//          http://www.boost.org/LICENSE_1_0.txt)
+
+//! Polygon offsetting driven by the voronoi diagram.
+//!
+//! The offset curve at distance `d` is the set of points at exactly the distance `d` from the
+//! polygon boundary. Inside the cell of a segment it is a line parallel to the segment, inside the
+//! cell of a polygon corner it is a circular arc around the corner. The [`Offsetter`] finds the
+//! points where the curve crosses the voronoi edges, and connects them cell by cell into closed
+//! loops. Loops that split, or vanish, as the distance grows are handled without special cases.
+
+use crate::builder::Builder;
+use crate::clipping::infinite_edge_line;
+use crate::diagram::{self as VD, SiteGeometry};
+use crate::geometry::{Point, Polygon};
+use crate::medial_axis::{distance_to_site, PolygonSegments};
+use crate::parabola::ParabolicArc;
+use crate::{cast, BvError, InputType, OutputType};
+
+/// An element of an offset loop
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OffsetElement<F: OutputType> {
+    /// A straight line, parallel to an input segment
+    Line { start: [F; 2], end: [F; 2] },
+    /// A circular arc around a polygon corner, the radius is the offset distance.
+    /// `ccw` is true if the arc goes counter clockwise from `start` to `end`.
+    Arc {
+        start: [F; 2],
+        end: [F; 2],
+        center: [F; 2],
+        ccw: bool,
+    },
+}
+
+impl<F: OutputType> OffsetElement<F> {
+    /// Returns the start point of the element
+    pub fn start(&self) -> [F; 2] {
+        match self {
+            Self::Line { start, .. } | Self::Arc { start, .. } => *start,
+        }
+    }
+
+    /// Returns the end point of the element
+    pub fn end(&self) -> [F; 2] {
+        match self {
+            Self::Line { end, .. } | Self::Arc { end, .. } => *end,
+        }
+    }
+
+    /// Returns the same element, going in the opposite direction
+    fn reversed(&self) -> Self {
+        match *self {
+            Self::Line { start, end } => Self::Line {
+                start: end,
+                end: start,
+            },
+            Self::Arc {
+                start,
+                end,
+                center,
+                ccw,
+            } => Self::Arc {
+                start: end,
+                end: start,
+                center,
+                ccw: !ccw,
+            },
+        }
+    }
+}
+
+/// A closed offset loop. The end of each element is the start of the next, the end of the last
+/// element is the start of the first.
+///
+/// Loops are oriented with the offset area to the left: outer loops are counter clockwise and
+/// loops around holes in the offset area are clockwise.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OffsetLoop<F: OutputType> {
+    pub elements: Vec<OffsetElement<F>>,
+}
+
+/// The distance to the input along an edge, parametrized by `u`. The distance is convex in `u`.
+#[derive(Debug, Clone, Copy)]
+enum EdgeProfile<F: OutputType> {
+    /// A straight edge `origin + u * direction` where the distance is measured to a point
+    Point {
+        origin: [F; 2],
+        direction: [F; 2],
+        site: [F; 2],
+    },
+    /// A straight edge `origin + u * direction` where the distance is measured to a line, the
+    /// distance is `s0 + u * k`.
+    Line {
+        origin: [F; 2],
+        direction: [F; 2],
+        s0: F,
+        k: F,
+    },
+    /// A parabolic edge, parametrized by the x coordinate in the local frame of the parabola:
+    /// `y = ((x - a)² + b²) / 2b`
+    Parabola {
+        origin: [F; 2],
+        unit_x: [F; 2],
+        a: F,
+        b: F,
+    },
+}
+
+impl<F: OutputType> EdgeProfile<F> {
+    /// The position at parameter `u`
+    fn position(&self, u: F) -> [F; 2] {
+        match *self {
+            Self::Point {
+                origin, direction, ..
+            }
+            | Self::Line {
+                origin, direction, ..
+            } => [origin[0] + u * direction[0], origin[1] + u * direction[1]],
+            Self::Parabola {
+                origin,
+                unit_x,
+                a,
+                b,
+            } => {
+                let y = ((u - a) * (u - a) + b * b) / (b + b);
+                [
+                    origin[0] + unit_x[0] * u - unit_x[1] * y,
+                    origin[1] + unit_x[1] * u + unit_x[0] * y,
+                ]
+            }
+        }
+    }
+
+    /// The distance to the input at parameter `u`
+    fn distance(&self, u: F) -> F {
+        match *self {
+            Self::Point { site, .. } => {
+                let p = self.position(u);
+                (p[0] - site[0]).hypot(p[1] - site[1])
+            }
+            Self::Line { s0, k, .. } => s0 + u * k,
+            Self::Parabola { a, b, .. } => ((u - a) * (u - a) + b * b) / (b + b).abs(),
+        }
+    }
+
+    /// The parameter of the minimum distance in `[u0, u1]`
+    fn minimum(&self, u0: F, u1: F) -> F {
+        let u = match *self {
+            Self::Point {
+                origin,
+                direction,
+                site,
+            } => {
+                let d = [origin[0] - site[0], origin[1] - site[1]];
+                -(d[0] * direction[0] + d[1] * direction[1])
+                    / (direction[0] * direction[0] + direction[1] * direction[1])
+            }
+            Self::Line { k, .. } => {
+                if k > F::zero() {
+                    u0
+                } else {
+                    u1
+                }
+            }
+            Self::Parabola { a, .. } => a,
+        };
+        u.max(u0).min(u1)
+    }
+
+    /// The parameters where the distance equals `distance`, the lower one is on the decreasing
+    /// side of the minimum and the higher one on the increasing side.
+    fn roots(&self, distance: F) -> (F, F) {
+        match *self {
+            Self::Point {
+                origin,
+                direction,
+                site,
+            } => {
+                let d = [origin[0] - site[0], origin[1] - site[1]];
+                let a = direction[0] * direction[0] + direction[1] * direction[1];
+                let b = d[0] * direction[0] + d[1] * direction[1];
+                let c = d[0] * d[0] + d[1] * d[1] - distance * distance;
+                let root = (b * b - a * c).max(F::zero()).sqrt();
+                ((-b - root) / a, (-b + root) / a)
+            }
+            Self::Line { s0, k, .. } => {
+                let u = (distance - s0) / k;
+                (u, u)
+            }
+            Self::Parabola { a, b, .. } => {
+                let root = ((b + b).abs() * distance - b * b).max(F::zero()).sqrt();
+                (a - root, a + root)
+            }
+        }
+    }
+}
+
+/// A point where the offset curve crosses a voronoi edge
+#[derive(Debug, Clone, Copy)]
+struct Crossing<F: OutputType> {
+    position: [F; 2],
+    /// true if the distance decreases through `distance` when going along the edge it was found
+    /// on, from vertex0 towards vertex1.
+    decreasing: bool,
+}
+
+/// Offsets polygons by building the voronoi diagram of the polygon segments once, the diagram
+/// can then be used for any number of offset distances.
+/// ```
+/// # use boostvoronoi_core::geometry::Polygon;
+/// # use boostvoronoi_core::offset::{OffsetElement, Offsetter};
+/// # use boostvoronoi_core::BvError;
+/// let square = Polygon::<i32>::from(vec![[0, 0], [100, 0], [100, 100], [0, 100]]);
+/// let offsetter = Offsetter::<i32, f64>::from_polygons([square])?;
+/// // inwards: a smaller square
+/// let loops = offsetter.offset(-10.0)?;
+/// assert_eq!(loops.len(), 1);
+/// assert_eq!(loops[0].elements.len(), 4);
+/// // outwards: four lines and four arcs around the corners
+/// let loops = offsetter.offset(10.0)?;
+/// assert_eq!(loops[0].elements.len(), 8);
+/// let arcs = loops[0].elements.iter().filter(|e| matches!(e, OffsetElement::Arc { .. }));
+/// assert_eq!(arcs.count(), 4);
+/// // the inward offset vanishes
+/// assert!(offsetter.offset(-50.0)?.is_empty());
+/// # Ok::<(), BvError>(())
+/// ```
+#[derive(Debug)]
+pub struct Offsetter<I: InputType, F: OutputType> {
+    diagram_: VD::Diagram<F>,
+    input_: PolygonSegments<I, F>,
+}
+
+impl<I: InputType, F: OutputType> Offsetter<I, F> {
+    /// Builds the voronoi diagram of the polygons. The polygons should not overlap each other,
+    /// the rings may have any orientation.
+    /// # Errors
+    /// Will return `BvError::ValueError` if a ring has less than three vertices, and any error
+    /// from `Builder::build()`.
+    pub fn from_polygons<T, IT>(polygons: T) -> Result<Self, BvError>
+    where
+        T: IntoIterator<Item = IT>,
+        IT: Into<Polygon<I>>,
+    {
+        let polygons: Vec<Polygon<I>> = polygons.into_iter().map(|p| p.into()).collect();
+        let diagram = Builder::<I, F>::default()
+            .with_polygons(polygons.iter().cloned())?
+            .build()?;
+        Ok(Self {
+            diagram_: diagram,
+            input_: PolygonSegments::new(&polygons),
+        })
+    }
+
+    /// Returns the offset loops at `distance` from the polygons. A positive distance offsets
+    /// outwards (the polygons grow), a negative distance offsets inwards (the polygons shrink).
+    /// # Errors
+    /// Will return `BvError::ValueError` if the distance is zero or not finite.
+    pub fn offset(&self, distance: F) -> Result<Vec<OffsetLoop<F>>, BvError> {
+        if distance == F::zero() || !distance.is_finite() {
+            return Err(BvError::ValueError(format!(
+                "The offset distance must be finite and non-zero, it was {:?}",
+                distance
+            )));
+        }
+        let outwards = distance > F::zero();
+        let distance = distance.abs();
+        let diagram = &self.diagram_;
+        let segments = self.input_.segments();
+
+        // The crossings of each edge, in order from vertex0 to vertex1. The crossings of an
+        // edge pair are stored at the edge with the lowest id, `first_crossing[e]` is the index
+        // of the first crossing of that edge.
+        let mut crossings = Vec::<Crossing<F>>::new();
+        // Whether each vertex is closer to the input than `distance`, decided once per vertex so
+        // that all the edges around it agree.
+        let mut near_vertex = Vec::<bool>::with_capacity(diagram.vertices().len());
+        for vertex in diagram.vertices().iter() {
+            let vertex = vertex.get();
+            let cell_id = diagram.edge_get_cell(vertex.get_incident_edge()?)?;
+            let site = SiteGeometry::of_cell(&diagram.get_cell(cell_id)?.get(), &[], segments)?;
+            near_vertex.push(distance_to_site(&site, [vertex.x(), vertex.y()]) < distance);
+        }
+        let mut first_crossing = vec![0_usize; diagram.edges().len() + 1];
+        for edge in diagram.edges().iter() {
+            let edge = edge.get();
+            let edge_id = edge.id();
+            if edge.twin()?.0 > edge_id.0 {
+                self.edge_crossings_(edge_id, distance, &near_vertex, &mut crossings)?;
+            }
+            first_crossing[edge_id.0 + 1] = crossings.len();
+        }
+        // returns the crossings of an edge, and if they should be reversed
+        let crossings_of = |edge_id: VD::EdgeIndex| -> Result<(usize, usize, bool), BvError> {
+            let twin_id = diagram.edge_get_twin(edge_id)?;
+            let (owner, reversed) = if twin_id.0 < edge_id.0 {
+                (twin_id, true)
+            } else {
+                (edge_id, false)
+            };
+            Ok((
+                first_crossing[owner.0],
+                first_crossing[owner.0 + 1],
+                reversed,
+            ))
+        };
+
+        // Inside each cell, the curve leaves the boundary where the distance increases through
+        // `distance` (walking counter clockwise around the cell) and comes back at the next
+        // point where the distance decreases. `link[c]` is the next crossing from crossing `c`,
+        // and the cell the piece between them belongs to.
+        let mut link = vec![None; crossings.len()];
+        let mut walk = Vec::<(usize, bool)>::new();
+        for cell in diagram.cells().iter() {
+            let cell = cell.get();
+            if cell.is_degenerate() {
+                continue;
+            }
+            walk.clear();
+            for edge_id in diagram.cell_edge_iterator(cell.id()) {
+                let (first, last, reversed) = crossings_of(edge_id)?;
+                if reversed {
+                    walk.extend((first..last).rev().map(|c| (c, !crossings[c].decreasing)));
+                } else {
+                    walk.extend((first..last).map(|c| (c, crossings[c].decreasing)));
+                }
+            }
+            for (i, (c, decreasing)) in walk.iter().enumerate() {
+                if *decreasing {
+                    continue;
+                }
+                let next = (1..walk.len())
+                    .map(|j| walk[(i + j) % walk.len()])
+                    .find(|(_, decreasing)| *decreasing)
+                    .ok_or_else(|| {
+                        BvError::InternalError(format!(
+                            "The offset curve does not return to cell {}",
+                            cell.id().0
+                        ))
+                    })?;
+                link[*c] = Some((next.0, cell.id()));
+            }
+        }
+
+        // Follow the links into loops
+        let mut visited = vec![false; crossings.len()];
+        let mut loops = Vec::<OffsetLoop<F>>::new();
+        for start in 0..crossings.len() {
+            if visited[start] {
+                continue;
+            }
+            let mut elements = Vec::<OffsetElement<F>>::new();
+            let mut c = start;
+            while !visited[c] {
+                visited[c] = true;
+                let (next, cell_id) = link[c].ok_or_else(|| {
+                    BvError::InternalError("The offset curve is not closed".to_string())
+                })?;
+                let (p0, p1) = (crossings[c].position, crossings[next].position);
+                if p0 != p1 {
+                    let cell = diagram.get_cell(cell_id)?.get();
+                    elements.push(match SiteGeometry::of_cell(&cell, &[], segments)? {
+                        SiteGeometry::Point(center) => OffsetElement::Arc {
+                            start: p0,
+                            end: p1,
+                            center: [cast::<I, F>(center.x), cast::<I, F>(center.y)],
+                            ccw: true,
+                        },
+                        SiteGeometry::Segment(_) => OffsetElement::Line { start: p0, end: p1 },
+                    });
+                }
+                c = next;
+            }
+            if c != start {
+                return Err(BvError::InternalError(
+                    "The offset curve does not form a loop".to_string(),
+                ));
+            }
+            if elements.is_empty() || self.input_.contains(crossings[start].position) == outwards {
+                continue;
+            }
+            // The loops are traced with the input on the left side
+            if !outwards {
+                elements.reverse();
+                for element in elements.iter_mut() {
+                    *element = element.reversed();
+                }
+            }
+            loops.push(OffsetLoop { elements });
+        }
+        Ok(loops)
+    }
+
+    /// Appends the crossings of the edge, in order from vertex0 to vertex1
+    fn edge_crossings_(
+        &self,
+        edge_id: VD::EdgeIndex,
+        distance: F,
+        near_vertex: &[bool],
+        crossings: &mut Vec<Crossing<F>>,
+    ) -> Result<(), BvError> {
+        let diagram = &self.diagram_;
+        let segments = self.input_.segments();
+        let edge = diagram.get_edge(edge_id)?.get();
+        let site = |cell_id: VD::CellIndex| {
+            SiteGeometry::of_cell(&diagram.get_cell(cell_id)?.get(), &[], segments)
+        };
+        let to_f = |p: Point<I>| [cast::<I, F>(p.x), cast::<I, F>(p.y)];
+        let position = |vertex_id: VD::VertexIndex| -> Result<[F; 2], BvError> {
+            let v = diagram.vertex_get(vertex_id)?.get();
+            Ok([v.x(), v.y()])
+        };
+        let (v0, v1) = (edge.vertex0(), diagram.edge_get_vertex1(edge_id)?);
+        // the infinite ends of an edge are never near the input
+        let near0 = v0.is_some_and(|v| near_vertex[v.0]);
+        let near1 = v1.is_some_and(|v| near_vertex[v.0]);
+
+        // The straight line of the edge, and the range of the parameter
+        let (origin, direction, u0, u1) = match (v0, v1) {
+            (Some(v0), Some(v1)) => {
+                let (p0, p1) = (position(v0)?, position(v1)?);
+                (p0, [p1[0] - p0[0], p1[1] - p0[1]], F::zero(), F::one())
+            }
+            (v0, v1) => {
+                let (origin, direction) =
+                    infinite_edge_line::<I, F>(diagram, edge_id, &[], segments)?;
+                match (v0, v1) {
+                    (Some(v0), _) => (position(v0)?, direction, F::zero(), F::infinity()),
+                    (_, Some(v1)) => (position(v1)?, direction, F::neg_infinity(), F::zero()),
+                    _ => (origin, direction, F::neg_infinity(), F::infinity()),
+                }
+            }
+        };
+
+        let sites = (
+            site(edge.cell()?)?,
+            site(diagram.edge_get_cell(edge.twin()?)?)?,
+        );
+        let (profile, u0, u1) = match sites {
+            (SiteGeometry::Point(point), SiteGeometry::Segment(line))
+            | (SiteGeometry::Segment(line), SiteGeometry::Point(point))
+                if edge.is_curved() =>
+            {
+                if !u0.is_finite() || !u1.is_finite() {
+                    return Err(BvError::InternalError(format!(
+                        "The curved edge {} is infinite",
+                        edge_id.0
+                    )));
+                }
+                let end = [origin[0] + direction[0], origin[1] + direction[1]];
+                let arc = ParabolicArc::new(&point, &line, origin, end)?;
+                let [d0, d1] = arc.directrix;
+                let d = [d1[0] - d0[0], d1[1] - d0[1]];
+                let length = d[0].hypot(d[1]);
+                let unit_x = [d[0] / length, d[1] / length];
+                let local = |p: [F; 2]| {
+                    let q = [p[0] - d0[0], p[1] - d0[1]];
+                    [
+                        unit_x[0] * q[0] + unit_x[1] * q[1],
+                        unit_x[0] * q[1] - unit_x[1] * q[0],
+                    ]
+                };
+                let focus = local(arc.focus);
+                (
+                    EdgeProfile::Parabola {
+                        origin: d0,
+                        unit_x,
+                        a: focus[0],
+                        b: focus[1],
+                    },
+                    local(origin)[0],
+                    local(end)[0],
+                )
+            }
+            (SiteGeometry::Point(point), _) | (_, SiteGeometry::Point(point)) => (
+                EdgeProfile::Point {
+                    origin,
+                    direction,
+                    site: to_f(point),
+                },
+                u0,
+                u1,
+            ),
+            (SiteGeometry::Segment(line), _) => {
+                let (start, end) = (to_f(line.start), to_f(line.end));
+                let d = [end[0] - start[0], end[1] - start[1]];
+                let length = d[0].hypot(d[1]);
+                let normal = [-d[1] / length, d[0] / length];
+                let mut s0 =
+                    normal[0] * (origin[0] - start[0]) + normal[1] * (origin[1] - start[1]);
+                let mut k = normal[0] * direction[0] + normal[1] * direction[1];
+                // the edge is on one side of the line, pick the normal pointing to that side
+                let inside = match (u0.is_finite(), u1.is_finite()) {
+                    (true, true) => (u0 + u1) / cast::<f64, F>(2.0),
+                    (true, false) => u0 + F::one(),
+                    (false, true) => u1 - F::one(),
+                    (false, false) => F::zero(),
+                };
+                if s0 + inside * k < F::zero() {
+                    s0 = -s0;
+                    k = -k;
+                }
+                (
+                    EdgeProfile::Line {
+                        origin,
+                        direction,
+                        s0,
+                        k,
+                    },
+                    u0,
+                    u1,
+                )
+            }
+        };
+
+        // The parameter along a parabola may go in either direction
+        let (forward, u0, u1, near0, near1) = if u0 <= u1 {
+            (true, u0, u1, near0, near1)
+        } else {
+            (false, u1, u0, near1, near0)
+        };
+        let minimum = profile.minimum(u0, u1);
+        if !(near0 || near1 || (minimum.is_finite() && profile.distance(minimum) < distance)) {
+            return Ok(());
+        }
+        let (low, high) = profile.roots(distance);
+        let mut found = Vec::<Crossing<F>>::with_capacity(2);
+        if !near0 {
+            found.push(Crossing {
+                position: profile.position(low.max(u0).min(minimum)),
+                decreasing: forward,
+            });
+        }
+        if !near1 {
+            found.push(Crossing {
+                position: profile.position(high.max(minimum).min(u1)),
+                decreasing: !forward,
+            });
+        }
+        if !forward {
+            found.reverse();
+        }
+        crossings.extend(found);
+        Ok(())
+    }
+}
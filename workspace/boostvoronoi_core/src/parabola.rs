@@ -0,0 +1,200 @@
+//          Copyright Eadf (github.com/eadf) 2021.
+// Distributed under the Boost Software License, Version 1.0.
+//    (See accompanying file LICENSE_1_0.txt or copy at
+//          http://www.boost.org/LICENSE_1_0.txt)
+
+//! Exact representation of the curved edges.
+//!
+//! A curved edge is always an arc of the parabola formed by one input point (the focus) and one
+//! input segment (the directrix). [`ParabolicArc`] describes that arc and converts it into a
+//! [`QuadraticBezier`], or into the coefficients of the implicit conic equation.
+
+use crate::diagram::{self as VD, SiteGeometry};
+use crate::geometry::{Line, Point};
+use crate::{cast, BvError, InputType, OutputType};
+
+/// A rational quadratic Bézier curve:
+/// `B(t) = Σ wᵢ·bᵢ(t)·Pᵢ / Σ wᵢ·bᵢ(t)` where `bᵢ` are the quadratic Bernstein polynomials.
+///
+/// Parabolic arcs are represented exactly with all weights set to one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuadraticBezier<F: OutputType> {
+    pub control_points: [[F; 2]; 3],
+    pub weights: [F; 3],
+}
+
+impl<F: OutputType> QuadraticBezier<F> {
+    /// Evaluates the curve at `t`, `t` in the range `[0, 1]`
+    pub fn point_at(&self, t: F) -> [F; 2] {
+        let s = F::one() - t;
+        let b = [s * s, cast::<f64, F>(2.0) * s * t, t * t];
+        let mut numerator = [F::zero(), F::zero()];
+        let mut denominator = F::zero();
+        for ((p, w), b) in self.control_points.iter().zip(self.weights).zip(b) {
+            let w = w * b;
+            numerator[0] = numerator[0] + w * p[0];
+            numerator[1] = numerator[1] + w * p[1];
+            denominator = denominator + w;
+        }
+        [numerator[0] / denominator, numerator[1] / denominator]
+    }
+}
+
+/// The arc of a curved edge, from `start` (vertex0 of the edge) to `end` (vertex1 of the edge).
+///
+/// Every point of the arc is equally far from `focus` and from the line through `directrix`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParabolicArc<F: OutputType> {
+    /// The input point of the edge
+    pub focus: [F; 2],
+    /// The input segment of the edge, as `[start, end]`
+    pub directrix: [[F; 2]; 2],
+    pub start: [F; 2],
+    pub end: [F; 2],
+}
+
+impl<F: OutputType> ParabolicArc<F> {
+    /// Creates the arc between `start` and `end` of the parabola formed by `focus` and
+    /// `directrix`.
+    /// # Errors
+    /// Will return `BvError::ValueError` if the segment has no length, or if the focus is on
+    /// the line through the segment.
+    pub fn new<I: InputType>(
+        focus: &Point<I>,
+        directrix: &Line<I>,
+        start: [F; 2],
+        end: [F; 2],
+    ) -> Result<Self, BvError> {
+        let to_f = |p: &Point<I>| [cast::<I, F>(p.x), cast::<I, F>(p.y)];
+        let rv = Self {
+            focus: to_f(focus),
+            directrix: [to_f(&directrix.start), to_f(&directrix.end)],
+            start,
+            end,
+        };
+        let (_, _, b) = rv.frame_();
+        if b.is_finite() && b != F::zero() {
+            Ok(rv)
+        } else {
+            Err(BvError::ValueError(format!(
+                "The point {:?} and the segment {:?} do not form a parabola",
+                focus, directrix
+            )))
+        }
+    }
+
+    /// Returns the arc of a curved edge, or `None` if the edge is not curved.
+    /// `points` and `segments` must be the input geometry of the `Builder`, in the same order.
+    /// # Errors
+    /// Will return `BvError::IdError` if the edge or its sites are not found, and
+    /// `BvError::ValueError` if a curved edge is infinite.
+    pub fn from_edge<I: InputType>(
+        diagram: &VD::Diagram<F>,
+        edge_id: VD::EdgeIndex,
+        points: &[Point<I>],
+        segments: &[Line<I>],
+    ) -> Result<Option<Self>, BvError> {
+        let edge = diagram.get_edge(edge_id)?.get();
+        if !edge.is_curved() {
+            return Ok(None);
+        }
+        let cell2_id = diagram.edge_get_cell(diagram.edge_get_twin(edge_id)?)?;
+        let site1 =
+            SiteGeometry::of_cell(&diagram.get_cell(edge.cell()?)?.get(), points, segments)?;
+        let site2 = SiteGeometry::of_cell(&diagram.get_cell(cell2_id)?.get(), points, segments)?;
+        let (focus, directrix) = match (site1, site2) {
+            (SiteGeometry::Point(point), SiteGeometry::Segment(segment))
+            | (SiteGeometry::Segment(segment), SiteGeometry::Point(point)) => (point, segment),
+            _ => {
+                return Err(BvError::InternalError(format!(
+                    "The curved edge {} is not formed by a point and a segment",
+                    edge_id.0
+                )))
+            }
+        };
+        let vertex = |v: Option<VD::VertexIndex>| -> Result<[F; 2], BvError> {
+            match v {
+                Some(v) => {
+                    let v = diagram.vertex_get(v)?.get();
+                    Ok([v.x(), v.y()])
+                }
+                None => Err(BvError::ValueError(format!(
+                    "The curved edge {} is infinite",
+                    edge_id.0
+                ))),
+            }
+        };
+        let start = vertex(edge.vertex0())?;
+        let end = vertex(diagram.edge_get_vertex1(edge_id)?)?;
+        Self::new(&focus, &directrix, start, end).map(Some)
+    }
+
+    /// Returns the local frame of the parabola: the origin at the start of the directrix and the
+    /// unit x axis along the directrix (the y axis is 90° counter clockwise from it). Together
+    /// with the y coordinate `b` of the focus in that frame.
+    /// With the focus at `(a, b)`, the parabola is `y = ((x - a)² + b²) / 2b` in the local frame.
+    fn frame_(&self) -> ([F; 2], [F; 2], F) {
+        let origin = self.directrix[0];
+        let dx = self.directrix[1][0] - origin[0];
+        let dy = self.directrix[1][1] - origin[1];
+        let length = dx.hypot(dy);
+        let u = [dx / length, dy / length];
+        let f = [self.focus[0] - origin[0], self.focus[1] - origin[1]];
+        let b = u[0] * f[1] - u[1] * f[0];
+        (origin, u, b)
+    }
+
+    /// Converts a point to the local frame, see `frame_()`.
+    #[inline]
+    fn to_local_(origin: [F; 2], u: [F; 2], p: [F; 2]) -> [F; 2] {
+        let d = [p[0] - origin[0], p[1] - origin[1]];
+        [u[0] * d[0] + u[1] * d[1], u[0] * d[1] - u[1] * d[0]]
+    }
+
+    /// Converts a point from the local frame, see `frame_()`.
+    #[inline]
+    fn from_local_(origin: [F; 2], u: [F; 2], p: [F; 2]) -> [F; 2] {
+        [
+            origin[0] + u[0] * p[0] - u[1] * p[1],
+            origin[1] + u[1] * p[0] + u[0] * p[1],
+        ]
+    }
+
+    /// Returns the arc as a quadratic Bézier curve going from `start` to `end`. The middle
+    /// control point is the intersection of the tangents at `start` and `end`, all the weights
+    /// are one.
+    pub fn to_bezier(&self) -> QuadraticBezier<F> {
+        let (origin, u, b) = self.frame_();
+        let a = Self::to_local_(origin, u, self.focus)[0];
+        let x0 = Self::to_local_(origin, u, self.start)[0];
+        let x2 = Self::to_local_(origin, u, self.end)[0];
+        let half = cast::<f64, F>(0.5);
+        let y = |x: F| ((x - a) * (x - a) + b * b) / (b + b);
+        // the tangents at x0 and x2 intersect at the mid x
+        let x1 = (x0 + x2) * half;
+        let y1 = y(x0) + (x0 - a) / b * (x1 - x0);
+        QuadraticBezier {
+            control_points: [self.start, Self::from_local_(origin, u, [x1, y1]), self.end],
+            weights: [F::one(), F::one(), F::one()],
+        }
+    }
+
+    /// Returns the coefficients `[A, B, C, D, E, F]` of the implicit equation of the parabola:
+    /// `A·x² + B·x·y + C·y² + D·x + E·y + F = 0`.
+    pub fn conic(&self) -> [F; 6] {
+        let (origin, u, _) = self.frame_();
+        // the unit normal of the directrix, and the directrix as n·p = c
+        let n = [-u[1], u[0]];
+        let c = n[0] * origin[0] + n[1] * origin[1];
+        let f = self.focus;
+        let two = cast::<f64, F>(2.0);
+        [
+            F::one() - n[0] * n[0],
+            -two * n[0] * n[1],
+            F::one() - n[1] * n[1],
+            two * (c * n[0] - f[0]),
+            two * (c * n[1] - f[1]),
+            f[0] * f[0] + f[1] * f[1] - c * c,
+        ]
+    }
+}
@@ -0,0 +1,659 @@
+//          Copyright Eadf (github.com/eadf) 2021.
+// Distributed under the Boost Software License, Version 1.0.
+//    (See accompanying file LICENSE_1_0.txt or copy at
+//          http://www.boost.org/LICENSE_1_0.txt)
+
+//! Clearance constrained shortest paths over the voronoi diagram used as a roadmap.
+//!
+//! The edges of the diagram form a graph where every edge keeps as far away from the input
+//! sites as possible. The infinite edges are clipped to a box around the input, the start and
+//! the goal, and the sides of that box connect them, so paths can go around the outside of the
+//! input. The start and the goal are connected with straight lines to the vertices of the cells
+//! they are in, and Dijkstra's algorithm finds the shortest path where no edge comes closer to
+//! an input site than the robot radius.
+
+use crate::clipping::infinite_edge_line;
+use crate::diagram::{self as VD, SiteGeometry};
+use crate::geometry::{Line, Point};
+use crate::measure::BoundaryPiece;
+use crate::parabola::ParabolicArc;
+use crate::point_location::PointLocator;
+use crate::visual_utils::{SimpleAffine, VoronoiVisualUtils};
+use crate::{cast, BvError, InputType, OutputType};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// A path found by `PathPlanner::shortest_path()`
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedPath<F: OutputType> {
+    /// The path from the start to the goal, curved edges are discretized
+    pub points: Vec<[F; 2]>,
+    /// The exact length of the path, parabolic arcs included
+    pub length: F,
+    /// The smallest distance between the path and any input site
+    pub clearance: F,
+}
+
+/// The length and the clearance of a finite edge
+#[derive(Debug, Clone, Copy)]
+struct EdgeWeight<F: OutputType> {
+    length: F,
+    clearance: F,
+}
+
+/// A connection between two nodes that is not a finite diagram edge
+#[derive(Debug, Clone)]
+struct Link<F: OutputType> {
+    to: usize,
+    length: F,
+    clearance: F,
+    /// The points of the connection after the first node, ending at the `to` node
+    points: Vec<[F; 2]>,
+}
+
+/// The nodes and connections added to the finite diagram edges for one search.
+/// The nodes are the diagram vertices, the start, the goal and then the points on the box.
+struct Roadmap<F: OutputType> {
+    /// The node where an edge without vertex0 comes in from the box, indexed by EdgeIndex
+    entries_: Vec<Option<usize>>,
+    /// The positions of the nodes on the box
+    box_points_: Vec<[F; 2]>,
+    /// The connections from each node
+    links_: Vec<Vec<Link<F>>>,
+}
+
+/// A node waiting in the search queue, the closest node is the greatest
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct QueueEntry<F: OutputType> {
+    distance: F,
+    node: usize,
+}
+
+impl<F: OutputType> Eq for QueueEntry<F> {}
+
+impl<F: OutputType> PartialOrd for QueueEntry<F> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<F: OutputType> Ord for QueueEntry<F> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // the distances are never NaN
+        other
+            .distance
+            .partial_cmp(&self.distance)
+            .unwrap_or(Ordering::Equal)
+            .then(other.node.cmp(&self.node))
+    }
+}
+
+/// How the search reached a node
+#[derive(Debug, Clone, Copy)]
+enum Step {
+    /// The connection with this index among the links of the previous node
+    Link(usize),
+    /// A finite diagram edge, from its vertex0
+    Edge(VD::EdgeIndex),
+}
+
+/// Finds paths over the edges of a voronoi diagram that keep a minimum distance to the input
+/// sites.
+/// ```
+/// # use boostvoronoi_core::builder::Builder;
+/// # use boostvoronoi_core::geometry::{Line, Point};
+/// # use boostvoronoi_core::path_planning::PathPlanner;
+/// # use boostvoronoi_core::BvError;
+/// // a closed corridor of width 20 with a wall in the middle
+/// let points: Vec<Point<i32>> = vec![];
+/// let segments: Vec<Line<i32>> = vec![
+///     [0, 0, 100, 0].into(),
+///     [100, 0, 100, 20].into(),
+///     [100, 20, 0, 20].into(),
+///     [0, 20, 0, 0].into(),
+///     [50, 5, 50, 15].into(),
+/// ];
+/// let diagram = Builder::<i32, f64>::default()
+///     .with_segments(segments.iter())?
+///     .build()?;
+/// let planner = PathPlanner::new(&diagram, &points, &segments)?;
+/// assert!(planner.shortest_path([10.0, 10.0], [90.0, 10.0], 2.0, 0.1)?.is_some());
+/// // the gaps around the wall are only 5 units wide
+/// assert!(planner.shortest_path([10.0, 10.0], [90.0, 10.0], 3.0, 0.1)?.is_none());
+/// # Ok::<(), BvError>(())
+/// ```
+pub struct PathPlanner<'a, I: InputType, F: OutputType> {
+    diagram_: &'a VD::Diagram<F>,
+    points_: &'a [Point<I>],
+    segments_: &'a [Line<I>],
+    locator_: PointLocator<I, F>,
+    // indexed by EdgeIndex, `None` for infinite edges
+    weights_: Vec<Option<EdgeWeight<F>>>,
+}
+
+impl<'a, I: InputType, F: OutputType> PathPlanner<'a, I, F> {
+    /// Creates a planner for `diagram`. `points` and `segments` must be the input geometry of
+    /// the `Builder`, in the same order.
+    /// # Errors
+    /// Will return `BvError::IdError` if a cell refers to geometry missing from the input.
+    pub fn new(
+        diagram: &'a VD::Diagram<F>,
+        points: &'a [Point<I>],
+        segments: &'a [Line<I>],
+    ) -> Result<Self, BvError> {
+        let locator = PointLocator::new(diagram, points, segments)?;
+        let mut rv = Self {
+            diagram_: diagram,
+            points_: points,
+            segments_: segments,
+            locator_: locator,
+            weights_: vec![None; diagram.edges().len()],
+        };
+        for edge_id in (0..diagram.edges().len()).map(VD::EdgeIndex) {
+            let twin_id = diagram.edge_get_twin(edge_id)?;
+            if twin_id.0 < edge_id.0 {
+                rv.weights_[edge_id.0] = rv.weights_[twin_id.0];
+            } else {
+                rv.weights_[edge_id.0] = rv.edge_weight_(edge_id)?;
+            }
+        }
+        Ok(rv)
+    }
+
+    /// Returns the shortest path from `start` to `goal` that stays at least `robot_radius` away
+    /// from every input site, or `None` if there is no such path.
+    /// Curved edges are discretized so that the points deviate at most `max_deviation` from the
+    /// parabolic arcs.
+    /// # Errors
+    /// Will return `BvError::ValueError` if `robot_radius` is negative or not finite.
+    pub fn shortest_path(
+        &self,
+        start: [F; 2],
+        goal: [F; 2],
+        robot_radius: F,
+        max_deviation: F,
+    ) -> Result<Option<PlannedPath<F>>, BvError> {
+        if !robot_radius.is_finite() || robot_radius < F::zero() {
+            return Err(BvError::ValueError(format!(
+                "The robot radius must be a non-negative number, got {}",
+                robot_radius
+            )));
+        }
+        // the diagram vertices, followed by the start, the goal and the nodes on the box
+        let start_node = self.diagram_.vertices().len();
+        let goal_node = start_node + 1;
+        let mut roadmap = self.roadmap_(start, goal, robot_radius)?;
+        for (node, q) in self.cell_nodes_(start, &roadmap)? {
+            if let Some(c) = self.connector_clearance_(start, q) {
+                roadmap.links_[start_node].push(Link {
+                    to: node,
+                    length: Self::length_(start, q),
+                    clearance: c,
+                    points: vec![q],
+                });
+            }
+        }
+        for (node, q) in self.cell_nodes_(goal, &roadmap)? {
+            if let Some(c) = self.connector_clearance_(q, goal) {
+                roadmap.links_[node].push(Link {
+                    to: goal_node,
+                    length: Self::length_(q, goal),
+                    clearance: c,
+                    points: vec![goal],
+                });
+            }
+        }
+        if let Some(c) = self.connector_clearance_(start, goal) {
+            roadmap.links_[start_node].push(Link {
+                to: goal_node,
+                length: Self::length_(start, goal),
+                clearance: c,
+                points: vec![goal],
+            });
+        }
+
+        let node_count = roadmap.links_.len();
+        let mut distance = vec![F::infinity(); node_count];
+        let mut clearance = vec![F::infinity(); node_count];
+        let mut previous: Vec<Option<(usize, Step)>> = vec![None; node_count];
+        let mut queue = BinaryHeap::new();
+        distance[start_node] = F::zero();
+        queue.push(QueueEntry {
+            distance: F::zero(),
+            node: start_node,
+        });
+        while let Some(QueueEntry { distance: d, node }) = queue.pop() {
+            if d > distance[node] {
+                continue;
+            }
+            if node == goal_node {
+                break;
+            }
+            let mut relax = |to: usize, length: F, c: F, step: Step| {
+                let d = d + length;
+                if d < distance[to] {
+                    distance[to] = d;
+                    clearance[to] = c.min(clearance[node]);
+                    previous[to] = Some((node, step));
+                    queue.push(QueueEntry {
+                        distance: d,
+                        node: to,
+                    });
+                }
+            };
+            for (i, link) in roadmap.links_[node].iter().enumerate() {
+                if link.clearance >= robot_radius {
+                    relax(link.to, link.length, link.clearance, Step::Link(i));
+                }
+            }
+            if node >= start_node {
+                continue;
+            }
+            let vertex = self.diagram_.vertex_get(VD::VertexIndex(node))?.get();
+            for edge_id in self
+                .diagram_
+                .edge_rot_next_iterator(Some(vertex.get_incident_edge()?))
+            {
+                let weight = match self.weights_[edge_id.0] {
+                    Some(weight) if weight.clearance >= robot_radius => weight,
+                    _ => continue,
+                };
+                if let Some(v1) = self.diagram_.edge_get_vertex1(edge_id)? {
+                    relax(v1.0, weight.length, weight.clearance, Step::Edge(edge_id));
+                }
+            }
+        }
+        if previous[goal_node].is_none() {
+            return Ok(None);
+        }
+        // walk back from the goal
+        let mut steps = Vec::new();
+        let mut node = goal_node;
+        while let Some((from, step)) = previous[node] {
+            steps.push((from, step));
+            node = from;
+        }
+        let mut points = vec![start];
+        for (from, step) in steps.into_iter().rev() {
+            match step {
+                Step::Link(i) => points.extend(roadmap.links_[from][i].points.iter().copied()),
+                Step::Edge(edge_id) => {
+                    let edge_points = self.edge_points_(edge_id, max_deviation)?;
+                    points.extend(edge_points.into_iter().skip(1));
+                }
+            }
+        }
+        Ok(Some(PlannedPath {
+            points,
+            length: distance[goal_node],
+            clearance: clearance[goal_node],
+        }))
+    }
+
+    /// Returns the length and the clearance of a finite edge, `None` for infinite edges.
+    fn edge_weight_(&self, edge_id: VD::EdgeIndex) -> Result<Option<EdgeWeight<F>>, BvError> {
+        let edge = self.diagram_.get_edge(edge_id)?.get();
+        let (v0, v1) = match (edge.vertex0(), self.diagram_.edge_get_vertex1(edge_id)?) {
+            (Some(v0), Some(v1)) => (self.position_(v0)?, self.position_(v1)?),
+            _ => return Ok(None),
+        };
+        if let Some(arc) =
+            ParabolicArc::from_edge(self.diagram_, edge_id, self.points_, self.segments_)?
+        {
+            let length = BoundaryPiece::curve(arc.to_bezier().control_points).length();
+            // the distance to the focus is the smallest at the apex of the parabola
+            let d = arc.directrix;
+            let u = [d[1][0] - d[0][0], d[1][1] - d[0][1]];
+            let along = |p: [F; 2]| (p[0] - d[0][0]) * u[0] + (p[1] - d[0][1]) * u[1];
+            let (a, b, f) = (along(arc.start), along(arc.end), along(arc.focus));
+            let clearance = if a.min(b) <= f && f <= a.max(b) {
+                let cross = u[0] * (arc.focus[1] - d[0][1]) - u[1] * (arc.focus[0] - d[0][0]);
+                cross.abs() / u[0].hypot(u[1]) * cast::<f64, F>(0.5)
+            } else {
+                Self::length_(arc.start, arc.focus).min(Self::length_(arc.end, arc.focus))
+            };
+            return Ok(Some(EdgeWeight { length, clearance }));
+        }
+        let twin_id = self.diagram_.edge_get_twin(edge_id)?;
+        let mut clearance = F::infinity();
+        for cell_id in [edge.cell()?, self.diagram_.edge_get_cell(twin_id)?] {
+            let site = SiteGeometry::of_cell(
+                &self.diagram_.get_cell(cell_id)?.get(),
+                self.points_,
+                self.segments_,
+            )?;
+            clearance = clearance.min(Self::segment_to_site_(v0, v1, &site));
+        }
+        Ok(Some(EdgeWeight {
+            length: Self::length_(v0, v1),
+            clearance,
+        }))
+    }
+
+    /// Clips the infinite edges to a box around the input, the diagram vertices, `start` and
+    /// `goal`, and connects the clipped ends along the sides of the box. The box keeps a margin
+    /// larger than `robot_radius` to every input site.
+    fn roadmap_(
+        &self,
+        start: [F; 2],
+        goal: [F; 2],
+        robot_radius: F,
+    ) -> Result<Roadmap<F>, BvError> {
+        let to_f = |p: &Point<I>| [cast::<I, F>(p.x), cast::<I, F>(p.y)];
+        let (mut lo, mut hi) = (start, start);
+        let sites = self.points_.iter().map(to_f).chain(
+            self.segments_
+                .iter()
+                .flat_map(|s| [to_f(&s.start), to_f(&s.end)]),
+        );
+        let vertices = self.diagram_.vertices().iter().map(|v| {
+            let v = v.get();
+            [v.x(), v.y()]
+        });
+        for p in sites.chain(vertices).chain([goal]) {
+            lo = [lo[0].min(p[0]), lo[1].min(p[1])];
+            hi = [hi[0].max(p[0]), hi[1].max(p[1])];
+        }
+        let margin =
+            robot_radius + (hi[0] - lo[0]).max(hi[1] - lo[1]) * cast::<f64, F>(0.1) + F::one();
+        let (lo, hi) = (
+            [lo[0] - margin, lo[1] - margin],
+            [hi[0] + margin, hi[1] + margin],
+        );
+
+        let first_box_node = self.diagram_.vertices().len() + 2;
+        let edge_count = self.diagram_.edges().len();
+        let mut entries = vec![None; edge_count];
+        let mut box_points = Vec::new();
+        for edge_id in (0..edge_count).map(VD::EdgeIndex) {
+            if self.diagram_.get_edge(edge_id)?.get().vertex0().is_some() {
+                continue;
+            }
+            let (origin, direction) =
+                infinite_edge_line(self.diagram_, edge_id, self.points_, self.segments_)?;
+            let origin = match self.diagram_.edge_get_vertex1(edge_id)? {
+                Some(v1) => self.position_(v1)?,
+                None => origin,
+            };
+            entries[edge_id.0] = Some(first_box_node + box_points.len());
+            box_points.push(Self::box_exit_(
+                origin,
+                [-direction[0], -direction[1]],
+                lo,
+                hi,
+            ));
+        }
+        let mut links = vec![Vec::new(); first_box_node + box_points.len()];
+        let mut link_both_ways = |a: usize, b: usize, points: Vec<[F; 2]>, clearance: F| {
+            let length = points
+                .windows(2)
+                .map(|w| Self::length_(w[0], w[1]))
+                .fold(F::zero(), |sum, l| sum + l);
+            links[a].push(Link {
+                to: b,
+                length,
+                clearance,
+                points: points[1..].to_vec(),
+            });
+            links[b].push(Link {
+                to: a,
+                length,
+                clearance,
+                points: points.iter().rev().skip(1).copied().collect(),
+            });
+        };
+
+        // the clipped infinite edges, these are always straight
+        for edge_id in (0..edge_count).map(VD::EdgeIndex) {
+            let twin_id = self.diagram_.edge_get_twin(edge_id)?;
+            if twin_id.0 < edge_id.0 {
+                continue;
+            }
+            let end = |edge_id: VD::EdgeIndex| -> Result<Option<(usize, [F; 2])>, BvError> {
+                Ok(match self.diagram_.get_edge(edge_id)?.get().vertex0() {
+                    Some(v) => Some((v.0, self.position_(v)?)),
+                    None => {
+                        entries[edge_id.0].map(|node| (node, box_points[node - first_box_node]))
+                    }
+                })
+            };
+            let (a, b) = match (end(edge_id)?, end(twin_id)?) {
+                (Some(a), Some(b)) if a.0 >= first_box_node || b.0 >= first_box_node => (a, b),
+                _ => continue,
+            };
+            let mut clearance = F::infinity();
+            for e in [edge_id, twin_id] {
+                let site = SiteGeometry::of_cell(
+                    &self
+                        .diagram_
+                        .get_cell(self.diagram_.edge_get_cell(e)?)?
+                        .get(),
+                    self.points_,
+                    self.segments_,
+                )?;
+                clearance = clearance.min(Self::segment_to_site_(a.1, b.1, &site));
+            }
+            link_both_ways(a.0, b.0, vec![a.1, b.1], clearance);
+        }
+
+        // the sides of the box, between neighbouring clipped ends
+        let (w, h) = (hi[0] - lo[0], hi[1] - lo[1]);
+        let corners = [
+            (F::zero(), lo),
+            (w, [hi[0], lo[1]]),
+            (w + h, hi),
+            (w + w + h, [lo[0], hi[1]]),
+        ];
+        let perimeter = w + w + h + h;
+        // the counterclockwise distance along the box from its lower left corner
+        let position = |p: [F; 2]| {
+            if p[1] <= lo[1] {
+                p[0] - lo[0]
+            } else if p[0] >= hi[0] {
+                w + p[1] - lo[1]
+            } else if p[1] >= hi[1] {
+                w + h + hi[0] - p[0]
+            } else {
+                w + w + h + hi[1] - p[1]
+            }
+        };
+        let mut order: Vec<usize> = (0..box_points.len()).collect();
+        order.sort_by(|a, b| {
+            position(box_points[*a])
+                .partial_cmp(&position(box_points[*b]))
+                .unwrap_or(Ordering::Equal)
+        });
+        if order.len() > 1 {
+            for (i, a) in order.iter().enumerate() {
+                let b = order[(i + 1) % order.len()];
+                let (pa, pb) = (box_points[*a], box_points[b]);
+                let from = position(pa);
+                let mut to = position(pb);
+                if i + 1 == order.len() {
+                    to = to + perimeter;
+                }
+                let mut points = vec![pa];
+                for turn in [F::zero(), perimeter] {
+                    for (at, corner) in corners.iter() {
+                        if from < *at + turn && *at + turn < to {
+                            points.push(*corner);
+                        }
+                    }
+                }
+                points.push(pb);
+                let clearance = points
+                    .windows(2)
+                    .filter_map(|w| self.connector_clearance_(w[0], w[1]))
+                    .fold(F::infinity(), F::min);
+                link_both_ways(*a + first_box_node, b + first_box_node, points, clearance);
+            }
+        }
+        Ok(Roadmap {
+            entries_: entries,
+            box_points_: box_points,
+            links_: links,
+        })
+    }
+
+    /// Returns the point where the ray from `origin` in `direction` leaves the box `lo`-`hi`.
+    /// `origin` must be inside the box.
+    fn box_exit_(origin: [F; 2], direction: [F; 2], lo: [F; 2], hi: [F; 2]) -> [F; 2] {
+        let t = |o: F, d: F, lo: F, hi: F| {
+            if d > F::zero() {
+                (hi - o) / d
+            } else if d < F::zero() {
+                (lo - o) / d
+            } else {
+                F::infinity()
+            }
+        };
+        let tx = t(origin[0], direction[0], lo[0], hi[0]);
+        let ty = t(origin[1], direction[1], lo[1], hi[1]);
+        if tx <= ty {
+            let x = if direction[0] > F::zero() {
+                hi[0]
+            } else {
+                lo[0]
+            };
+            [x, (origin[1] + tx * direction[1]).max(lo[1]).min(hi[1])]
+        } else {
+            let y = if direction[1] > F::zero() {
+                hi[1]
+            } else {
+                lo[1]
+            };
+            [(origin[0] + ty * direction[0]).max(lo[0]).min(hi[0]), y]
+        }
+    }
+
+    /// Returns the nodes on the boundary of the cell containing `p`, with their positions.
+    fn cell_nodes_(
+        &self,
+        p: [F; 2],
+        roadmap: &Roadmap<F>,
+    ) -> Result<Vec<(usize, [F; 2])>, BvError> {
+        let mut rv = Vec::new();
+        let cell_id = match self.locator_.locate(p[0], p[1]) {
+            Some(cell_id) => cell_id,
+            None => return Ok(rv),
+        };
+        let first_box_node = self.diagram_.vertices().len() + 2;
+        let entry = |edge_id: VD::EdgeIndex| {
+            roadmap.entries_[edge_id.0]
+                .map(|node| (node, roadmap.box_points_[node - first_box_node]))
+        };
+        for edge_id in self.diagram_.cell_edge_iterator(cell_id) {
+            match self.diagram_.get_edge(edge_id)?.get().vertex0() {
+                Some(v) => rv.push((v.0, self.position_(v)?)),
+                None => rv.extend(entry(edge_id)),
+            }
+            // an edge without vertex1 ends on the box where its twin comes in
+            if self.diagram_.edge_get_vertex1(edge_id)?.is_none() {
+                rv.extend(entry(self.diagram_.edge_get_twin(edge_id)?));
+            }
+        }
+        Ok(rv)
+    }
+
+    /// The smallest distance between the line `a`-`b` and any input site, `None` if there are
+    /// no sites.
+    fn connector_clearance_(&self, a: [F; 2], b: [F; 2]) -> Option<F> {
+        self.points_
+            .iter()
+            .map(|p| SiteGeometry::Point(*p))
+            .chain(self.segments_.iter().map(|s| SiteGeometry::Segment(*s)))
+            .map(|site| Self::segment_to_site_(a, b, &site))
+            .reduce(F::min)
+    }
+
+    /// The smallest distance between the line `a`-`b` and a site
+    fn segment_to_site_(a: [F; 2], b: [F; 2], site: &SiteGeometry<I>) -> F {
+        let to_f = |p: &Point<I>| [cast::<I, F>(p.x), cast::<I, F>(p.y)];
+        let (c, d) = match site {
+            SiteGeometry::Point(p) => (to_f(p), to_f(p)),
+            SiteGeometry::Segment(s) => (to_f(&s.start), to_f(&s.end)),
+        };
+        let orientation = |p: [F; 2], q: [F; 2], r: [F; 2]| {
+            (q[0] - p[0]) * (r[1] - p[1]) - (q[1] - p[1]) * (r[0] - p[0])
+        };
+        if orientation(a, b, c) * orientation(a, b, d) < F::zero()
+            && orientation(c, d, a) * orientation(c, d, b) < F::zero()
+        {
+            return F::zero();
+        }
+        Self::point_to_segment_(a, c, d)
+            .min(Self::point_to_segment_(b, c, d))
+            .min(Self::point_to_segment_(c, a, b))
+            .min(Self::point_to_segment_(d, a, b))
+    }
+
+    /// The distance from `p` to the closest point of the line `a`-`b`
+    fn point_to_segment_(p: [F; 2], a: [F; 2], b: [F; 2]) -> F {
+        let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+        let length = dx * dx + dy * dy;
+        let t = if length > F::zero() {
+            (((p[0] - a[0]) * dx + (p[1] - a[1]) * dy) / length)
+                .max(F::zero())
+                .min(F::one())
+        } else {
+            F::zero()
+        };
+        (p[0] - a[0] - t * dx).hypot(p[1] - a[1] - t * dy)
+    }
+
+    #[inline]
+    fn length_(a: [F; 2], b: [F; 2]) -> F {
+        (b[0] - a[0]).hypot(b[1] - a[1])
+    }
+
+    fn position_(&self, vertex_id: VD::VertexIndex) -> Result<[F; 2], BvError> {
+        let v = self.diagram_.vertex_get(vertex_id)?.get();
+        Ok([v.x(), v.y()])
+    }
+
+    /// The points of a finite edge from vertex0 to vertex1, curved edges are discretized
+    fn edge_points_(
+        &self,
+        edge_id: VD::EdgeIndex,
+        max_deviation: F,
+    ) -> Result<Vec<[F; 2]>, BvError> {
+        let edge = self.diagram_.get_edge(edge_id)?.get();
+        let v1 = self.diagram_.edge_get_vertex1(edge_id)?;
+        let mut points = match (edge.vertex0(), v1) {
+            (Some(v0), Some(v1)) => vec![self.position_(v0)?, self.position_(v1)?],
+            _ => {
+                return Err(BvError::InternalError(format!(
+                    "The path contains the infinite edge {}",
+                    edge_id.0
+                )))
+            }
+        };
+        if edge.is_curved() {
+            let twin_id = self.diagram_.edge_get_twin(edge_id)?;
+            let site = |cell_id| -> Result<SiteGeometry<I>, BvError> {
+                SiteGeometry::of_cell(
+                    &self.diagram_.get_cell(cell_id)?.get(),
+                    self.points_,
+                    self.segments_,
+                )
+            };
+            match (
+                site(edge.cell()?)?,
+                site(self.diagram_.edge_get_cell(twin_id)?)?,
+            ) {
+                (SiteGeometry::Point(point), SiteGeometry::Segment(segment))
+                | (SiteGeometry::Segment(segment), SiteGeometry::Point(point)) => {
+                    VoronoiVisualUtils::discretize(
+                        &point,
+                        &segment,
+                        max_deviation,
+                        &SimpleAffine::default(),
+                        &mut points,
+                    );
+                }
+                _ => (),
+            }
+        }
+        Ok(points)
+    }
+}
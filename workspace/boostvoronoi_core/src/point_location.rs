@@ -0,0 +1,364 @@
+//          Copyright Eadf (github.com/eadf) 2021.
+// Distributed under the Boost Software License, Version 1.0.
+//    (See accompanying file LICENSE_1_0.txt or copy at
+//          http://www.boost.org/LICENSE_1_0.txt)
+
+//! Point location: finds the voronoi cell, i.e. the nearest input site, of an arbitrary point.
+//!
+//! The [`PointLocator`] keeps the cell adjacency of a built diagram together with the input
+//! geometry. A query starts at a cell picked from a coarse bucket grid and walks towards the
+//! query point over neighbouring cells, always moving to a site closer to the query point.
+//! The distance to a segment site is the distance to the closest point of the segment, so
+//! parabolic edges and segment cells are handled without looking at the edge geometry.
+//! For reasonably distributed input a query visits an expected constant number of cells.
+
+use crate::diagram::{self as VD, SiteGeometry};
+use crate::geometry::{Line, Point};
+use crate::sync_diagram as SD;
+use crate::{cast, BvError, InputType, OutputType};
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+
+/// The input site of a cell, in f64 coordinates
+#[derive(Debug, Copy, Clone)]
+enum Site {
+    Point([f64; 2]),
+    Segment([f64; 2], [f64; 2]),
+}
+
+impl Site {
+    /// Returns the squared distance to the site, and a rank that makes point sites win when a
+    /// segment site is equally close (i.e. the query point is beyond the end of the segment).
+    #[inline]
+    fn key(&self, q: [f64; 2]) -> (f64, u8) {
+        match *self {
+            Site::Point(p) => (distance_squared(q, p), 0),
+            Site::Segment(a, b) => {
+                let ab = [b[0] - a[0], b[1] - a[1]];
+                let t = (q[0] - a[0]) * ab[0] + (q[1] - a[1]) * ab[1];
+                let len = ab[0] * ab[0] + ab[1] * ab[1];
+                let closest = if t <= 0.0 {
+                    a
+                } else if t >= len {
+                    b
+                } else {
+                    let t = t / len;
+                    [a[0] + t * ab[0], a[1] + t * ab[1]]
+                };
+                (distance_squared(q, closest), 1)
+            }
+        }
+    }
+
+    /// A point representing the site in the bucket grid
+    #[inline]
+    fn representative(&self) -> [f64; 2] {
+        match *self {
+            Site::Point(p) => p,
+            Site::Segment(a, b) => [(a[0] + b[0]) * 0.5, (a[1] + b[1]) * 0.5],
+        }
+    }
+}
+
+#[inline(always)]
+fn distance_squared(a: [f64; 2], b: [f64; 2]) -> f64 {
+    let dx = a[0] - b[0];
+    let dy = a[1] - b[1];
+    dx * dx + dy * dy
+}
+
+#[inline(always)]
+fn compare_keys(a: (f64, u8), b: (f64, u8)) -> Ordering {
+    a.0.total_cmp(&b.0).then(a.1.cmp(&b.1))
+}
+
+/// Answers "which cell contains the point (x, y)?", i.e. nearest input site queries, for a
+/// built `Diagram` or `SyncDiagram`.
+///
+/// The locator copies what it needs, it does not borrow the diagram. It keeps scratch buffers
+/// for the queries, so it is not `Sync`: clone it to query from several threads.
+/// ```
+/// # use boostvoronoi_core::builder::Builder;
+/// # use boostvoronoi_core::geometry::{Line, Point};
+/// # use boostvoronoi_core::point_location::PointLocator;
+/// # use boostvoronoi_core::BvError;
+/// let points: Vec<Point<i32>> = vec![[0, 0].into(), [10, 10].into()];
+/// let segments: Vec<Line<i32>> = vec![[0, 20, 20, 20].into()];
+/// let diagram = Builder::<i32, f64>::default()
+///     .with_vertices(points.iter())?
+///     .with_segments(segments.iter())?
+///     .build()?;
+/// let locator = PointLocator::new(&diagram, &points, &segments)?;
+/// let cell = locator.locate(9.0, 12.0).unwrap();
+/// assert_eq!(diagram.get_cell(cell)?.get().source_index(), 1);
+/// let cell = locator.locate(5.0, 19.0).unwrap();
+/// assert_eq!(diagram.get_cell(cell)?.get().source_index(), 2);
+/// # Ok::<(), BvError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct PointLocator<I: InputType, F: OutputType> {
+    // indexed by CellIndex
+    sites_: Vec<Site>,
+    // the neighbours of cell `c` are `neighbours_[first_neighbour_[c]..first_neighbour_[c+1]]`
+    first_neighbour_: Vec<usize>,
+    neighbours_: Vec<usize>,
+    // a start cell for the walk, for each bucket of the grid
+    buckets_: Vec<usize>,
+    grid_min_: [f64; 2],
+    grid_step_: [f64; 2],
+    grid_size_: usize,
+    // the cells already searched, and the cells left to search, when the walk reaches cells at
+    // the same distance. Reused between queries.
+    visited_: RefCell<ahash::AHashSet<usize>>,
+    stack_: RefCell<Vec<usize>>,
+    pd_: PhantomData<(I, F)>,
+}
+
+impl<I: InputType, F: OutputType> PointLocator<I, F> {
+    /// Builds a locator for `diagram`. `points` and `segments` must be the input geometry of the
+    /// `Builder`, in the same order. Polygons and polylines count as their segments, in order.
+    /// # Errors
+    /// Will return `BvError::IdError` if a cell refers to geometry missing from the input.
+    pub fn new(
+        diagram: &VD::Diagram<F>,
+        points: &[Point<I>],
+        segments: &[Line<I>],
+    ) -> Result<Self, BvError> {
+        let edges: Vec<VD::Edge> = diagram.edges().iter().map(|e| e.get()).collect();
+        Self::new_(
+            diagram.cells().iter().map(|c| c.get()),
+            &edges,
+            points,
+            segments,
+        )
+    }
+
+    /// Builds a locator for a `SyncDiagram`, see `PointLocator::new()`.
+    pub fn new_sync(
+        diagram: &SD::SyncDiagram<F>,
+        points: &[Point<I>],
+        segments: &[Line<I>],
+    ) -> Result<Self, BvError> {
+        Self::new_(
+            diagram.cells().iter().copied(),
+            diagram.edges(),
+            points,
+            segments,
+        )
+    }
+
+    fn new_(
+        cells: impl Iterator<Item = VD::Cell>,
+        edges: &[VD::Edge],
+        points: &[Point<I>],
+        segments: &[Line<I>],
+    ) -> Result<Self, BvError> {
+        let to_f64 = |p: Point<I>| [cast::<I, f64>(p.x), cast::<I, f64>(p.y)];
+        let mut sites = Vec::<Site>::new();
+        // cells without edges can't be reached by the walk
+        let mut reachable = Vec::<bool>::new();
+        for cell in cells {
+            sites.push(match SiteGeometry::of_cell(&cell, points, segments)? {
+                SiteGeometry::Point(p) => Site::Point(to_f64(p)),
+                SiteGeometry::Segment(l) => Site::Segment(to_f64(l.start), to_f64(l.end)),
+            });
+            reachable.push(!cell.is_degenerate());
+        }
+
+        // The neighbouring cells, in compressed row format
+        let mut first_neighbour = vec![0_usize; sites.len() + 1];
+        for edge in edges.iter() {
+            first_neighbour[edge.cell()?.0 + 1] += 1;
+        }
+        for i in 1..first_neighbour.len() {
+            first_neighbour[i] += first_neighbour[i - 1];
+        }
+        let mut neighbours = vec![0_usize; edges.len()];
+        let mut fill = first_neighbour.clone();
+        for edge in edges.iter() {
+            let cell = edge.cell()?.0;
+            let twin = edges
+                .get(edge.twin()?.0)
+                .ok_or_else(|| BvError::IdError(format!("Edge {} has no twin", edge.id().0)))?;
+            neighbours[fill[cell]] = twin.cell()?.0;
+            fill[cell] += 1;
+        }
+
+        let mut rv = Self {
+            sites_: sites,
+            first_neighbour_: first_neighbour,
+            neighbours_: neighbours,
+            buckets_: Vec::new(),
+            grid_min_: [0.0, 0.0],
+            grid_step_: [1.0, 1.0],
+            grid_size_: 0,
+            visited_: RefCell::default(),
+            stack_: RefCell::default(),
+            pd_: PhantomData,
+        };
+        rv.init_grid_(&reachable);
+        Ok(rv)
+    }
+
+    /// Fills the bucket grid with start cells. Empty buckets are given the cell of the nearest
+    /// (in grid steps) non-empty bucket.
+    fn init_grid_(&mut self, reachable: &[bool]) {
+        let num_sites = self.sites_.len();
+        if num_sites == 0 {
+            return;
+        }
+        let mut min = [f64::MAX, f64::MAX];
+        let mut max = [f64::MIN, f64::MIN];
+        for site in self.sites_.iter() {
+            let p = site.representative();
+            for i in 0..2 {
+                min[i] = min[i].min(p[i]);
+                max[i] = max[i].max(p[i]);
+            }
+        }
+        // around two sites per bucket
+        let size = ((num_sites as f64 * 0.5).sqrt().ceil() as usize).max(1);
+        self.grid_min_ = min;
+        for i in 0..2 {
+            let extent = max[i] - min[i];
+            self.grid_step_[i] = if extent > 0.0 {
+                extent / size as f64
+            } else {
+                1.0
+            };
+        }
+        self.grid_size_ = size;
+
+        let mut buckets = vec![usize::MAX; size * size];
+        let mut queue = VecDeque::<usize>::new();
+        for (cell, site) in self.sites_.iter().enumerate() {
+            if !reachable[cell] && num_sites > 1 {
+                continue;
+            }
+            let bucket = self.bucket_(site.representative());
+            if buckets[bucket] == usize::MAX {
+                buckets[bucket] = cell;
+                queue.push_back(bucket);
+            }
+        }
+        if queue.is_empty() {
+            // only unreachable cells, the walk will not move anyway
+            buckets.fill(0);
+        }
+        while let Some(bucket) = queue.pop_front() {
+            let (col, row) = (bucket % size, bucket / size);
+            let cell = buckets[bucket];
+            let mut visit = |b: usize| {
+                if buckets[b] == usize::MAX {
+                    buckets[b] = cell;
+                    queue.push_back(b);
+                }
+            };
+            if col > 0 {
+                visit(bucket - 1);
+            }
+            if col + 1 < size {
+                visit(bucket + 1);
+            }
+            if row > 0 {
+                visit(bucket - size);
+            }
+            if row + 1 < size {
+                visit(bucket + size);
+            }
+        }
+        self.buckets_ = buckets;
+    }
+
+    /// Returns the bucket of a point, points outside the grid are clamped to it
+    #[inline]
+    fn bucket_(&self, q: [f64; 2]) -> usize {
+        let max = (self.grid_size_ - 1) as f64;
+        let col = ((q[0] - self.grid_min_[0]) / self.grid_step_[0]).clamp(0.0, max) as usize;
+        let row = ((q[1] - self.grid_min_[1]) / self.grid_step_[1]).clamp(0.0, max) as usize;
+        row * self.grid_size_ + col
+    }
+
+    #[inline]
+    fn neighbours_(&self, cell: usize) -> &[usize] {
+        &self.neighbours_[self.first_neighbour_[cell]..self.first_neighbour_[cell + 1]]
+    }
+
+    /// Returns the cell containing the point `(x, y)`, i.e. the cell of the input site nearest
+    /// to the point. Points on the boundary between cells may return either cell.
+    /// Returns `None` if the diagram is empty.
+    pub fn locate(&self, x: F, y: F) -> Option<VD::CellIndex> {
+        if self.sites_.is_empty() {
+            return None;
+        }
+        let q = [cast::<F, f64>(x), cast::<F, f64>(y)];
+        Some(VD::CellIndex(self.walk_(self.buckets_[self.bucket_(q)], q)))
+    }
+
+    /// Returns the cell containing the point `(x, y)`, starting the search at `hint`.
+    /// This is faster than `locate()` when the queries are coherent, e.g. when following a path,
+    /// and the previous result is used as the hint.
+    /// Returns `None` if the diagram is empty or if `hint` is not a cell of the diagram.
+    pub fn locate_from(&self, hint: VD::CellIndex, x: F, y: F) -> Option<VD::CellIndex> {
+        if hint.0 >= self.sites_.len() {
+            return None;
+        }
+        let q = [cast::<F, f64>(x), cast::<F, f64>(y)];
+        Some(VD::CellIndex(self.walk_(hint.0, q)))
+    }
+
+    /// Walks from `start` towards the cell containing `q`.
+    fn walk_(&self, start: usize, q: [f64; 2]) -> usize {
+        let mut current = start;
+        let mut current_key = self.sites_[current].key(q);
+        'walk: loop {
+            // move to the neighbour closest to q, as long as it is closer than the current site
+            let mut best = current;
+            let mut best_key = current_key;
+            for &n in self.neighbours_(current) {
+                let key = self.sites_[n].key(q);
+                if compare_keys(key, best_key) == Ordering::Less {
+                    best = n;
+                    best_key = key;
+                }
+            }
+            if best != current {
+                current = best;
+                current_key = best_key;
+                continue;
+            }
+            // No neighbour is closer. Sites sharing an endpoint can be at exactly the same
+            // distance, so search the cells at the same distance for a way forward.
+            let mut visited = self.visited_.borrow_mut();
+            visited.clear();
+            let _ = visited.insert(current);
+            let mut stack = self.stack_.borrow_mut();
+            stack.clear();
+            stack.push(current);
+            while let Some(c) = stack.pop() {
+                for &n in self.neighbours_(c) {
+                    if !visited.insert(n) {
+                        continue;
+                    }
+                    let key = self.sites_[n].key(q);
+                    if compare_keys(key, current_key) == Ordering::Less {
+                        current = n;
+                        current_key = key;
+                        continue 'walk;
+                    }
+                    if key.0 == current_key.0 {
+                        stack.push(n);
+                    }
+                }
+            }
+            return current;
+        }
+    }
+
+    /// Returns the number of cells of the diagram
+    pub fn num_cells(&self) -> usize {
+        self.sites_.len()
+    }
+}
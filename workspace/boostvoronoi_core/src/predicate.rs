@@ -91,9 +91,10 @@ pub(crate) mod orientation_predicate {
     use crate::geometry::Point;
     use crate::predicate::robust_cross_product;
     use crate::{cast, InputType};
+    use boostvoronoi_ext::extended_int::ExtendedInt;
     use num_traits::Zero;
 
-    #[derive(Debug, PartialEq, Eq)]
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
     pub(crate) enum Orientation {
         Right,     // = -1,
         Collinear, // = 0,
@@ -127,6 +128,27 @@ pub(crate) mod orientation_predicate {
         eval_f(cp)
     }
 
+    /// Same as `eval_p()` but the determinant is evaluated with `ExtendedInt`, so the result
+    /// is exact for the full range of the input type.
+    pub(crate) fn eval_p_exact<I: InputType>(
+        point1: Point<I>,
+        point2: Point<I>,
+        point3: Point<I>,
+    ) -> Orientation {
+        let dx1 = ExtendedInt::from(point1.x) - ExtendedInt::from(point2.x);
+        let dx2 = ExtendedInt::from(point2.x) - ExtendedInt::from(point3.x);
+        let dy1 = ExtendedInt::from(point1.y) - ExtendedInt::from(point2.y);
+        let dy2 = ExtendedInt::from(point2.y) - ExtendedInt::from(point3.y);
+        let cp = dx1 * dy2 - dy1 * dx2;
+        if cp.is_zero() {
+            Orientation::Collinear
+        } else if cp.is_neg() {
+            Orientation::Right
+        } else {
+            Orientation::Left
+        }
+    }
+
     #[inline(always)]
     pub(crate) fn eval_i(dif_x1: i64, dif_y1: i64, dif_x2: i64, dif_y2: i64) -> Orientation {
         eval_f(robust_cross_product::<i64, f64>(
@@ -667,7 +689,7 @@ pub(crate) mod node_comparison_predicate {
             }
             return (node.left_site().y1(), 1);
         }
-        return (node.right_site().y0(), -1);
+        (node.right_site().y0(), -1)
     }
 }
 
@@ -52,21 +52,26 @@ impl<I: InputType> Site<I> {
 ///   1) point site
 ///   2) start-point of the segment site
 ///   3) endpoint of the segment site
+///
 /// Implicit segment direction is defined: the start-point of
 /// the segment compares less than its endpoint.
+///
 /// Each input segment is divided onto two site events:
 ///   1) One going from the start-point to the endpoint
 ///      (is_inverse() = false)
 ///   2) Another going from the endpoint to the start-point
 ///      (is_inverse() = true)
+///
 /// In beach line data structure segment sites of the first
 /// type precede sites of the second type for the same segment.
+///
 /// Members:
 ///   `point0_` - point site or segment's start-point
 ///   `point1_` - segment's endpoint if site is a segment
 ///   `sorted_index_` - the last bit encodes information if the site is inverse;
-///                     the other VS::Bits encode site event index among the sorted site events
+///     the other VS::Bits encode site event index among the sorted site events
 ///   `initial_index_` - site index among the initial input set
+///
 /// Note: for all sites `is_inverse_` flag is set to `false` by default.
 #[derive(Copy, Clone)]
 pub struct SiteEvent<I: InputType, F: OutputType> {
@@ -102,7 +107,7 @@ impl<I: InputType, F: OutputType> fmt::Debug for SiteEvent<I, F> {
     }
 }
 
-#[allow(clippy::incorrect_partial_ord_impl_on_ord_type)]
+#[allow(clippy::non_canonical_partial_ord_impl)]
 impl<I: InputType, F: OutputType> PartialOrd for SiteEvent<I, F> {
     #[inline]
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
@@ -308,6 +313,11 @@ impl<I: InputType, F: OutputType> SiteEvent<I, F> {
         self.initial_index_
     }
 
+    #[inline(always)]
+    pub(crate) fn set_initial_index(&mut self, index: SiteEventIndexType) {
+        self.initial_index_ = index;
+    }
+
     // todo this looks suspicious
     pub(crate) fn is_inverse(&self) -> bool {
         (self.flags_ & VD::ColorBits::IS_INVERSE__BIT.0) != 0
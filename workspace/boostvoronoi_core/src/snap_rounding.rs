@@ -0,0 +1,294 @@
+//          Copyright Eadf (github.com/eadf) 2021.
+// Distributed under the Boost Software License, Version 1.0.
+//    (See accompanying file LICENSE_1_0.txt or copy at
+//          http://www.boost.org/LICENSE_1_0.txt)
+
+//! Snap rounding of intersecting integer segments.
+//!
+//! The sweep-line algorithm requires that input segments only meet at their endpoints.
+//! [`snap_round()`] converts an arbitrary set of integer segments into a set that honours that
+//! contract. All the intersections are computed exactly, and every intersection and endpoint
+//! defines a 'hot pixel': the unit square centered on the nearest integer coordinate. Each input
+//! segment is then replaced by the polyline connecting the centers of the hot pixels it passes
+//! through (Hobby's snap rounding). The resulting fragments only meet at their endpoints.
+
+use crate::geometry::{Line, Point};
+use crate::validation::{segment_intersection, SegmentIntersection};
+use crate::{cast, InputType};
+use boostvoronoi_ext::extended_int::ExtendedInt;
+use std::cmp::Ordering;
+
+#[cfg(test)]
+mod tests;
+
+/// The result of [`snap_round()`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapRounded<I: InputType> {
+    /// The non-intersecting output segments. Zero length input segments are kept as they are.
+    pub segments: Vec<Line<I>>,
+    /// For each output segment: the index of the input segment it originates from.
+    /// If several input segments snapped onto the same fragment, the lowest index is reported.
+    pub source_segment: Vec<usize>,
+}
+
+/// A rational number `num/den` with `den > 0`, used as a bound on the segment parameter.
+/// `open` is true if the bound itself is excluded from the interval.
+struct Bound {
+    num: ExtendedInt,
+    den: ExtendedInt,
+    open: bool,
+}
+
+impl Bound {
+    fn closed(num: i32) -> Self {
+        Self {
+            num: ExtendedInt::from(num),
+            den: ExtendedInt::from(1_i32),
+            open: false,
+        }
+    }
+
+    /// exact comparison of the rational values, the `open` flag is ignored
+    fn cmp_value(&self, other: &Self) -> Ordering {
+        let d = &self.num * &other.den - &other.num * &self.den;
+        if d.is_zero() {
+            Ordering::Equal
+        } else if d.is_neg() {
+            Ordering::Less
+        } else {
+            Ordering::Greater
+        }
+    }
+}
+
+/// Tightens the parameter interval `[lo, hi]` of the segment `p + t*d` so that the coordinate
+/// stays inside the half open interval `[l, r)`. Returns false if that is impossible.
+fn clip_axis(
+    lo: &mut Bound,
+    hi: &mut Bound,
+    p: ExtendedInt,
+    d: ExtendedInt,
+    l: ExtendedInt,
+    r: ExtendedInt,
+) -> bool {
+    let (new_lo, new_hi) = if d.is_zero() {
+        return !(p.clone() - &l).is_neg() && (p - &r).is_neg();
+    } else if d.is_pos() {
+        (
+            Bound {
+                num: l - &p,
+                den: d.clone(),
+                open: false,
+            },
+            Bound {
+                num: r - &p,
+                den: d,
+                open: true,
+            },
+        )
+    } else {
+        (
+            Bound {
+                num: p.clone() - &r,
+                den: -d.clone(),
+                open: true,
+            },
+            Bound {
+                num: p - &l,
+                den: -d,
+                open: false,
+            },
+        )
+    };
+    match new_lo.cmp_value(lo) {
+        Ordering::Greater => *lo = new_lo,
+        Ordering::Equal => lo.open |= new_lo.open,
+        Ordering::Less => (),
+    }
+    match new_hi.cmp_value(hi) {
+        Ordering::Less => *hi = new_hi,
+        Ordering::Equal => hi.open |= new_hi.open,
+        Ordering::Greater => (),
+    }
+    true
+}
+
+/// If the segment passes through the hot pixel centered at `c`, return the segment parameter
+/// where it enters the pixel. The pixel is the half open square `[c-1/2, c+1/2)`, all the
+/// arithmetic is done with doubled coordinates so that it stays exact.
+fn pixel_entry<I: InputType>(line: &Line<I>, c: Point<I>) -> Option<Bound> {
+    let two = |v: I| ExtendedInt::from(v) * 2;
+    let one = || ExtendedInt::from(1_i32);
+    let mut lo = Bound::closed(0);
+    let mut hi = Bound::closed(1);
+    if !clip_axis(
+        &mut lo,
+        &mut hi,
+        two(line.start.x),
+        two(line.end.x) - two(line.start.x),
+        two(c.x) - one(),
+        two(c.x) + one(),
+    ) || !clip_axis(
+        &mut lo,
+        &mut hi,
+        two(line.start.y),
+        two(line.end.y) - two(line.start.y),
+        two(c.y) - one(),
+        two(c.y) + one(),
+    ) {
+        return None;
+    }
+    match lo.cmp_value(&hi) {
+        Ordering::Less => Some(lo),
+        Ordering::Equal if !lo.open && !hi.open => Some(lo),
+        _ => None,
+    }
+}
+
+/// Returns `round(n/d)`, rounding halves upwards. I.e. the `c` satisfying `c-1/2 <= n/d < c+1/2`.
+/// The value is estimated with floats and then corrected using exact arithmetic.
+fn round_div(n: ExtendedInt, d: ExtendedInt) -> i64 {
+    let (n, d) = if d.is_neg() { (-n, -d) } else { (n, d) };
+    // c = floor((2n + d) / 2d)
+    let m = n * 2 + &d;
+    let d = d * 2;
+    let mut c = (m.d() / d.d()).floor() as i64;
+    let residual = m.clone() - &(ExtendedInt::from(c) * &d);
+    c += (residual.d() / d.d()).floor() as i64;
+    loop {
+        let residual = m.clone() - &(ExtendedInt::from(c) * &d);
+        if residual.is_neg() {
+            c -= 1;
+        } else if !(residual - &d).is_neg() {
+            c += 1;
+        } else {
+            return c;
+        }
+    }
+}
+
+/// The hot pixel of the intersection point of two non-parallel segments.
+fn intersection_pixel<I: InputType>(a: &Line<I>, b: &Line<I>) -> Point<I> {
+    let e = |v: I| ExtendedInt::from(v);
+    let dax = e(a.end.x) - e(a.start.x);
+    let day = e(a.end.y) - e(a.start.y);
+    let dbx = e(b.end.x) - e(b.start.x);
+    let dby = e(b.end.y) - e(b.start.y);
+    // a.start + t*da == b.start + u*db, t = num/den
+    let den = &dax * &dby - &day * &dbx;
+    let num = (e(b.start.x) - e(a.start.x)) * &dby - (e(b.start.y) - e(a.start.y)) * &dbx;
+    let x = round_div(e(a.start.x) * &den + &(num.clone() * &dax), den.clone());
+    let y = round_div(e(a.start.y) * &den + &(num * &day), den);
+    Point::new(cast::<i64, I>(x), cast::<i64, I>(y))
+}
+
+/// Snap rounds a set of integer segments into a set of segments that only intersect at their
+/// endpoints, suitable as input for [`crate::builder::Builder::with_segments()`].
+///
+/// Every output segment remembers which input segment it came from, so that the
+/// `source_index()` of a cell can be traced back to the original geometry.
+/// Note that the output segments may move up to half a unit away from the original
+/// geometry, that is the price of staying on the integer grid.
+/// ```
+/// # use boostvoronoi_core::geometry::Line;
+/// # use boostvoronoi_core::snap_rounding::snap_round;
+/// // Two crossing segments
+/// let s = vec![Line::from([0_i32, 0, 10, 10]), Line::from([0, 10, 10, 0])];
+/// let rounded = snap_round(s.iter());
+/// // are split into four pieces meeting at (5,5)
+/// assert_eq!(rounded.segments.len(), 4);
+/// assert_eq!(rounded.source_segment, vec![0, 0, 1, 1]);
+/// ```
+pub fn snap_round<I, T, IT>(segments: T) -> SnapRounded<I>
+where
+    I: InputType,
+    T: IntoIterator<Item = IT>,
+    IT: Copy + Into<Line<I>>,
+{
+    let input: Vec<Line<I>> = segments.into_iter().map(|s| s.into()).collect();
+    let min_x = |l: &Line<I>| l.start.x.min(l.end.x);
+    let max_x = |l: &Line<I>| l.start.x.max(l.end.x);
+    let point_cmp = |a: &Point<I>, b: &Point<I>| a.x.cmp(&b.x).then(a.y.cmp(&b.y));
+
+    // Collect the hot pixels: all the endpoints and all the intersections
+    let mut hot_pixels: Vec<Point<I>> = input.iter().flat_map(|l| [l.start, l.end]).collect();
+    let mut sorted: Vec<usize> = (0..input.len()).collect();
+    sorted.sort_by_key(|i| min_x(&input[*i]));
+    let mut active: Vec<usize> = Vec::new();
+    for i in sorted {
+        let l = &input[i];
+        active.retain(|a| max_x(&input[*a]) >= min_x(l));
+        if l.start != l.end {
+            for a in active.iter() {
+                if segment_intersection(&input[*a], l) == SegmentIntersection::Intersecting {
+                    hot_pixels.push(intersection_pixel(&input[*a], l));
+                }
+            }
+            active.push(i);
+        }
+    }
+    hot_pixels.sort_by(point_cmp);
+    hot_pixels.dedup();
+
+    // Reroute each segment through the centers of the hot pixels it passes through
+    let mut fragments: Vec<(Point<I>, Point<I>, usize)> = Vec::new();
+    for (i, l) in input.iter().enumerate() {
+        if l.start == l.end {
+            fragments.push((l.start, l.end, i));
+            continue;
+        }
+        // only pixels within the x-extent (plus one) can be hit, at the limits of the input
+        // type the extent can't be extended
+        let first = match min_x(l).checked_sub(&I::one()) {
+            Some(x) => hot_pixels.partition_point(|p| p.x < x),
+            None => 0,
+        };
+        let last = max_x(l).checked_add(&I::one());
+        let mut hits: Vec<(Bound, Point<I>)> = hot_pixels[first..]
+            .iter()
+            .take_while(|p| last.is_none_or(|x| p.x <= x))
+            .filter_map(|p| pixel_entry(l, *p).map(|b| (b, *p)))
+            .collect();
+        hits.sort_by(|a, b| a.0.cmp_value(&b.0).then_with(|| point_cmp(&a.1, &b.1)));
+        for w in hits.windows(2) {
+            fragments.push((w[0].1, w[1].1, i));
+        }
+    }
+
+    // Overlapping fragments collapse into one, the lowest source index is kept
+    let normalized = |(a, b, i): (Point<I>, Point<I>, usize)| {
+        if point_cmp(&a, &b) == Ordering::Greater {
+            (b, a, i)
+        } else {
+            (a, b, i)
+        }
+    };
+    let mut keys: Vec<_> = fragments.iter().copied().map(normalized).collect();
+    keys.sort_by(|a, b| {
+        point_cmp(&a.0, &b.0)
+            .then_with(|| point_cmp(&a.1, &b.1))
+            .then(a.2.cmp(&b.2))
+    });
+    keys.dedup_by(|a, b| a.0 == b.0 && a.1 == b.1);
+    let mut emitted = vec![false; keys.len()];
+    let mut result = SnapRounded {
+        segments: Vec::with_capacity(keys.len()),
+        source_segment: Vec::with_capacity(keys.len()),
+    };
+    // Keep the output in input order, with the original direction of each fragment
+    for (a, b, i) in fragments {
+        let (na, nb, _) = normalized((a, b, i));
+        if let Ok(k) = keys.binary_search_by(|k| {
+            point_cmp(&k.0, &na)
+                .then_with(|| point_cmp(&k.1, &nb))
+                .then(k.2.cmp(&i))
+        }) {
+            if !emitted[k] {
+                emitted[k] = true;
+                result.segments.push(Line::new(a, b));
+                result.source_segment.push(i);
+            }
+        }
+    }
+    result
+}
@@ -0,0 +1,113 @@
+use crate::builder::Builder;
+use crate::geometry::{Line, Point};
+use crate::snap_rounding::{round_div, snap_round};
+use crate::BvError;
+use boostvoronoi_ext::extended_int::ExtendedInt;
+
+#[test]
+fn round_div_1() {
+    let r = |n: i64, d: i64| round_div(ExtendedInt::from(n), ExtendedInt::from(d));
+    assert_eq!(r(10, 4), 3);
+    assert_eq!(r(9, 4), 2);
+    assert_eq!(r(-10, 4), -2);
+    assert_eq!(r(10, -4), -2);
+    assert_eq!(r(-9, 4), -2);
+    assert_eq!(r(-11, 4), -3);
+    assert_eq!(r(i64::MAX - 1, 2), i64::MAX / 2);
+    assert_eq!(r(i64::MAX, 3), i64::MAX / 3);
+    assert_eq!(r(i64::MAX - 1, 4), (i64::MAX / 4) + 1);
+}
+
+#[test]
+fn snap_round_1() {
+    // a 'T' junction and a crossing at a non integer coordinate
+    let s = [
+        Line::from([0_i32, 0, 10, 0]),
+        Line::from([5, 0, 5, 10]),
+        Line::from([0, 3, 9, 4]),
+    ];
+    let r = snap_round(s.iter());
+    assert_eq!(
+        r.segments,
+        vec![
+            Line::from([0, 0, 5, 0]),
+            Line::from([5, 0, 10, 0]),
+            Line::from([5, 0, 5, 4]),
+            Line::from([5, 4, 5, 10]),
+            Line::from([0, 3, 5, 4]),
+            Line::from([5, 4, 9, 4]),
+        ]
+    );
+    assert_eq!(r.source_segment, vec![0, 0, 1, 1, 2, 2]);
+}
+
+#[test]
+fn snap_round_2() {
+    // collinear overlapping segments collapse into shared fragments
+    let s = [Line::from([0_i32, 0, 10, 0]), Line::from([5, 0, 20, 0])];
+    let r = snap_round(s.iter());
+    assert_eq!(
+        r.segments,
+        vec![
+            Line::from([0, 0, 5, 0]),
+            Line::from([5, 0, 10, 0]),
+            Line::from([10, 0, 20, 0]),
+        ]
+    );
+    assert_eq!(r.source_segment, vec![0, 0, 1]);
+}
+
+#[test]
+fn snap_round_3() -> Result<(), BvError> {
+    // pseudo random segments, the output must always pass validation and build
+    let mut seed: u64 = 0x2545F4914F6CDD1D;
+    let mut rnd = || {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        ((seed >> 33) % 200) as i32 - 100
+    };
+    let s: Vec<Line<i32>> = (0..40)
+        .map(|_| Line::new(Point::new(rnd(), rnd()), Point::new(rnd(), rnd())))
+        .collect();
+    let r = snap_round(s.iter());
+    let builder = Builder::<i32, f64>::default().with_segments(r.segments.iter())?;
+    assert!(builder.validate().is_valid());
+    let _ = builder.build()?;
+    Ok(())
+}
+
+#[test]
+fn snap_round_4() -> Result<(), BvError> {
+    // segments touching the limits of the input type must not overflow
+    let (min, max) = (i32::MIN, i32::MAX);
+    let s = [
+        Line::from([min, min, max, max]),
+        Line::from([min, max, max, min]),
+        Line::from([min, 0, min, 10]),
+        Line::from([max, 0, max, 10]),
+    ];
+    let r = snap_round(s.iter());
+    // the diagonals cross at (-1/2,-1/2), rounded upwards to the origin
+    assert_eq!(
+        r.segments,
+        vec![
+            Line::from([min, min, 0, 0]),
+            Line::from([0, 0, max, max]),
+            Line::from([min, max, 0, 0]),
+            Line::from([0, 0, max, min]),
+            Line::from([min, 0, min, 10]),
+            Line::from([max, 0, max, 10]),
+        ]
+    );
+    assert_eq!(r.source_segment, vec![0, 0, 1, 1, 2, 3]);
+
+    let s = [
+        Line::from([i64::MIN, 0, i64::MAX, 0]),
+        Line::from([0, i64::MIN, 0, i64::MAX]),
+    ];
+    let r = snap_round(s.iter());
+    assert_eq!(r.segments.len(), 4);
+    assert_eq!(r.source_segment, vec![0, 0, 1, 1]);
+    Ok(())
+}
@@ -11,6 +11,7 @@
 //! See <https://www.boost.org/doc/libs/1_76_0/libs/polygon/doc/voronoi_diagram.htm> for diagram description.
 
 use crate::diagram as VD;
+use crate::validation as VV;
 use crate::BvError;
 pub use crate::{InputType, OutputType};
 #[cfg(feature = "serde")]
@@ -20,12 +21,17 @@ use serde::{Deserialize, Serialize};
 /// This is useful when traversing the diagram in a multi threaded environment.
 ///
 /// It also comes in an optional `serde` flavor.
+///
+/// `Builder::build_sync()` constructs this type directly, without going through a `Diagram`.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Default, Debug)]
 pub struct SyncDiagram<F: OutputType> {
-    cells_: Vec<VD::Cell>,         // indexed by CellIndex
-    vertices_: Vec<VD::Vertex<F>>, // indexed by VertexIndex
-    edges_: Vec<VD::Edge>,         // indexed by EdgeIndex
+    pub(crate) cells_: Vec<VD::Cell>,         // indexed by CellIndex
+    pub(crate) vertices_: Vec<VD::Vertex<F>>, // indexed by VertexIndex
+    pub(crate) edges_: Vec<VD::Edge>,         // indexed by EdgeIndex
+    // the source index ranges of the polygon rings and polylines
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) path_records_: Vec<VD::PathRecord>,
 }
 
 impl<F: OutputType> SyncDiagram<F> {
@@ -34,9 +40,18 @@ impl<F: OutputType> SyncDiagram<F> {
             cells_: cells,
             vertices_: vertices,
             edges_: edges,
+            path_records_: Vec::new(),
         }
     }
 
+    /// clear the list of cells, vertices and edges
+    pub fn clear(&mut self) {
+        self.cells_.clear();
+        self.vertices_.clear();
+        self.edges_.clear();
+        self.path_records_.clear();
+    }
+
     /// Returns a reference to the list of cells
     #[inline]
     pub fn cells(&self) -> &Vec<VD::Cell> {
@@ -157,6 +172,24 @@ impl<F: OutputType> SyncDiagram<F> {
             .ok_or_else(|| BvError::IdError(format!("The cell id {} does not exists", cell_id.0)))
     }
 
+    /// Returns the polygon ring, or polyline, element a cell was created from.
+    /// Returns `Ok(None)` if the cell was not created by `Builder::with_polygons()` or
+    /// `Builder::with_polylines()`.
+    pub fn cell_path_source(
+        &self,
+        cell_id: VD::CellIndex,
+    ) -> Result<Option<VD::PathSource>, BvError> {
+        Ok(VD::PathRecord::lookup(
+            &self.path_records_,
+            self.cell_get(cell_id)?,
+        ))
+    }
+
+    /// Checks the half-edge invariants of the diagram, see `Diagram::validate_topology()`.
+    pub fn validate_topology(&self) -> VV::TopologyReport {
+        VV::validate_topology(&self.cells_, &self.edges_, &self.vertices_)
+    }
+
     #[inline]
     /// Returns a reference to all of the vertices
     pub fn vertices(&self) -> &Vec<VD::Vertex<F>> {
@@ -171,6 +204,15 @@ impl<F: OutputType> SyncDiagram<F> {
         })
     }
 
+    /// Returns the cells around the vertex in counter clockwise order, i.e. the cells of the
+    /// sites defining the vertex. See `Diagram::vertex_cells()`.
+    pub fn vertex_cells(&self, vertex_id: VD::VertexIndex) -> Result<Vec<VD::CellIndex>, BvError> {
+        let incident_edge = self.vertex_get(vertex_id)?.get_incident_edge()?;
+        self.edge_rot_next_iterator(incident_edge)
+            .map(|edge_id| self.edge_get(edge_id)?.cell())
+            .collect()
+    }
+
     #[inline]
     /// Returns a mutable reference to a vertex
     pub fn vertex_get_mut(
@@ -183,6 +225,102 @@ impl<F: OutputType> SyncDiagram<F> {
     }
 }
 
+impl<F: OutputType> VD::DiagramOutput<F> for SyncDiagram<F> {
+    #[inline]
+    fn reserve_(&mut self, additional_sites: usize) {
+        self.cells_.reserve(additional_sites);
+        self.vertices_.reserve(additional_sites << 1);
+        self.edges_
+            .reserve((additional_sites << 2) + (additional_sites << 1));
+    }
+
+    #[inline]
+    fn clear_(&mut self) {
+        self.clear();
+    }
+
+    #[inline]
+    fn path_records_mut_(&mut self) -> &mut Vec<VD::PathRecord> {
+        &mut self.path_records_
+    }
+
+    #[inline]
+    fn num_cells_(&self) -> usize {
+        self.cells_.len()
+    }
+
+    #[inline]
+    fn num_vertices_(&self) -> usize {
+        self.vertices_.len()
+    }
+
+    #[inline]
+    fn num_edges_(&self) -> usize {
+        self.edges_.len()
+    }
+
+    #[inline]
+    fn push_cell_(&mut self, cell: VD::Cell) {
+        self.cells_.push(cell);
+    }
+
+    #[inline]
+    fn push_vertex_(&mut self, vertex: VD::Vertex<F>) {
+        self.vertices_.push(vertex);
+    }
+
+    #[inline]
+    fn push_edge_(&mut self, edge: VD::Edge) {
+        self.edges_.push(edge);
+    }
+
+    #[inline]
+    fn cell_(&self, cell_id: usize) -> Option<VD::Cell> {
+        self.cells_.get(cell_id).copied()
+    }
+
+    #[inline]
+    fn vertex_(&self, vertex_id: usize) -> Option<VD::Vertex<F>> {
+        self.vertices_.get(vertex_id).copied()
+    }
+
+    #[inline]
+    fn edge_(&self, edge_id: usize) -> Option<VD::Edge> {
+        self.edges_.get(edge_id).copied()
+    }
+
+    #[inline]
+    fn update_cell_(&mut self, cell_id: usize, f: impl FnOnce(&mut VD::Cell)) {
+        if let Some(cell) = self.cells_.get_mut(cell_id) {
+            f(cell);
+        }
+    }
+
+    #[inline]
+    fn update_vertex_(&mut self, vertex_id: usize, f: impl FnOnce(&mut VD::Vertex<F>)) {
+        if let Some(vertex) = self.vertices_.get_mut(vertex_id) {
+            f(vertex);
+        }
+    }
+
+    #[inline]
+    fn update_edge_(&mut self, edge_id: usize, f: impl FnOnce(&mut VD::Edge)) {
+        if let Some(edge) = self.edges_.get_mut(edge_id) {
+            f(edge);
+        }
+    }
+
+    #[inline]
+    fn truncate_vertices_(&mut self, len: usize) {
+        self.vertices_.truncate(len);
+    }
+
+    #[inline]
+    fn truncate_edges_(&mut self, len: usize) {
+        self.edges_.truncate(len);
+    }
+}
+
 /// Iterator over edges pointing away from the vertex indicated by the initial edge.
 pub struct EdgeRotNextIterator<'s, F: OutputType> {
     diagram_: &'s SyncDiagram<F>,
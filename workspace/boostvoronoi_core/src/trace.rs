@@ -0,0 +1,121 @@
+//          Copyright Eadf (github.com/eadf) 2021.
+// Distributed under the Boost Software License, Version 1.0.
+//    (See accompanying file LICENSE_1_0.txt or copy at
+//          http://www.boost.org/LICENSE_1_0.txt)
+
+//! Structured tracing of the sweep-line algorithm.
+//!
+//! A [`SweepObserver`] registered with [`crate::builder::Builder::with_observer()`] receives a
+//! [`SweepEvent`] for every step the algorithm takes. This can be used to animate or debug the
+//! algorithm, or to diff two runs against each other, without recompiling with the
+//! `console_debug` feature.
+//!
+//! Edge and vertex indices refer to the diagram under construction. Degenerate edges are removed
+//! when the diagram is finished (reported by [`SweepEvent::Finished`]), so the edge indices of the
+//! final diagram may differ.
+
+use crate::diagram::{EdgeIndex, SourceCategory, SourceIndex, VertexIndex};
+use crate::geometry::Point;
+use crate::site_event as VSE;
+use crate::{InputType, OutputType};
+
+/// A site event, as seen by a [`SweepObserver`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceSite<I: InputType> {
+    /// The index of the site among the sorted site events, this is also the index of its cell
+    pub sorted_index: usize,
+    /// The index of the input geometry the site was created from
+    pub source_index: SourceIndex,
+    /// The kind of input geometry the site was created from
+    pub source_category: SourceCategory,
+    /// The point of a point site, or the start point of a segment site
+    pub point0: Point<I>,
+    /// The end point of a segment site, same as `point0` for point sites
+    pub point1: Point<I>,
+    /// True if the site is a segment
+    pub is_segment: bool,
+    /// True if the site is a segment with reversed direction
+    pub is_inverse: bool,
+}
+
+impl<I: InputType, F: OutputType> From<&VSE::SiteEvent<I, F>> for TraceSite<I> {
+    fn from(site: &VSE::SiteEvent<I, F>) -> Self {
+        Self {
+            sorted_index: site.sorted_index(),
+            source_index: site.initial_index(),
+            source_category: site.source_category().into(),
+            point0: site.point0(),
+            point1: site.point1(),
+            is_segment: site.is_segment(),
+            is_inverse: site.is_inverse(),
+        }
+    }
+}
+
+/// The steps of the sweep-line algorithm.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SweepEvent<I: InputType, F: OutputType> {
+    /// A site event was taken from the site queue
+    SiteEventProcessed { site: TraceSite<I> },
+    /// Three consecutive arcs of the beach-line formed a circle event.
+    /// `lower_x` is the x coordinate where the sweep-line will reach the event.
+    CircleEventCreated {
+        id: usize,
+        x: F,
+        y: F,
+        lower_x: F,
+        sites: [TraceSite<I>; 3],
+    },
+    /// A circle event was invalidated by a change of the beach-line
+    CircleEventDeactivated { id: usize },
+    /// A circle event was taken from the circle event queue
+    CircleEventProcessed { id: usize, x: F, y: F, lower_x: F },
+    /// A bisector node was inserted into the beach-line
+    BeachLineNodeInserted {
+        node: usize,
+        left_site: TraceSite<I>,
+        right_site: TraceSite<I>,
+    },
+    /// The sites of a beach-line node were changed, this happens when a circle event merges
+    /// two bisectors into one
+    BeachLineNodeUpdated {
+        node: usize,
+        left_site: TraceSite<I>,
+        right_site: TraceSite<I>,
+    },
+    /// A bisector node was removed from the beach-line
+    BeachLineNodeRemoved {
+        node: usize,
+        left_site: TraceSite<I>,
+        right_site: TraceSite<I>,
+    },
+    /// A pair of twin half-edges was added to the diagram
+    EdgeCreated { edge: EdgeIndex, twin: EdgeIndex },
+    /// A vertex was added to the diagram
+    VertexCreated { vertex: VertexIndex, x: F, y: F },
+    /// All the events have been processed and the diagram is finished
+    Finished {
+        num_cells: usize,
+        num_edges: usize,
+        num_vertices: usize,
+    },
+}
+
+/// Receives the events of the sweep-line algorithm, see
+/// [`crate::builder::Builder::with_observer()`].
+/// Any `FnMut(&SweepEvent<I, F>)` closure is an observer.
+pub trait SweepObserver<I: InputType, F: OutputType> {
+    /// Called for every step of the algorithm
+    fn on_event(&mut self, event: &SweepEvent<I, F>);
+}
+
+impl<I, F, T> SweepObserver<I, F> for T
+where
+    I: InputType,
+    F: OutputType,
+    T: FnMut(&SweepEvent<I, F>),
+{
+    fn on_event(&mut self, event: &SweepEvent<I, F>) {
+        self(event)
+    }
+}
@@ -0,0 +1,419 @@
+//          Copyright Eadf (github.com/eadf) 2021.
+// Distributed under the Boost Software License, Version 1.0.
+//    (See accompanying file LICENSE_1_0.txt or copy at
+//          http://www.boost.org/LICENSE_1_0.txt)
+
+//! Pre-build validation of the input geometry, and post-build validation of the diagram topology.
+//!
+//! The sweep-line algorithm requires that input segments never intersect except at their
+//! endpoints, and that no input point lies inside a segment. Violating that contract does not
+//! always result in an error; sometimes the output diagram is just silently broken.
+//! [`crate::builder::Builder::validate()`] checks the contract, using exact integer predicates,
+//! and reports the offending input by source index.
+//!
+//! [`crate::diagram::Diagram::validate_topology()`] checks the half-edge invariants of a built
+//! diagram: twins, next/prev links around every cell, the edges around every vertex, the
+//! incident edges and the Euler characteristic.
+
+use crate::diagram::{
+    Cell, CellIndex, ColorBits, Edge, EdgeIndex, SourceIndex, Vertex, VertexIndex,
+};
+use crate::geometry::{Line, Point};
+use crate::predicate::orientation_predicate::{eval_p_exact, Orientation};
+use crate::predicate::point_comparison::point_comparison;
+use crate::{InputType, OutputType};
+
+#[cfg(test)]
+mod tests;
+
+/// The result of [`crate::builder::Builder::validate()`].
+/// All the pairs are sorted: the lowest source index comes first in each pair, and the lists are
+/// sorted and free of duplicates.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct ValidationReport {
+    /// Pairs of segments intersecting each other at a point that is not an endpoint of both
+    /// segments. I.e. proper crossings and 'T' junctions.
+    pub intersecting_segments: Vec<(SourceIndex, SourceIndex)>,
+    /// Pairs of collinear segments sharing more than one point.
+    pub overlapping_segments: Vec<(SourceIndex, SourceIndex)>,
+    /// Points located strictly inside a segment, given as (point index, segment index).
+    pub points_on_segments: Vec<(SourceIndex, SourceIndex)>,
+}
+
+impl ValidationReport {
+    /// Returns true if no problems were found
+    pub fn is_valid(&self) -> bool {
+        self.intersecting_segments.is_empty()
+            && self.overlapping_segments.is_empty()
+            && self.points_on_segments.is_empty()
+    }
+}
+
+/// The ways two segments can relate to each other.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub(crate) enum SegmentIntersection {
+    /// No common points, or only a shared endpoint.
+    None,
+    /// A single common point that is not an endpoint of both segments.
+    Intersecting,
+    /// Collinear and sharing more than one point.
+    Overlapping,
+}
+
+/// Returns true if `p` is located strictly between `a` and `b`. The points must be collinear.
+#[inline]
+fn strictly_between<I: InputType>(p: Point<I>, a: Point<I>, b: Point<I>) -> bool {
+    let (lo, hi) = if point_comparison(a, b) {
+        (a, b)
+    } else {
+        (b, a)
+    };
+    point_comparison(lo, p) && point_comparison(p, hi)
+}
+
+/// Returns true if `p` lies strictly inside the segment `l` (endpoints excluded).
+pub(crate) fn point_in_segment_interior<I: InputType>(p: Point<I>, l: &Line<I>) -> bool {
+    eval_p_exact(l.start, l.end, p) == Orientation::Collinear && strictly_between(p, l.start, l.end)
+}
+
+/// Exact classification of the intersection between two (non-degenerate) segments.
+pub(crate) fn segment_intersection<I: InputType>(a: &Line<I>, b: &Line<I>) -> SegmentIntersection {
+    let o1 = eval_p_exact(a.start, a.end, b.start);
+    let o2 = eval_p_exact(a.start, a.end, b.end);
+
+    if o1 == Orientation::Collinear && o2 == Orientation::Collinear {
+        // Collinear segments, compare the lexicographic extents
+        let (a_lo, a_hi) = if point_comparison(a.start, a.end) {
+            (a.start, a.end)
+        } else {
+            (a.end, a.start)
+        };
+        let (b_lo, b_hi) = if point_comparison(b.start, b.end) {
+            (b.start, b.end)
+        } else {
+            (b.end, b.start)
+        };
+        let lo = if point_comparison(a_lo, b_lo) {
+            b_lo
+        } else {
+            a_lo
+        };
+        let hi = if point_comparison(a_hi, b_hi) {
+            a_hi
+        } else {
+            b_hi
+        };
+        return if point_comparison(lo, hi) {
+            SegmentIntersection::Overlapping
+        } else {
+            SegmentIntersection::None
+        };
+    }
+    let o3 = eval_p_exact(b.start, b.end, a.start);
+    let o4 = eval_p_exact(b.start, b.end, a.end);
+
+    let straddles = |x: Orientation, y: Orientation| {
+        x == Orientation::Collinear || y == Orientation::Collinear || x != y
+    };
+    if !(straddles(o1, o2) && straddles(o3, o4)) {
+        return SegmentIntersection::None;
+    }
+    // The segments share exactly one point. That is only allowed if it is a shared endpoint.
+    if o1 != Orientation::Collinear
+        && o2 != Orientation::Collinear
+        && o3 != Orientation::Collinear
+        && o4 != Orientation::Collinear
+    {
+        return SegmentIntersection::Intersecting;
+    }
+    if (o1 == Orientation::Collinear && strictly_between(b.start, a.start, a.end))
+        || (o2 == Orientation::Collinear && strictly_between(b.end, a.start, a.end))
+        || (o3 == Orientation::Collinear && strictly_between(a.start, b.start, b.end))
+        || (o4 == Orientation::Collinear && strictly_between(a.end, b.start, b.end))
+    {
+        SegmentIntersection::Intersecting
+    } else {
+        SegmentIntersection::None
+    }
+}
+
+/// Checks the input points and segments against each other.
+/// The input is swept in x order and every item is only tested against the segments whose
+/// x-extent overlaps it, so the cost is low for typical input.
+/// Degenerate (zero length) segments should be given as points.
+pub(crate) fn validate<I: InputType>(
+    points: &[(SourceIndex, Point<I>)],
+    segments: &[(SourceIndex, Line<I>)],
+) -> ValidationReport {
+    #[derive(Clone, Copy)]
+    enum Item<I: InputType> {
+        Segment(SourceIndex, Line<I>),
+        Point(SourceIndex, Point<I>),
+    }
+    let min_x = |item: &Item<I>| match item {
+        Item::Segment(_, l) => l.start.x.min(l.end.x),
+        Item::Point(_, p) => p.x,
+    };
+
+    let mut items: Vec<Item<I>> = segments
+        .iter()
+        .map(|(i, l)| Item::Segment(*i, *l))
+        .chain(points.iter().map(|(i, p)| Item::Point(*i, *p)))
+        .collect();
+    // Segments goes before points with the same min x, so points at that x are tested against them
+    items.sort_by(|a, b| {
+        min_x(a)
+            .cmp(&min_x(b))
+            .then_with(|| matches!(a, Item::Point(..)).cmp(&matches!(b, Item::Point(..))))
+    });
+
+    let mut report = ValidationReport::default();
+    let mut active: Vec<(SourceIndex, Line<I>)> = Vec::new();
+    for item in items {
+        let x = min_x(&item);
+        active.retain(|(_, l)| l.start.x.max(l.end.x) >= x);
+        match item {
+            Item::Point(pi, p) => {
+                for (si, l) in active.iter() {
+                    if point_in_segment_interior(p, l) {
+                        report.points_on_segments.push((pi, *si));
+                    }
+                }
+            }
+            Item::Segment(si, l) => {
+                for (ai, al) in active.iter() {
+                    let pair = (*ai.min(&si), *ai.max(&si));
+                    match segment_intersection(al, &l) {
+                        SegmentIntersection::None => (),
+                        SegmentIntersection::Intersecting => {
+                            report.intersecting_segments.push(pair)
+                        }
+                        SegmentIntersection::Overlapping => report.overlapping_segments.push(pair),
+                    }
+                }
+                active.push((si, l));
+            }
+        }
+    }
+    report.intersecting_segments.sort_unstable();
+    report.intersecting_segments.dedup();
+    report.overlapping_segments.sort_unstable();
+    report.overlapping_segments.dedup();
+    report.points_on_segments.sort_unstable();
+    report.points_on_segments.dedup();
+    report
+}
+
+/// A broken half-edge invariant, found by `Diagram::validate_topology()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TopologyViolation {
+    /// The element is not stored at the position of its own id
+    MisplacedId(String),
+    /// The element refers to an id that does not exist, or a required link is missing
+    DanglingReference(String),
+    /// The twin of the edge is missing, is the edge itself, or does not point back
+    TwinMismatch(EdgeIndex),
+    /// `prev(next(edge))` or `next(prev(edge))` is not the edge
+    NextPrevMismatch(EdgeIndex),
+    /// The next edge belongs to another cell
+    NextCellMismatch(EdgeIndex),
+    /// The next edge does not start where the edge ends
+    NextVertexMismatch(EdgeIndex),
+    /// The incident edge of the cell belongs to another cell
+    CellIncidentEdge(CellIndex),
+    /// The next links from the incident edge never return to it
+    CellNotClosed(CellIndex),
+    /// The incident edge of the vertex starts somewhere else
+    VertexIncidentEdge(VertexIndex),
+    /// The rot_next links from the incident edge never return to it, or leave the vertex
+    VertexNotClosed(VertexIndex),
+    /// The cell is a placeholder that was never replaced by the builder
+    TemporaryCell(CellIndex),
+    /// `V - E + F` is not 2, counting infinity as one vertex and the edge pairs as edges
+    EulerCharacteristic {
+        vertices: usize,
+        edges: usize,
+        faces: usize,
+    },
+}
+
+/// The result of `Diagram::validate_topology()` and `SyncDiagram::validate_topology()`.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct TopologyReport {
+    /// The violations, in the order they were found
+    pub violations: Vec<TopologyViolation>,
+}
+
+impl TopologyReport {
+    /// Returns true if no problems were found
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+/// Checks the half-edge invariants of the diagram elements, indexed by their ids.
+pub(crate) fn validate_topology<F: OutputType>(
+    cells: &[Cell],
+    edges: &[Edge],
+    vertices: &[Vertex<F>],
+) -> TopologyReport {
+    use TopologyViolation as TV;
+    let mut violations = Vec::new();
+    let edge = |e: Option<EdgeIndex>| e.and_then(|e| edges.get(e.0));
+
+    for (i, cell) in cells.iter().enumerate() {
+        if cell.id_.0 == usize::MAX
+            || cell.source_index_ == usize::MAX
+            || cell.color_ == ColorBits::TEMPORARY_CELL.0
+        {
+            violations.push(TV::TemporaryCell(CellIndex(i)));
+            continue;
+        }
+        if cell.id_.0 != i {
+            violations.push(TV::MisplacedId(format!(
+                "cell {} has the id {}",
+                i, cell.id_.0
+            )));
+        }
+        let incident_edge = match cell.incident_edge_ {
+            // degenerate cells have no edges
+            None => continue,
+            Some(e) => e,
+        };
+        match edges.get(incident_edge.0) {
+            None => violations.push(TV::DanglingReference(format!(
+                "cell {} has the incident edge {}",
+                i, incident_edge.0
+            ))),
+            Some(e) if e.cell_ != Some(CellIndex(i)) => {
+                violations.push(TV::CellIncidentEdge(CellIndex(i)))
+            }
+            Some(_) => {
+                // walk the next links, every edge can be visited at most once
+                let mut current = incident_edge;
+                let mut closed = false;
+                for _ in 0..edges.len() {
+                    match edge(Some(current)).and_then(|e| e.next_ccw_) {
+                        Some(next) if next == incident_edge => {
+                            closed = true;
+                            break;
+                        }
+                        Some(next) => current = next,
+                        None => break,
+                    }
+                }
+                if !closed {
+                    violations.push(TV::CellNotClosed(CellIndex(i)));
+                }
+            }
+        }
+    }
+
+    for (i, e) in edges.iter().enumerate() {
+        let edge_id = EdgeIndex(i);
+        if e.id_ != edge_id {
+            violations.push(TV::MisplacedId(format!(
+                "edge {} has the id {}",
+                i, e.id_.0
+            )));
+        }
+        if e.cell_.is_none_or(|c| c.0 >= cells.len()) {
+            violations.push(TV::DanglingReference(format!(
+                "edge {} has the cell {:?}",
+                i, e.cell_
+            )));
+        }
+        if e.vertex_.is_some_and(|v| v.0 >= vertices.len()) {
+            violations.push(TV::DanglingReference(format!(
+                "edge {} has the vertex {:?}",
+                i, e.vertex_
+            )));
+        }
+        let twin = edge(e.twin_);
+        if e.twin_ == Some(edge_id) || !twin.is_some_and(|t| t.twin_ == Some(edge_id)) {
+            violations.push(TV::TwinMismatch(edge_id));
+        }
+        let (next, prev) = (edge(e.next_ccw_), edge(e.prev_ccw_));
+        let (next, prev) = match (next, prev) {
+            (Some(next), Some(prev)) => (next, prev),
+            _ => {
+                violations.push(TV::DanglingReference(format!(
+                    "edge {} has the next edge {:?} and the previous edge {:?}",
+                    i, e.next_ccw_, e.prev_ccw_
+                )));
+                continue;
+            }
+        };
+        if next.prev_ccw_ != Some(edge_id) || prev.next_ccw_ != Some(edge_id) {
+            violations.push(TV::NextPrevMismatch(edge_id));
+        }
+        if next.cell_ != e.cell_ {
+            violations.push(TV::NextCellMismatch(edge_id));
+        }
+        // the end of the edge is the start of its twin
+        if let Some(twin) = twin {
+            if next.vertex_ != twin.vertex_ {
+                violations.push(TV::NextVertexMismatch(edge_id));
+            }
+        }
+    }
+
+    for (i, vertex) in vertices.iter().enumerate() {
+        let vertex_id = VertexIndex(i);
+        if vertex.id_ != vertex_id {
+            violations.push(TV::MisplacedId(format!(
+                "vertex {} has the id {}",
+                i, vertex.id_.0
+            )));
+        }
+        let incident_edge = match vertex.incident_edge_ {
+            Some(e) if e.0 < edges.len() => e,
+            e => {
+                violations.push(TV::DanglingReference(format!(
+                    "vertex {} has the incident edge {:?}",
+                    i, e
+                )));
+                continue;
+            }
+        };
+        if edges[incident_edge.0].vertex_ != Some(vertex_id) {
+            violations.push(TV::VertexIncidentEdge(vertex_id));
+            continue;
+        }
+        // rot_next(e) = next(twin(e)), walk around the vertex
+        let mut current = incident_edge;
+        let mut closed = false;
+        for _ in 0..edges.len() {
+            match edge(edge(Some(current)).and_then(|e| e.twin_)).and_then(|t| t.next_ccw_) {
+                Some(next) if edge(Some(next)).is_none_or(|e| e.vertex_ != Some(vertex_id)) => {
+                    break
+                }
+                Some(next) if next == incident_edge => {
+                    closed = true;
+                    break;
+                }
+                Some(next) => current = next,
+                None => break,
+            }
+        }
+        if !closed {
+            violations.push(TV::VertexNotClosed(vertex_id));
+        }
+    }
+
+    // with all the infinite edges meeting in one vertex at infinity the diagram is a connected
+    // planar graph, where the cells are the faces
+    if !edges.is_empty() {
+        let infinity = usize::from(edges.iter().any(|e| e.vertex_.is_none()));
+        let faces = cells.iter().filter(|c| c.incident_edge_.is_some()).count();
+        let (v, e) = (vertices.len() + infinity, edges.len() / 2);
+        if v + faces != e + 2 {
+            violations.push(TV::EulerCharacteristic {
+                vertices: v,
+                edges: e,
+                faces,
+            });
+        }
+    }
+    TopologyReport { violations }
+}
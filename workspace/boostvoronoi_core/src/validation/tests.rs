@@ -0,0 +1,209 @@
+use crate::builder::Builder;
+use crate::diagram::{Cell, CellIndex, ColorBits, EdgeIndex, VertexIndex};
+use crate::geometry::{Line, Point};
+use crate::sync_diagram::SyncDiagram;
+use crate::validation::{segment_intersection, SegmentIntersection, TopologyViolation};
+use crate::BvError;
+
+fn line(x1: i32, y1: i32, x2: i32, y2: i32) -> Line<i32> {
+    Line::from([x1, y1, x2, y2])
+}
+
+#[test]
+fn segment_intersection_1() {
+    // proper crossing
+    assert_eq!(
+        segment_intersection(&line(0, 0, 10, 10), &line(0, 10, 10, 0)),
+        SegmentIntersection::Intersecting
+    );
+    // shared endpoint
+    assert_eq!(
+        segment_intersection(&line(0, 0, 10, 10), &line(10, 10, 20, 0)),
+        SegmentIntersection::None
+    );
+    // 'T' junction
+    assert_eq!(
+        segment_intersection(&line(0, 0, 10, 0), &line(5, 0, 5, 10)),
+        SegmentIntersection::Intersecting
+    );
+    // collinear, touching at an endpoint
+    assert_eq!(
+        segment_intersection(&line(0, 0, 10, 0), &line(10, 0, 20, 0)),
+        SegmentIntersection::None
+    );
+    // collinear, overlapping
+    assert_eq!(
+        segment_intersection(&line(0, 0, 10, 0), &line(20, 0, 5, 0)),
+        SegmentIntersection::Overlapping
+    );
+    // collinear, disjoint
+    assert_eq!(
+        segment_intersection(&line(0, 0, 10, 10), &line(11, 11, 20, 20)),
+        SegmentIntersection::None
+    );
+}
+
+#[test]
+fn segment_intersection_2() {
+    // these coordinates would overflow an i64 cross product
+    let big = i64::MAX - 1;
+    let a = Line::from([-big, -big, big, big]);
+    let b = Line::from([-big, big, big, -big]);
+    let c = Line::from([-big, -big + 1, big, big]);
+    assert_eq!(
+        segment_intersection(&a, &b),
+        SegmentIntersection::Intersecting
+    );
+    assert_eq!(segment_intersection(&a, &c), SegmentIntersection::None);
+}
+
+#[test]
+fn validate_1() -> Result<(), BvError> {
+    let points = [Point::new(5, 5), Point::new(100, 100), Point::new(20, 0)];
+    let segments = [
+        line(0, 0, 10, 10),
+        line(0, 10, 10, 0),
+        line(10, 10, 20, 0),
+        line(20, 0, 40, 0),
+        line(30, 0, 50, 0),
+        line(25, 0, 25, 10),
+    ];
+    let report = Builder::<i32, f64>::default()
+        .with_vertices(points.iter())?
+        .with_segments(segments.iter())?
+        .validate();
+    assert!(!report.is_valid());
+    assert_eq!(report.intersecting_segments, vec![(3, 4), (6, 8)]);
+    assert_eq!(report.overlapping_segments, vec![(6, 7)]);
+    assert_eq!(report.points_on_segments, vec![(0, 3), (0, 4)]);
+    Ok(())
+}
+
+#[test]
+fn validate_2() -> Result<(), BvError> {
+    let points = [Point::new(5, 6)];
+    let segments = [
+        line(0, 0, 10, 10),
+        line(10, 10, 20, 0),
+        line(30, 30, 30, 30),
+    ];
+    let report = Builder::<i32, f64>::default()
+        .with_vertices(points.iter())?
+        .with_segments(segments.iter())?
+        .validate();
+    assert!(report.is_valid());
+    Ok(())
+}
+
+#[test]
+fn validate_3() -> Result<(), BvError> {
+    // a zero length segment is a point site, it is validated like one
+    let segments = [line(0, 0, 40, 40), line(20, 20, 20, 20)];
+    let report = Builder::<i32, f64>::default()
+        .with_segments(segments.iter())?
+        .validate();
+    assert_eq!(report.points_on_segments, vec![(1, 0)]);
+
+    let points = [Point::new(20, 20)];
+    let report = Builder::<i32, f64>::default()
+        .with_vertices(points.iter())?
+        .with_segments(segments[..1].iter())?
+        .validate();
+    assert_eq!(report.points_on_segments, vec![(0, 1)]);
+    Ok(())
+}
+
+fn sync_diagram() -> Result<SyncDiagram<f64>, BvError> {
+    let points = [Point::new(5, 6), Point::new(-3, 12), Point::new(18, 20)];
+    let segments = [line(0, 0, 10, 10), line(10, 10, 20, 0)];
+    Builder::<i32, f64>::default()
+        .with_vertices(points.iter())?
+        .with_segments(segments.iter())?
+        .build_sync()
+}
+
+#[test]
+fn validate_topology_1() -> Result<(), BvError> {
+    let diagram = sync_diagram()?;
+    assert!(diagram.validate_topology().is_valid());
+
+    // a twin that does not point back
+    let mut broken = sync_diagram()?;
+    broken.edges_[0].twin_ = Some(EdgeIndex(2));
+    let report = broken.validate_topology();
+    assert!(report
+        .violations
+        .contains(&TopologyViolation::TwinMismatch(EdgeIndex(0))));
+
+    // a next link into another cell
+    let mut broken = sync_diagram()?;
+    let other = broken.edges_[0].twin_.unwrap();
+    broken.edges_[0].next_ccw_ = Some(other);
+    let report = broken.validate_topology();
+    assert!(report
+        .violations
+        .contains(&TopologyViolation::NextPrevMismatch(EdgeIndex(0))));
+    assert!(report
+        .violations
+        .contains(&TopologyViolation::NextCellMismatch(EdgeIndex(0))));
+    Ok(())
+}
+
+#[test]
+fn validate_topology_2() -> Result<(), BvError> {
+    let diagram = sync_diagram()?;
+
+    // a placeholder cell left by the builder
+    let mut broken = sync_diagram()?;
+    broken.cells_.push(Cell::new(
+        CellIndex(usize::MAX),
+        usize::MAX,
+        ColorBits::TEMPORARY_CELL.0,
+    ));
+    let report = broken.validate_topology();
+    assert_eq!(
+        report.violations,
+        vec![TopologyViolation::TemporaryCell(CellIndex(
+            diagram.cells_.len()
+        ))]
+    );
+
+    // an incident edge that starts at another vertex
+    let mut broken = sync_diagram()?;
+    let incident_edge = broken.vertices_[1].incident_edge_;
+    broken.vertices_[0].incident_edge_ = incident_edge;
+    let report = broken.validate_topology();
+    assert_eq!(
+        report.violations,
+        vec![TopologyViolation::VertexIncidentEdge(VertexIndex(0))]
+    );
+
+    // a missing vertex breaks the Euler characteristic
+    let mut broken = sync_diagram()?;
+    let _ = broken.vertices_.pop();
+    let report = broken.validate_topology();
+    assert!(report
+        .violations
+        .iter()
+        .any(|v| matches!(v, TopologyViolation::EulerCharacteristic { .. })));
+    Ok(())
+}
+
+#[test]
+fn validate_topology_3() -> Result<(), BvError> {
+    // a next link to an edge that does not exist must be reported, not panic
+    let mut broken = sync_diagram()?;
+    let twin = broken.edges_[broken.vertices_[0].incident_edge_.unwrap().0]
+        .twin_
+        .unwrap();
+    broken.edges_[twin.0].next_ccw_ = Some(EdgeIndex(999_999));
+    let report = broken.validate_topology();
+    assert!(report
+        .violations
+        .contains(&TopologyViolation::VertexNotClosed(VertexIndex(0))));
+    assert!(report
+        .violations
+        .iter()
+        .any(|v| matches!(v, TopologyViolation::DanglingReference(_))));
+    Ok(())
+}
@@ -431,6 +431,16 @@ impl<F: OutputType> SimpleAffine<F> {
         )
     }
 
+    /// transform from destination coordinate system to source coordinate system
+    /// without rounding to the input type
+    #[inline(always)]
+    pub fn reverse_transform_f(&self, point: [F; 2]) -> [F; 2] {
+        [
+            (point[0] - self.to_offset[0]) / self.scale[0] - self.to_center_[0],
+            (point[1] - self.to_offset[1]) / self.scale[1] - self.to_center_[1],
+        ]
+    }
+
     /// transform from source coordinate system to dest coordinate system
     #[inline(always)]
     pub fn transform(&self, x: F, y: F) -> [F; 2] {
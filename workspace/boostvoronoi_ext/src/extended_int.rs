@@ -59,7 +59,7 @@ impl<I: InputType> From<I> for ExtendedInt {
                 }
             }
             cmp::Ordering::Less => {
-                let mut c: u64 = (-that) as u64;
+                let mut c: u64 = that.unsigned_abs();
                 rv.chunks_.push(Wrapping((c & 0xFFFFFFFF) as u32));
                 c >>= 32;
                 if c != 0 {
@@ -105,11 +105,11 @@ impl ExtendedInt {
         match self.size() {
             0 => return rv,
             1 => {
-                rv.0 = self.chunks_.get(0).unwrap().0.to_f64().unwrap();
+                rv.0 = self.chunks_.first().unwrap().0.to_f64().unwrap();
             }
             2 => {
                 rv.0 = self.chunks_.get(1).unwrap().0.to_f64().unwrap() * sep
-                    + self.chunks_.get(0).unwrap().0.to_f64().unwrap();
+                    + self.chunks_.first().unwrap().0.to_f64().unwrap();
             }
             _ => {
                 for v in self.chunks_.iter().rev().take(3) {
@@ -290,9 +290,11 @@ impl ExtendedInt {
                 self.chunks_.push(Wrapping(0));
             }
         }
-        if (self.count_ as usize) < self.chunks_.len() {
-            let _ = self.chunks_.pop();
+        // The borrow may have cleared more than one of the top chunks, e.g. (2^64+1)-(2^64-4)
+        while self.count_ > 0 && self.chunks_[(self.count_ - 1) as usize].0 == 0 {
+            self.count_ -= 1;
         }
+        self.chunks_.truncate(self.count_ as usize);
     }
 
     fn mul_other(&mut self, e1: &Self, e2: &Self) {
@@ -369,7 +371,7 @@ impl ops::Add for ExtendedInt {
     }
 }
 
-impl<'a, 'b> ops::Add<&'b ExtendedInt> for &'a ExtendedInt {
+impl<'b> ops::Add<&'b ExtendedInt> for &ExtendedInt {
     type Output = ExtendedInt;
     /// Adds `self` to `that` returning a new object containing the result
     /// ```
@@ -429,7 +431,7 @@ impl ops::Sub for ExtendedInt {
     }
 }
 
-impl<'a, 'b> ops::Sub<&'b ExtendedInt> for &'a ExtendedInt {
+impl<'b> ops::Sub<&'b ExtendedInt> for &ExtendedInt {
     type Output = ExtendedInt;
     /// Subtracts `that` from `self` returning a new object containing the result
     /// ```
@@ -489,7 +491,7 @@ impl ops::Mul for ExtendedInt {
     }
 }
 
-impl<'a, 'b> ops::Mul<&'b ExtendedInt> for &'a ExtendedInt {
+impl<'b> ops::Mul<&'b ExtendedInt> for &ExtendedInt {
     type Output = ExtendedInt;
     /// Multiplies `self` with `that` returning a new object containing the result
     /// ```
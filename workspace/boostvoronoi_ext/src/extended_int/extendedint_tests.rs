@@ -55,3 +55,16 @@ fn extended_int_test_2() {
     println!("r:{:?} d():{}", r, r.d());
     approx::assert_ulps_eq!(r.d(), aa * bb);
 }
+
+#[test]
+fn extended_int_test_3() {
+    // the borrow clears two chunks, the result must still compare correctly
+    use crate::extended_int::ExtendedInt;
+    let a = ExtendedInt::from(i64::MAX) * 2 + &ExtendedInt::from(3_i32);
+    let b = ExtendedInt::from(3074457345618258602_i64) * 6;
+    let r = a - &b;
+    approx::assert_ulps_eq!(r.d(), 5.0);
+    let r = r - &ExtendedInt::from(6_i32);
+    assert!(r.is_neg());
+    approx::assert_ulps_eq!(r.d(), -1.0);
+}
@@ -4,6 +4,16 @@ pub mod extended_exp_fpt;
 pub mod extended_int;
 pub mod robust_fpt;
 
+/// A feature gated println(), only defined when the feature "console_debug" is selected.
+#[cfg(feature = "console_debug")]
+macro_rules! tln {
+    ($($arg:tt)*) => ({
+     println!($($arg)*)
+    });
+}
+#[cfg(feature = "console_debug")]
+pub(crate) use tln;
+
 use num_traits::NumCast;
 
 #[inline(always)]
@@ -16,6 +16,8 @@ mod robustdif_tests;
 #[cfg(test)]
 mod robustfpt_tests;
 
+#[cfg(feature = "console_debug")]
+use crate::tln;
 use num_traits::Zero;
 use ordered_float::OrderedFloat;
 use std::fmt;